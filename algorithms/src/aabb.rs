@@ -54,6 +54,10 @@ impl FastBoundingRect for PathEvent {
                 *min = Point::min(*min, Point::min(segment.ctrl1, Point::min(segment.ctrl2, segment.to)));
                 *max = Point::max(*max, Point::max(segment.ctrl1, Point::max(segment.ctrl2, segment.to)));
             }
+            PathEvent::Conic(ref segment) => {
+                *min = Point::min(*min, Point::min(segment.ctrl, segment.to));
+                *max = Point::max(*max, Point::max(segment.ctrl, segment.to));
+            }
             PathEvent::Close(..) => {}
         }
     }
@@ -145,6 +149,11 @@ impl TightBoundingRect for PathEvent {
                 *min = Point::min(*min, r.min());
                 *max = Point::max(*max, r.max());
             }
+            PathEvent::Conic(ref segment) => {
+                let r = segment.bounding_rect();
+                *min = Point::min(*min, r.min());
+                *max = Point::max(*max, r.max());
+            }
             PathEvent::Close(..) => {}
         }
     }
@@ -23,6 +23,10 @@
 //! );
 //! let hatched_path = path_builder.build();
 //! ```
+//!
+//! Several layers of hatches at different angles can be generated in one call with
+//! `Hatcher::cross_hatch_path`, and `Hatcher::contour_path` generates lines that follow
+//! the outline of the path at increasing distances (concentric hatching).
 
 
 use crate::path::PathEvent;
@@ -34,6 +38,7 @@ use std::marker::PhantomData;
 
 use std::cmp::Ordering;
 use std::mem;
+use std::ops::Range;
 use std::f32;
 
 /// Parameters for the hatcher.
@@ -208,6 +213,17 @@ pub trait HatchBuilder {
     fn add_segment(&mut self, segment: &HatchSegment);
     /// Specifies the distance between each row of the pattern.
     fn next_offset(&mut self, row_idx: u32) -> f32;
+
+    /// Specifies the distance between each row of the pattern, knowing the position
+    /// of the current row.
+    ///
+    /// `v` is the rotated position of the row that was just generated, relative to
+    /// `uv_origin` (see `HatchSegment::v`). This is the method invoked by the hatcher.
+    /// The default implementation ignores the position and calls `next_offset`.
+    fn next_offset_at(&mut self, row_idx: u32, _v: f32) -> f32 { self.next_offset(row_idx) }
+
+    /// Called before each layer of hatches generated by `Hatcher::cross_hatch_path`.
+    fn begin_pass(&mut self, _pass: u32, _angle: Angle<f32>) {}
 }
 
 pub struct Dot {
@@ -250,9 +266,35 @@ pub struct RegularDotPattern<Cb: FnMut(&Dot)> {
     pub callback: Cb,
 }
 
+/// A contour line generated by `Hatcher::contour_path`.
+pub struct Contour<'l> {
+    /// The points of the polyline.
+    pub points: &'l [Point],
+    /// Whether the last point connects back to the first one.
+    pub is_closed: bool,
+    /// Index of the current level, zero being the closest to the outline.
+    pub level: u32,
+    /// Distance between the contour and the outline of the path.
+    pub offset: f32,
+}
+
+/// The output of `Hatcher::contour_path`.
+///
+/// Implement this trait to create custom contour patterns.
+pub trait ContourBuilder {
+    /// Called for each contour polyline.
+    fn add_contour(&mut self, contour: &Contour);
+    /// Specifies the distance between a level and the previous one (or the
+    /// outline of the path for the first level).
+    fn next_offset(&mut self, level: u32) -> f32;
+}
+
 /// A context object that can fill a path with a hatching or dot pattern.
 pub struct Hatcher {
     events: HatchingEvents,
+    contour_events: ContourEvents,
+    contour_samples: Vec<(Point, bool)>,
+    contour_runs: Vec<Vec<Point>>,
     active_edges: Vec<Edge>,
     transform: Rotation2D<f32>,
     compute_tangents: bool,
@@ -265,6 +307,9 @@ impl Hatcher {
     pub fn new() -> Self {
         Hatcher {
             events: HatchingEvents::new(),
+            contour_events: ContourEvents::new(),
+            contour_samples: Vec::new(),
+            contour_runs: Vec::new(),
             active_edges: Vec::new(),
             transform: Rotation2D::identity(),
             compute_tangents: true,
@@ -304,6 +349,65 @@ impl Hatcher {
         self.events = events;
     }
 
+    /// Generate several layers of hatches for a path, one for each angle.
+    ///
+    /// This is equivalent to calling `hatch_path` once per angle, but the path is
+    /// only flattened once. The angle specified in the options is ignored and
+    /// `HatchBuilder::begin_pass` is invoked before each layer.
+    pub fn cross_hatch_path<Iter>(
+        &mut self,
+        it: Iter,
+        options: &HatchingOptions,
+        angles: &[Angle<f32>],
+        output: &mut dyn HatchBuilder,
+    )
+    where
+        Iter: Iterator<Item = PathEvent>,
+    {
+        let mut events = mem::replace(&mut self.events, HatchingEvents::new());
+        events.set_path(options.tolerance, Angle::radians(0.0), it);
+
+        let mut rotated = HatchingEvents::new();
+        for (pass, angle) in angles.iter().enumerate() {
+            rotated.set_rotated(&events, *angle);
+            output.begin_pass(pass as u32, *angle);
+            self.hatch(&rotated, &options.with_angle(*angle), output);
+        }
+
+        self.events = events;
+    }
+
+    /// Generate contour lines that follow the outline of the path, on the inside.
+    ///
+    /// The contours are the sets of points of the shape that are at a certain distance
+    /// of its outline. They are generated level by level at the distances specified by
+    /// the `ContourBuilder`, until the shape is entirely covered.
+    ///
+    /// The path is filled using the even-odd fill rule, and only the tolerance of the
+    /// options is used.
+    pub fn contour_path<Iter>(
+        &mut self,
+        it: Iter,
+        options: &HatchingOptions,
+        output: &mut dyn ContourBuilder,
+    )
+    where
+        Iter: Iterator<Item = PathEvent>,
+    {
+        let mut contour_events = mem::replace(&mut self.contour_events, ContourEvents::new());
+        contour_events.set_path(options.tolerance, it);
+
+        // The inside and distance queries run on the same sorted edges as the hatching
+        // scanlines, without rotation.
+        let mut events = mem::replace(&mut self.events, HatchingEvents::new());
+        events.set_polygons(&contour_events);
+
+        self.contour(&contour_events, &events, options.tolerance, output);
+
+        self.contour_events = contour_events;
+        self.events = events;
+    }
+
     /// Generate dots for a path.
     pub fn dot_path<Iter>(
         &mut self,
@@ -338,14 +442,19 @@ impl Hatcher {
         self.segment.b.tangent = vector(f32::NAN, f32::NAN);
         self.compute_tangents = options.compute_tangents;
 
-        let mut y = events.edges.first().unwrap().from.y + output.next_offset(0);
+        let y_start = match events.edges.first() {
+            Some(edge) => edge.from.y,
+            None => { return; }
+        };
+
+        let mut y = y_start + output.next_offset_at(0, y_start - self.uv_origin.y);
         let mut y_max = y;
 
         for edge in &events.edges {
             let y2 = edge.from.y;
             while y < y2 {
                 self.hatch_line(y, output);
-                let offset = output.next_offset(self.segment.row);
+                let offset = output.next_offset_at(self.segment.row, y - self.uv_origin.y);
                 y += offset;
                 if offset <= 0.0 {
                     return;
@@ -357,7 +466,7 @@ impl Hatcher {
 
         while y < y_max {
             self.hatch_line(y, output);
-            let offset = output.next_offset(self.segment.row);
+            let offset = output.next_offset_at(self.segment.row, y - self.uv_origin.y);
             y += offset;
             if offset <= 0.0 {
                 return;
@@ -365,6 +474,157 @@ impl Hatcher {
         }
     }
 
+    fn contour(
+        &mut self,
+        polygons: &ContourEvents,
+        events: &HatchingEvents,
+        tolerance: f32,
+        output: &mut dyn ContourBuilder,
+    ) {
+        let mut offset = 0.0;
+        let mut level = 0;
+        let mut raw_offset = Vec::new();
+        let mut offset_edges = Vec::new();
+        loop {
+            let step = output.next_offset(level);
+            if step.is_nan() || step <= 0.0 {
+                return;
+            }
+            offset += step;
+
+            let mut found_contour = false;
+            for range in &polygons.sub_paths {
+                let polygon = &polygons.points[range.clone()];
+                // Contours on the outside of the polygon are discarded, it's simpler to generate
+                // both sides than to figure out the orientation of each sub-path.
+                for &side in &[1.0, -1.0] {
+                    offset_polygon(polygon, offset * side, tolerance, &mut offset_edges, &mut raw_offset);
+                    self.sample_contour(events, &raw_offset, offset, tolerance);
+
+                    for run in &self.contour_runs {
+                        if run.len() < 2 {
+                            continue;
+                        }
+                        let is_closed = self.contour_runs.len() == 1
+                            && (run[0] - run[run.len() - 1]).square_length() <= tolerance * tolerance;
+                        let points = if is_closed { &run[..run.len() - 1] } else { &run[..] };
+                        output.add_contour(&Contour {
+                            points,
+                            is_closed,
+                            level,
+                            offset,
+                        });
+                        found_contour = true;
+                    }
+                }
+            }
+
+            if !found_contour {
+                return;
+            }
+
+            level += 1;
+        }
+    }
+
+    // Split a raw offset curve into the parts that are at the right distance from the
+    // outline (the "runs"), discarding the parts that are too close to other edges or
+    // outside of the shape.
+    fn sample_contour(
+        &mut self,
+        events: &HatchingEvents,
+        raw_offset: &[Point],
+        offset: f32,
+        tolerance: f32,
+    ) {
+        // Allow some imprecision for points that are exactly at the requested offset,
+        // and for the flattened arcs that can be up to half of the tolerance closer.
+        let min_distance = f32::min(
+            offset * 0.999,
+            f32::max(offset - tolerance * 0.5, offset * 0.5),
+        );
+        let sample_step = f32::max(offset * 0.25, tolerance);
+
+        let samples = &mut self.contour_samples;
+        let runs = &mut self.contour_runs;
+        samples.clear();
+        runs.clear();
+
+        let n = raw_offset.len();
+        for i in 0..n {
+            let a = raw_offset[i];
+            let b = raw_offset[(i + 1) % n];
+            let count = ((b - a).length() / sample_step).ceil().max(1.0) as u32;
+            for j in 0..count {
+                let p = a.lerp(b, j as f32 / count as f32);
+                samples.push((p, events.is_valid_contour_point(p, min_distance)));
+            }
+        }
+
+        let first_invalid = match samples.iter().position(|&(_, valid)| !valid) {
+            Some(idx) => idx,
+            None => {
+                if !samples.is_empty() {
+                    let mut run: Vec<Point> = samples.iter().map(|&(p, _)| p).collect();
+                    run.push(run[0]);
+                    runs.push(run);
+                }
+                return;
+            }
+        };
+
+        let boundary = |valid: Point, invalid: Point| {
+            let mut a = valid;
+            let mut b = invalid;
+            for _ in 0..12 {
+                let mid = a.lerp(b, 0.5);
+                if events.is_valid_contour_point(mid, min_distance) {
+                    a = mid;
+                } else {
+                    b = mid;
+                }
+            }
+            a
+        };
+
+        let count = samples.len();
+        let mut run = Vec::new();
+        for k in 1..=count {
+            let (p0, valid0) = samples[(first_invalid + k - 1) % count];
+            let (p1, valid1) = samples[(first_invalid + k) % count];
+            match (valid0, valid1) {
+                (true, true) => {
+                    run.push(p1);
+                }
+                (true, false) => {
+                    run.push(boundary(p0, p1));
+                    runs.push(mem::take(&mut run));
+                }
+                (false, true) => {
+                    run.push(boundary(p1, p0));
+                    run.push(p1);
+                }
+                (false, false) => {}
+            }
+        }
+
+        // Join the runs that were separated by a sharp corner of the offset curve.
+        let merge_threshold = tolerance * tolerance;
+        let mut i = 0;
+        while runs.len() > 1 && i < runs.len() {
+            let next = (i + 1) % runs.len();
+            let end = *runs[i].last().unwrap();
+            let start = runs[next][0];
+            if (start - end).square_length() <= merge_threshold {
+                let next_run = runs.remove(next);
+                let i = if next < i { i - 1 } else { i };
+                runs[i].extend_from_slice(&next_run[1..]);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
     fn dot(
         &mut self,
         events: &HatchingEvents,
@@ -442,6 +702,253 @@ struct HatchingEvents {
     edges: Vec<Edge>,
 }
 
+impl HatchingEvents {
+    // Builds the events of the polygons of a contour pass, without rotation.
+    fn set_polygons(&mut self, polygons: &ContourEvents) {
+        let mut builder = EventsBuilder::new(Angle::radians(0.0));
+        builder.edges = mem::replace(&mut self.edges, Vec::new());
+        builder.edges.clear();
+        for range in &polygons.sub_paths {
+            let polygon = &polygons.points[range.clone()];
+            builder.move_to(polygon[0]);
+            for &p in &polygon[1..] {
+                builder.line_to(p);
+            }
+            builder.close();
+        }
+        mem::swap(self, &mut builder.build());
+    }
+
+    // Whether the point is inside of the shape and at least at a given distance
+    // of the outline.
+    //
+    // This follows the sweep line of `Hatcher::hatch_line`: the edges are sorted by
+    // their upper endpoint and the inside test counts the edges that cross the scanline
+    // going through the point on its left.
+    fn is_valid_contour_point(&self, p: Point, min_distance: f32) -> bool {
+        let sq_min_distance = min_distance * min_distance;
+        let mut inside = false;
+        for edge in &self.edges {
+            if edge.from.y > p.y + min_distance {
+                break;
+            }
+
+            if edge.from.y <= p.y && edge.to.y > p.y && edge.solve_x_for_y(p.y) < p.x {
+                inside = !inside;
+            }
+
+            if edge.to.y >= p.y - min_distance
+                && square_distance_to_segment(p, edge) < sq_min_distance {
+                return false;
+            }
+        }
+
+        inside
+    }
+
+    fn set_rotated(&mut self, src: &HatchingEvents, angle: Angle<f32>) {
+        let rotation = Rotation2D::new(angle);
+        self.edges.clear();
+        for edge in &src.edges {
+            let mut from = rotation.transform_point(edge.from);
+            let mut to = rotation.transform_point(edge.to);
+            if compare_positions(from, to) == Ordering::Greater {
+                mem::swap(&mut from, &mut to);
+            }
+            self.edges.push(Edge { from, to });
+        }
+        self.edges.sort_by(|a, b| compare_positions(a.from, b.from));
+    }
+}
+
+// The flattened sub-paths to generate contours for.
+//
+// The offset curves are built from the polygons, the queries against the outline
+// use the hatching events built from them (see `HatchingEvents::set_polygons`).
+struct ContourEvents {
+    points: Vec<Point>,
+    sub_paths: Vec<Range<usize>>,
+}
+
+impl ContourEvents {
+    fn new() -> Self {
+        ContourEvents {
+            points: Vec::new(),
+            sub_paths: Vec::new(),
+        }
+    }
+
+    fn set_path<Iter>(&mut self, tolerance: f32, it: Iter)
+    where
+        Iter: Iterator<Item = PathEvent>
+    {
+        let mut builder = ContourEventsBuilder {
+            events: mem::replace(self, ContourEvents::new()),
+            start: 0,
+        };
+        builder.events.points.clear();
+        builder.events.sub_paths.clear();
+
+        let mut builder = builder.flattened(tolerance);
+        for evt in it {
+            builder.path_event(evt);
+        }
+        *self = builder.build();
+    }
+}
+
+struct ContourEventsBuilder {
+    events: ContourEvents,
+    start: usize,
+}
+
+impl ContourEventsBuilder {
+    fn end_sub_path(&mut self) {
+        let points = &mut self.events.points;
+        if points.len() > self.start + 1 && points[self.start] == points[points.len() - 1] {
+            points.pop();
+        }
+
+        if points.len() < self.start + 3 {
+            // Not enough points to enclose anything.
+            points.truncate(self.start);
+            return;
+        }
+
+        self.events.sub_paths.push(self.start..points.len());
+        self.start = points.len();
+    }
+}
+
+impl Build for ContourEventsBuilder {
+    type PathType = ContourEvents;
+
+    fn build(mut self) -> ContourEvents {
+        self.build_and_reset()
+    }
+
+    fn build_and_reset(&mut self) -> ContourEvents {
+        self.end_sub_path();
+        self.start = 0;
+
+        mem::replace(&mut self.events, ContourEvents::new())
+    }
+}
+
+impl FlatPathBuilder for ContourEventsBuilder {
+    fn move_to(&mut self, to: Point) {
+        self.end_sub_path();
+        self.events.points.push(to);
+    }
+
+    fn line_to(&mut self, to: Point) {
+        if self.events.points.len() > self.start && to == self.current_position() {
+            return;
+        }
+        self.events.points.push(to);
+    }
+
+    fn close(&mut self) {
+        let first = if self.events.points.len() > self.start {
+            Some(self.events.points[self.start])
+        } else {
+            None
+        };
+        self.end_sub_path();
+        // Subsequent commands start from the first point of the closed sub-path.
+        if let Some(first) = first {
+            self.events.points.push(first);
+        }
+    }
+
+    fn current_position(&self) -> Point {
+        self.events.points.last().cloned().unwrap_or(point(0.0, 0.0))
+    }
+}
+
+// Offsets each edge of a closed polygon by a signed distance along its normal and
+// joins them with circular arcs where they don't intersect.
+//
+// The result can have self-intersections or parts that are too close to the polygon,
+// which are filtered out later.
+fn offset_polygon(
+    polygon: &[Point],
+    offset: f32,
+    tolerance: f32,
+    edges: &mut Vec<Edge>,
+    output: &mut Vec<Point>,
+) {
+    edges.clear();
+    output.clear();
+
+    let n = polygon.len();
+    for i in 0..n {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % n];
+        let e = b - a;
+        let normal = vector(-e.y, e.x).normalize() * offset;
+        edges.push(Edge { from: a + normal, to: b + normal });
+    }
+
+    // Where the offset edges cross, cut them at the intersection.
+    for i in 0..n {
+        let next = (i + 1) % n;
+        let e1 = edges[i].to_vector();
+        let e2 = edges[next].to_vector();
+        if e1.cross(e2) * offset <= 0.0 {
+            continue;
+        }
+        if let Some((t, _)) = edges[i].intersection_t(&edges[next]) {
+            let p = edges[i].sample(t);
+            edges[i].to = p;
+            edges[next].from = p;
+        }
+    }
+
+    let distance = offset.abs();
+    // Maximum angle between two points of an arc for half of the tolerance.
+    let tolerance = tolerance * 0.5;
+    let max_arc_step = if tolerance < distance {
+        2.0 * (1.0 - tolerance / distance).acos()
+    } else {
+        f32::consts::FRAC_PI_2
+    };
+
+    for i in 0..n {
+        let next = (i + 1) % n;
+        output.push(edges[i].from);
+        output.push(edges[i].to);
+
+        let e1 = polygon[next] - polygon[i];
+        let e2 = polygon[(i + 2) % n] - polygon[next];
+        if e1.cross(e2) * offset >= 0.0 {
+            // The offset edges intersect, or the vertex is flat.
+            continue;
+        }
+
+        let center = polygon[next];
+        let n1 = edges[i].to - center;
+        let angle = f32::atan2(e1.cross(e2), e1.dot(e2));
+        let count = (angle.abs() / max_arc_step).ceil() as u32;
+        for k in 1..count {
+            let rotation = Rotation2D::new(Angle::radians(angle * k as f32 / count as f32));
+            output.push(center + rotation.transform_vector(n1));
+        }
+    }
+}
+
+fn square_distance_to_segment(p: Point, segment: &Edge) -> f32 {
+    let v = segment.to_vector();
+    let sq_len = v.square_length();
+    let t = if sq_len > 0.0 {
+        ((p - segment.from).dot(v) / sq_len).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    (segment.sample(t) - p).square_length()
+}
+
 impl HatchingEvents {
     fn new() -> Self {
         HatchingEvents {
@@ -625,6 +1132,43 @@ impl<Cb: FnMut(&HatchSegment)> HatchBuilder for RegularHatchingPattern<Cb> {
     fn add_segment(&mut self, segment: &HatchSegment) { (self.callback)(segment) }
 }
 
+/// A `HatchBuilder` implementation for hatching patterns with intervals computed
+/// by a callback.
+pub struct VariableHatchingPattern<Sp, Cb>
+where
+    Sp: FnMut(u32, f32) -> f32,
+    Cb: FnMut(&HatchSegment),
+{
+    /// Computes the distance to the next row from the index of the next row and the
+    /// position of the current one (see `HatchBuilder::next_offset_at`).
+    pub interval: Sp,
+    /// A callback invoked for each segment.
+    pub callback: Cb,
+}
+
+impl<Sp, Cb> HatchBuilder for VariableHatchingPattern<Sp, Cb>
+where
+    Sp: FnMut(u32, f32) -> f32,
+    Cb: FnMut(&HatchSegment),
+{
+    fn next_offset(&mut self, row: u32) -> f32 { (self.interval)(row, 0.0) }
+    fn next_offset_at(&mut self, row: u32, v: f32) -> f32 { (self.interval)(row, v) }
+    fn add_segment(&mut self, segment: &HatchSegment) { (self.callback)(segment) }
+}
+
+/// A `ContourBuilder` implementation for contours at constant intervals.
+pub struct RegularContourPattern<Cb: FnMut(&Contour)> {
+    /// The distance between each level of contours.
+    pub interval: f32,
+    /// A callback invoked for each contour.
+    pub callback: Cb,
+}
+
+impl<Cb: FnMut(&Contour)> ContourBuilder for RegularContourPattern<Cb> {
+    fn next_offset(&mut self, _level: u32) -> f32 { self.interval }
+    fn add_contour(&mut self, contour: &Contour) { (self.callback)(contour) }
+}
+
 // Converts a hatching pattern into a dotted pattern.
 struct HatchesToDots<'l> {
     builder: &'l mut dyn DotBuilder,
@@ -677,3 +1221,181 @@ impl<'l> HatchBuilder for HatchesToDots<'l> {
 fn modulo(a: f32, m: f32) -> f32 {
     if a >= 0.0 { a % m } else { m + (a % m) }
 }
+
+#[cfg(test)]
+use crate::square;
+
+#[test]
+fn cross_hatching() {
+    use crate::path::Path;
+
+    // A rectangle and angles that aren't symmetric, so that the segments of each pass
+    // depend on the sign of the rotation.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 4.0));
+    builder.line_to(point(0.0, 4.0));
+    builder.close();
+    let path = builder.build();
+
+    struct Output {
+        passes: Vec<Angle<f32>>,
+        segments: Vec<(usize, Point, Point)>,
+    }
+
+    impl HatchBuilder for Output {
+        fn next_offset(&mut self, _row: u32) -> f32 { 1.0 }
+        fn begin_pass(&mut self, _pass: u32, angle: Angle<f32>) { self.passes.push(angle); }
+        fn add_segment(&mut self, segment: &HatchSegment) {
+            self.segments.push((self.passes.len() - 1, segment.a.position, segment.b.position));
+        }
+    }
+
+    let mut output = Output { passes: Vec::new(), segments: Vec::new() };
+    let angles = [Angle::degrees(30.0), Angle::degrees(120.0)];
+    let mut hatcher = Hatcher::new();
+    hatcher.cross_hatch_path(path.iter(), &HatchingOptions::DEFAULT, &angles, &mut output);
+
+    assert_eq!(output.passes, angles.to_vec());
+    for (pass, angle) in angles.iter().enumerate() {
+        let segments: Vec<_> = output.segments.iter().filter(|s| s.0 == pass).collect();
+        assert!(!segments.is_empty());
+
+        // The segments go along the direction of the angle, counter-clockwise from the
+        // x axis with the y axis pointing down.
+        let direction = vector(angle.radians.cos(), -angle.radians.sin());
+        for &&(_, a, b) in &segments {
+            let v = (b - a).normalize();
+            assert!(v.cross(direction).abs() < 0.001, "pass {}: {:?} {:?}", pass, a, b);
+        }
+
+        // Each pass is the same as hatching the path with its angle.
+        let mut expected = Vec::new();
+        hatcher.hatch_path(
+            path.iter(),
+            &HatchingOptions::angle(*angle),
+            &mut RegularHatchingPattern {
+                interval: 1.0,
+                callback: &mut |segment: &HatchSegment| {
+                    expected.push((segment.a.position, segment.b.position));
+                },
+            },
+        );
+        let segments: Vec<_> = segments.iter().map(|&&(_, a, b)| (a, b)).collect();
+        assert_eq!(segments, expected);
+    }
+}
+
+#[test]
+fn variable_hatching() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 0.0, 10.0);
+    let path = builder.build();
+
+    let mut rows = Vec::new();
+    let mut hatcher = Hatcher::new();
+    hatcher.hatch_path(
+        path.iter(),
+        &HatchingOptions::DEFAULT,
+        &mut VariableHatchingPattern {
+            // Rows get further apart as they get further from the origin.
+            interval: |_row, v| 1.0 + v * 0.5,
+            callback: |segment: &HatchSegment| { rows.push(segment.v); },
+        },
+    );
+
+    assert_eq!(rows, vec![1.0, 2.5, 4.75, 8.125]);
+}
+
+#[test]
+fn contour_hatching() {
+    use crate::path::Path;
+    use crate::math::Rect;
+
+    fn bounds(points: &[Point]) -> Rect {
+        let mut min = points[0];
+        let mut max = points[0];
+        for p in points {
+            min = Point::min(min, *p);
+            max = Point::max(max, *p);
+        }
+        Rect { origin: min, size: (max - min).to_size() }
+    }
+
+    fn check_bounds(points: &[Point], min: f32, max: f32) {
+        let r = bounds(points);
+        assert!((r.min_x() - min).abs() < 0.01, "{:?}", r);
+        assert!((r.min_y() - min).abs() < 0.01, "{:?}", r);
+        assert!((r.max_x() - max).abs() < 0.01, "{:?}", r);
+        assert!((r.max_y() - max).abs() < 0.01, "{:?}", r);
+    }
+
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 0.0, 10.0);
+    let path = builder.build();
+
+    let mut contours = Vec::new();
+    let mut hatcher = Hatcher::new();
+    hatcher.contour_path(
+        path.iter(),
+        &HatchingOptions::DEFAULT,
+        &mut RegularContourPattern {
+            interval: 1.0,
+            callback: |contour: &Contour| {
+                contours.push((contour.level, contour.offset, contour.is_closed, contour.points.to_vec()));
+            },
+        },
+    );
+
+    for (level, &(l, offset, closed, ref points)) in contours.iter().take(4).enumerate() {
+        assert_eq!(l, level as u32);
+        assert_eq!(offset, level as f32 + 1.0);
+        assert!(closed);
+        check_bounds(points, offset, 10.0 - offset);
+        for p in points {
+            // The contours of a square are squares (the corners are not rounded).
+            let distance = f32::min(
+                f32::min(p.x, 10.0 - p.x),
+                f32::min(p.y, 10.0 - p.y),
+            );
+            assert!((distance - offset).abs() < 0.01);
+        }
+    }
+    // The center of the square is at a distance of 5 from the outline.
+    assert!(contours.iter().all(|c| c.1 <= 5.0));
+
+    // A square with a hole.
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 0.0, 10.0);
+    square(&mut builder, 4.0, 4.0, 2.0);
+    let path = builder.build();
+
+    let mut contours = Vec::new();
+    hatcher.contour_path(
+        path.iter(),
+        &HatchingOptions::DEFAULT,
+        &mut RegularContourPattern {
+            interval: 1.0,
+            callback: |contour: &Contour| {
+                if contour.level == 0 {
+                    contours.push((contour.is_closed, contour.points.to_vec()));
+                }
+            },
+        },
+    );
+
+    assert_eq!(contours.len(), 2);
+    assert!(contours[0].0);
+    assert!(contours[1].0);
+    check_bounds(&contours[0].1, 1.0, 9.0);
+    // Around the hole, the corners are rounded.
+    check_bounds(&contours[1].1, 3.0, 7.0);
+    for p in &contours[1].1 {
+        let dx = f32::max(f32::max(4.0 - p.x, p.x - 6.0), 0.0);
+        let dy = f32::max(f32::max(4.0 - p.y, p.y - 6.0), 0.0);
+        assert!(((dx * dx + dy * dy).sqrt() - 1.0).abs() < 0.11);
+    }
+}
@@ -56,13 +56,24 @@ where
                     prev = p;
                 });
             }
+            PathEvent::Conic(ref segment) => {
+                let (min, max) = segment.fast_bounding_range_y();
+                if min > point.y || max < point.y {
+                    continue;
+                }
+                let mut prev = segment.from;
+                segment.for_each_flattened(tolerance, &mut|p| {
+                    test_segment(*point, &LineSegment { from: prev, to: p }, &mut winding);
+                    prev = p;
+                });
+            }
         }
     }
 
     winding
 }
 
-fn test_segment(point: Point, segment: &LineSegment<f32>, winding: &mut i32) {
+pub(crate) fn test_segment(point: Point, segment: &LineSegment<f32>, winding: &mut i32) {
     if let Some(pos) = segment.horizontal_line_intersection(point.y) {
         if pos.x < point.x {
             if segment.to.y > segment.from.y {
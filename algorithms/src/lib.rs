@@ -17,6 +17,19 @@ pub mod hit_test;
 pub mod walk;
 pub mod aabb;
 pub mod fit;
+pub mod spatial_index;
+pub mod stippling;
 
 pub use crate::path::math;
 pub use crate::path::geom;
+
+// Adds an axis-aligned square sub-path, for tests.
+#[cfg(test)]
+fn square(builder: &mut crate::path::Builder, x: f32, y: f32, size: f32) {
+    use crate::math::point;
+    builder.move_to(point(x, y));
+    builder.line_to(point(x + size, y));
+    builder.line_to(point(x + size, y + size));
+    builder.line_to(point(x, y + size));
+    builder.close();
+}
@@ -1,9 +1,10 @@
-//! Find the first collision between a ray and a path.
+//! Find the collisions between a ray and a path.
 
-use crate::path::PathEvent;
+use crate::path::{PathEvent, FillRule};
 use crate::math::{Point, point, Vector, vector};
 use crate::geom::{LineSegment, Line};
 use std::f32;
+use std::cmp::Ordering;
 
 pub struct Ray {
     pub origin: Point,
@@ -59,6 +60,13 @@ where
                     prev = p;
                 });
             }
+            PathEvent::Conic(ref segment) => {
+                let mut prev = segment.from;
+                segment.for_each_flattened(tolerance, &mut|p| {
+                    test_segment(&mut state, &LineSegment { from: prev, to: p });
+                    prev = p;
+                });
+            }
         }
     }
 
@@ -76,6 +84,197 @@ where
     })
 }
 
+/// How the ray crosses the outline of the shape at a given intersection.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Crossing {
+    /// The ray goes from the outside to the inside of the shape.
+    Entering,
+    /// The ray goes from the inside to the outside of the shape.
+    Exiting,
+    /// The ray stays inside of the shape (for example where sub-paths overlap
+    /// with the non-zero fill rule).
+    Internal,
+}
+
+/// An intersection between a ray and a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayIntersection {
+    /// Position of the point of contact.
+    pub position: Point,
+    /// Normal of the outline at the point of contact, facing the origin of the ray.
+    pub normal: Vector,
+    /// Distance between the origin of the ray and the point of contact.
+    pub distance: f32,
+    /// Index of the edge that was hit, counting the line, curve and close events
+    /// of the path (not the move-to events). The segments that implicitly close the
+    /// sub-paths which don't end with a close event also count as edges, right after
+    /// the last edge of their sub-path.
+    pub edge: u32,
+    /// Winding number of the path right after the intersection along the ray.
+    pub winding: i32,
+    /// Whether the ray enters or exits the shape, according to the fill rule.
+    pub crossing: Crossing,
+}
+
+/// Find all of the intersections between a ray and the path, sorted by distance.
+///
+/// Only the intersections that are closer than `max_distance` to the origin of the
+/// ray are returned (pass `f32::INFINITY` to get them all).
+///
+/// Unlike `raycast_path`, the sub-paths are considered closed, and the ray
+/// is considered to cross the outline only once when passing through a vertex
+/// between two edges, so that the crossings are consistent with the fill rule.
+pub fn raycast_path_all<Iter>(
+    ray: &Ray,
+    path: Iter,
+    fill_rule: FillRule,
+    max_distance: f32,
+    tolerance: f32,
+) -> Vec<RayIntersection>
+where
+    Iter: Iterator<Item=PathEvent>,
+{
+    let mut hits = Vec::new();
+
+    let ray_len = ray.direction.square_length();
+    if ray_len == 0.0 || ray_len.is_nan() {
+        return hits;
+    }
+
+    let mut state = RayCastAllInner {
+        ray,
+        inv_sqrt_len: 1.0 / ray_len.sqrt(),
+        hits: &mut hits,
+        edge: 0,
+    };
+
+    let mut first = point(0.0, 0.0);
+    let mut current = first;
+    let mut edge_since_move = false;
+    for evt in path {
+        match evt {
+            PathEvent::MoveTo(to) => {
+                if edge_since_move && current != first {
+                    // Close the previous sub-path.
+                    state.test_segment(&LineSegment { from: current, to: first });
+                    state.edge += 1;
+                }
+                first = to;
+                current = to;
+                edge_since_move = false;
+                continue;
+            }
+            PathEvent::Line(ref segment) | PathEvent::Close(ref segment) => {
+                state.test_segment(segment);
+                current = segment.to;
+            }
+            PathEvent::Quadratic(ref segment) => {
+                let mut prev = segment.from;
+                segment.for_each_flattened(tolerance, &mut|p| {
+                    state.test_segment(&LineSegment { from: prev, to: p });
+                    prev = p;
+                });
+                current = segment.to;
+            }
+            PathEvent::Cubic(ref segment) => {
+                let mut prev = segment.from;
+                segment.for_each_flattened(tolerance, &mut|p| {
+                    state.test_segment(&LineSegment { from: prev, to: p });
+                    prev = p;
+                });
+                current = segment.to;
+            }
+            PathEvent::Conic(ref segment) => {
+                let mut prev = segment.from;
+                segment.for_each_flattened(tolerance, &mut|p| {
+                    state.test_segment(&LineSegment { from: prev, to: p });
+                    prev = p;
+                });
+                current = segment.to;
+            }
+        }
+        state.edge += 1;
+        edge_since_move = true;
+    }
+
+    if edge_since_move && current != first {
+        state.test_segment(&LineSegment { from: current, to: first });
+    }
+
+    hits.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(Ordering::Equal));
+
+    // The winding number is zero past the last intersection, so we can deduce the
+    // winding number at the origin of the ray from the sum of the crossings.
+    let mut winding = 0;
+    for hit in &hits {
+        winding -= hit.winding;
+    }
+
+    let is_inside = |winding: i32| match fill_rule {
+        FillRule::EvenOdd => winding % 2 != 0,
+        FillRule::NonZero => winding != 0,
+    };
+
+    for hit in &mut hits {
+        let was_inside = is_inside(winding);
+        // At this point hit.winding contains the winding delta of the crossing.
+        winding += hit.winding;
+        hit.winding = winding;
+        hit.crossing = match (was_inside, is_inside(winding)) {
+            (false, true) => Crossing::Entering,
+            (true, false) => Crossing::Exiting,
+            _ => Crossing::Internal,
+        };
+    }
+
+    hits.retain(|hit| hit.distance <= max_distance);
+
+    hits
+}
+
+struct RayCastAllInner<'l> {
+    ray: &'l Ray,
+    inv_sqrt_len: f32,
+    hits: &'l mut Vec<RayIntersection>,
+    edge: u32,
+}
+
+impl<'l> RayCastAllInner<'l> {
+    fn test_segment(&mut self, segment: &LineSegment<f32>) {
+        let d = self.ray.direction;
+        // Which side of the ray the endpoints are on. Zero is treated as being on the
+        // negative side so that the ray crossing a vertex is only counted once.
+        let side_from = d.cross(segment.from - self.ray.origin);
+        let side_to = d.cross(segment.to - self.ray.origin);
+        if (side_from > 0.0) == (side_to > 0.0) {
+            return;
+        }
+
+        let s = side_from / (side_from - side_to);
+        let position = segment.sample(s);
+        let t = (position - self.ray.origin).dot(d) * self.inv_sqrt_len;
+        if t < 0.0 {
+            return;
+        }
+
+        let v = segment.to_vector();
+        let mut normal = vector(-v.y, v.x);
+        if normal.dot(d) > 0.0 {
+            normal = -normal;
+        }
+
+        self.hits.push(RayIntersection {
+            position,
+            normal: normal.normalize(),
+            distance: t,
+            edge: self.edge,
+            // Store the winding delta for now, it is accumulated later.
+            winding: if side_to > side_from { 1 } else { -1 },
+            crossing: Crossing::Internal,
+        });
+    }
+}
+
 struct RayCastInner {
     ray: Line<f32>,
     min_dot: f32,
@@ -146,3 +345,129 @@ fn test_raycast() {
     ).unwrap();
     assert!(hit.position.approx_eq(&point(1.0, 0.0)));
 }
+
+#[test]
+fn test_raycast_all() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    let ray = Ray { origin: point(-1.0, 0.5), direction: vector(2.0, 0.0) };
+    let hits = raycast_path_all(&ray, path.iter(), FillRule::EvenOdd, f32::INFINITY, 0.1);
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].position, point(0.0, 0.5));
+    assert_eq!(hits[0].normal, vector(-1.0, 0.0));
+    assert_eq!(hits[0].distance, 1.0);
+    assert_eq!(hits[0].edge, 3);
+    assert_eq!(hits[0].crossing, Crossing::Entering);
+    assert_eq!(hits[1].position, point(1.0, 0.5));
+    assert_eq!(hits[1].normal, vector(-1.0, 0.0));
+    assert_eq!(hits[1].distance, 2.0);
+    assert_eq!(hits[1].edge, 1);
+    assert_eq!(hits[1].crossing, Crossing::Exiting);
+    assert_eq!(hits[1].winding, 0);
+
+    // Starting inside.
+    let ray = Ray { origin: point(0.5, 0.5), direction: vector(0.0, -1.0) };
+    let hits = raycast_path_all(&ray, path.iter(), FillRule::EvenOdd, f32::INFINITY, 0.1);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].position, point(0.5, 0.0));
+    assert_eq!(hits[0].edge, 0);
+    assert_eq!(hits[0].crossing, Crossing::Exiting);
+
+    // Maximum distance.
+    let ray = Ray { origin: point(-1.0, 0.5), direction: vector(1.0, 0.0) };
+    let hits = raycast_path_all(&ray, path.iter(), FillRule::EvenOdd, 1.5, 0.1);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].crossing, Crossing::Entering);
+
+    // Through a vertex.
+    let ray = Ray { origin: point(-1.0, -1.0), direction: vector(1.0, 1.0) };
+    let hits = raycast_path_all(&ray, path.iter(), FillRule::EvenOdd, f32::INFINITY, 0.1);
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].crossing, Crossing::Entering);
+    assert_eq!(hits[1].crossing, Crossing::Exiting);
+
+    // Touching a vertex.
+    let ray = Ray { origin: point(-1.0, 1.0), direction: vector(1.0, -1.0) };
+    let hits = raycast_path_all(&ray, path.iter(), FillRule::EvenOdd, f32::INFINITY, 0.1);
+    assert_eq!(hits.len() % 2, 0);
+    for hit in &hits {
+        assert_eq!(hit.position, point(0.0, 0.0));
+    }
+    assert_eq!(hits.last().unwrap().winding, 0);
+}
+
+#[test]
+fn test_raycast_all_overlapping() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(4.0, 0.0));
+    builder.line_to(point(4.0, 4.0));
+    builder.line_to(point(0.0, 4.0));
+    builder.close();
+    // Not explicitly closed.
+    builder.move_to(point(1.0, 1.0));
+    builder.line_to(point(3.0, 1.0));
+    builder.line_to(point(3.0, 3.0));
+    builder.line_to(point(1.0, 3.0));
+    let path = builder.build();
+
+    let ray = Ray { origin: point(-1.0, 2.0), direction: vector(1.0, 0.0) };
+
+    let hits = raycast_path_all(&ray, path.iter(), FillRule::NonZero, f32::INFINITY, 0.1);
+    let crossings: Vec<Crossing> = hits.iter().map(|hit| hit.crossing).collect();
+    assert_eq!(crossings, vec![
+        Crossing::Entering, Crossing::Internal, Crossing::Internal, Crossing::Exiting
+    ]);
+    let windings: Vec<i32> = hits.iter().map(|hit| hit.winding.abs()).collect();
+    assert_eq!(windings, vec![1, 2, 1, 0]);
+
+    let hits = raycast_path_all(&ray, path.iter(), FillRule::EvenOdd, f32::INFINITY, 0.1);
+    let crossings: Vec<Crossing> = hits.iter().map(|hit| hit.crossing).collect();
+    assert_eq!(crossings, vec![
+        Crossing::Entering, Crossing::Exiting, Crossing::Entering, Crossing::Exiting
+    ]);
+    let distances: Vec<f32> = hits.iter().map(|hit| hit.distance).collect();
+    assert_eq!(distances, vec![1.0, 2.0, 4.0, 5.0]);
+    let edges: Vec<u32> = hits.iter().map(|hit| hit.edge).collect();
+    assert_eq!(edges, vec![3, 7, 5, 1]);
+
+    // The implicit close of the first sub-path comes before the edges of the second one.
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 1.0));
+    builder.line_to(point(3.0, 1.0));
+    builder.line_to(point(3.0, 3.0));
+    builder.line_to(point(1.0, 3.0));
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(4.0, 0.0));
+    builder.line_to(point(4.0, 4.0));
+    builder.line_to(point(0.0, 4.0));
+    builder.close();
+    let path = builder.build();
+
+    let hits = raycast_path_all(&ray, path.iter(), FillRule::EvenOdd, f32::INFINITY, 0.1);
+    let edges: Vec<u32> = hits.iter().map(|hit| hit.edge).collect();
+    assert_eq!(edges, vec![7, 3, 1, 5]);
+
+    // NaNs in the path don't make the sort panic. The path builder rejects them in
+    // debug builds so the events are provided directly.
+    let a = point(0.0, 0.0);
+    let b = point(f32::NAN, 1.0);
+    let c = point(0.0, 4.0);
+    let events = vec![
+        PathEvent::MoveTo(a),
+        PathEvent::Line(LineSegment { from: a, to: b }),
+        PathEvent::Line(LineSegment { from: b, to: c }),
+        PathEvent::Close(LineSegment { from: c, to: a }),
+    ];
+    raycast_path_all(&ray, events.into_iter(), FillRule::EvenOdd, f32::INFINITY, 0.1);
+}
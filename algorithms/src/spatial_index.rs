@@ -0,0 +1,843 @@
+//! Bounding volume hierarchy to speed up hit testing and raycasting among many paths.
+//!
+//! [`hit_test_path`](../hit_test/fn.hit_test_path.html) and
+//! [`raycast_path`](../raycast/fn.raycast_path.html) iterate over every event of a single
+//! path. When picking among a large number of shapes, it is much faster to flatten the
+//! paths once and store their segments in a `SpatialIndex`.
+//!
+//! The index is a two-level bounding volume hierarchy: a tree of path bounding rectangles,
+//! and for each path a tree of its flattened line segments.
+//!
+//! # Example
+//!
+//! ```
+//! use lyon_algorithms::spatial_index::SpatialIndex;
+//! use lyon_algorithms::path::{Path, FillRule};
+//! use lyon_algorithms::path::builder::*;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let square = builder.build();
+//!
+//! let tolerance = 0.01;
+//! let mut index_builder = SpatialIndex::builder(tolerance);
+//! let id = index_builder.add_path(square.iter());
+//! let index = index_builder.build();
+//!
+//! let mut hits = Vec::new();
+//! index.hit_test(point(5.0, 5.0), FillRule::NonZero, &mut |hit| { hits.push(hit.path); });
+//! assert_eq!(hits, vec![id]);
+//! ```
+
+use crate::path::{PathEvent, FillRule};
+use crate::math::{Point, Vector, Rect, point, vector};
+use crate::geom::LineSegment;
+use crate::raycast::Ray;
+use crate::hit_test;
+
+use std::cell::Cell;
+use std::f32;
+use std::ops::Range;
+
+/// Maximum number of items in a leaf of the hierarchy.
+const LEAF_SIZE: usize = 4;
+
+/// Upper bound of the depth of the trees (they are balanced).
+const MAX_DEPTH: usize = 64;
+
+/// Identifies a path in a `SpatialIndex`.
+///
+/// Ids are allocated sequentially, starting at zero, in the order in which
+/// paths are added to the `SpatialIndexBuilder`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PathId(pub u32);
+
+/// A line segment of the flattened outline of an indexed path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IndexedSegment {
+    pub segment: LineSegment<f32>,
+    /// The path this segment belongs to.
+    pub path: PathId,
+    /// Index of the edge this segment approximates, counting the line, curve and close
+    /// events of the path (not the move-to events).
+    pub edge: u32,
+}
+
+/// The result of a point query.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PointHit {
+    /// The path containing the point.
+    pub path: PathId,
+    /// Winding number of the point with respect to the path.
+    pub winding: i32,
+}
+
+/// The result of a ray query.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayHit {
+    /// The path that was hit.
+    pub path: PathId,
+    /// Index of the edge that was hit (see `IndexedSegment::edge`).
+    pub edge: u32,
+    /// Position of the point of contact.
+    pub position: Point,
+    /// Normal of the outline at the point of contact, facing the origin of the ray.
+    pub normal: Vector,
+    /// Distance between the origin of the ray and the point of contact.
+    pub distance: f32,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Node {
+    min: Point,
+    max: Point,
+    // For leaves, the range of items is `first..(first + count)`.
+    // For internal nodes (count == 0), the children are at `first` and `first + 1`.
+    first: u32,
+    count: u32,
+}
+
+impl Node {
+    fn is_leaf(&self) -> bool { self.count > 0 }
+
+    fn items(&self) -> Range<usize> {
+        self.first as usize .. (self.first + self.count) as usize
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct PathInfo {
+    min: Point,
+    max: Point,
+    // Index of the root in `SpatialIndex::segment_nodes`, if the path has segments.
+    root: Option<u32>,
+}
+
+/// Builds a `SpatialIndex`.
+pub struct SpatialIndexBuilder {
+    tolerance: f32,
+    segments: Vec<IndexedSegment>,
+    path_ranges: Vec<Range<usize>>,
+}
+
+impl SpatialIndexBuilder {
+    /// Constructor.
+    ///
+    /// Curves are approximated with line segments using the provided tolerance.
+    pub fn new(tolerance: f32) -> Self {
+        SpatialIndexBuilder {
+            tolerance,
+            segments: Vec::new(),
+            path_ranges: Vec::new(),
+        }
+    }
+
+    /// Adds a path to the index and returns its id.
+    pub fn add_path<Iter>(&mut self, path: Iter) -> PathId
+    where
+        Iter: Iterator<Item=PathEvent>,
+    {
+        let id = PathId(self.path_ranges.len() as u32);
+        let start = self.segments.len();
+        let mut edge = 0;
+        for evt in path {
+            match evt {
+                PathEvent::MoveTo(..) => {
+                    continue;
+                }
+                PathEvent::Line(segment) | PathEvent::Close(segment) => {
+                    self.add_segment(id, edge, segment);
+                }
+                PathEvent::Quadratic(segment) => {
+                    let mut prev = segment.from;
+                    segment.for_each_flattened(self.tolerance, &mut |p| {
+                        self.add_segment(id, edge, LineSegment { from: prev, to: p });
+                        prev = p;
+                    });
+                }
+                PathEvent::Cubic(segment) => {
+                    let mut prev = segment.from;
+                    segment.for_each_flattened(self.tolerance, &mut |p| {
+                        self.add_segment(id, edge, LineSegment { from: prev, to: p });
+                        prev = p;
+                    });
+                }
+                PathEvent::Conic(segment) => {
+                    let mut prev = segment.from;
+                    segment.for_each_flattened(self.tolerance, &mut |p| {
+                        self.add_segment(id, edge, LineSegment { from: prev, to: p });
+                        prev = p;
+                    });
+                }
+            }
+            edge += 1;
+        }
+
+        self.path_ranges.push(start..self.segments.len());
+
+        id
+    }
+
+    fn add_segment(&mut self, path: PathId, edge: u32, segment: LineSegment<f32>) {
+        if segment.from == segment.to {
+            return;
+        }
+        self.segments.push(IndexedSegment { segment, path, edge });
+    }
+
+    /// Builds the hierarchy.
+    pub fn build(self) -> SpatialIndex {
+        let mut segments = self.segments;
+        let mut segment_nodes = Vec::new();
+        let mut paths = Vec::with_capacity(self.path_ranges.len());
+        let mut path_items = Vec::with_capacity(self.path_ranges.len());
+
+        for (idx, range) in self.path_ranges.iter().enumerate() {
+            if range.start == range.end {
+                paths.push(PathInfo {
+                    min: point(0.0, 0.0),
+                    max: point(0.0, 0.0),
+                    root: None,
+                });
+                continue;
+            }
+
+            let mut items: Vec<BuildItem> = segments[range.clone()].iter().enumerate().map(|(i, s)| {
+                BuildItem::new(s.segment.from, s.segment.to, (range.start + i) as u32)
+            }).collect();
+
+            let root = build_tree(&mut items, range.start as u32, &mut segment_nodes);
+
+            // The leaves refer to the segments in the order of the build items.
+            let sorted: Vec<IndexedSegment> = items.iter().map(|item| {
+                segments[item.index as usize]
+            }).collect();
+            segments[range.clone()].copy_from_slice(&sorted);
+
+            let root_node = segment_nodes[root as usize];
+            paths.push(PathInfo {
+                min: root_node.min,
+                max: root_node.max,
+                root: Some(root),
+            });
+            path_items.push(BuildItem::new(root_node.min, root_node.max, idx as u32));
+        }
+
+        let mut path_nodes = Vec::new();
+        if !path_items.is_empty() {
+            build_tree(&mut path_items, 0, &mut path_nodes);
+        }
+        let path_order = path_items.iter().map(|item| item.index).collect();
+
+        SpatialIndex {
+            segments,
+            segment_nodes,
+            paths,
+            path_nodes,
+            path_order,
+        }
+    }
+}
+
+/// A bounding volume hierarchy of the outlines of a set of paths.
+///
+/// See the [module documentation](index.html).
+pub struct SpatialIndex {
+    segments: Vec<IndexedSegment>,
+    segment_nodes: Vec<Node>,
+    paths: Vec<PathInfo>,
+    path_nodes: Vec<Node>,
+    path_order: Vec<u32>,
+}
+
+impl SpatialIndex {
+    /// Creates a builder for a spatial index.
+    pub fn builder(tolerance: f32) -> SpatialIndexBuilder {
+        SpatialIndexBuilder::new(tolerance)
+    }
+
+    /// Returns the number of paths in the index.
+    pub fn num_paths(&self) -> usize { self.paths.len() }
+
+    /// Returns the number of line segments in the index.
+    pub fn num_segments(&self) -> usize { self.segments.len() }
+
+    /// Returns the bounding rectangle of a path.
+    pub fn path_bounding_rect(&self, id: PathId) -> Rect {
+        let info = &self.paths[id.0 as usize];
+        Rect {
+            origin: info.min,
+            size: (info.max - info.min).to_size(),
+        }
+    }
+
+    /// Invokes the callback for each path that contains the point, according to the fill rule.
+    ///
+    /// Like `hit_test_path`, sub-paths that are not explicitly closed are not implicitly closed.
+    pub fn hit_test(&self, position: Point, fill_rule: FillRule, callback: &mut dyn FnMut(PointHit)) {
+        self.for_each_path_in_box(position, position, &mut |idx| {
+            let winding = self.winding_number(idx, position);
+            let inside = match fill_rule {
+                FillRule::EvenOdd => winding % 2 != 0,
+                FillRule::NonZero => winding != 0,
+            };
+            if inside {
+                callback(PointHit {
+                    path: PathId(idx),
+                    winding,
+                });
+            }
+        });
+    }
+
+    /// Computes the winding number of a position with respect to a path of the index.
+    pub fn winding_number_at_position(&self, id: PathId, position: Point) -> i32 {
+        self.winding_number(id.0, position)
+    }
+
+    /// Invokes the callback for each segment that intersects the rectangle.
+    pub fn segments_in_rect(&self, rect: &Rect, callback: &mut dyn FnMut(&IndexedSegment)) {
+        let min = rect.min();
+        let max = rect.max();
+        self.for_each_path_in_box(min, max, &mut |idx| {
+            self.for_each_segment_in_box(idx, min, max, &mut |segment| {
+                if segment_intersects_box(&segment.segment, min, max) {
+                    callback(segment);
+                }
+            });
+        });
+    }
+
+    /// Invokes the callback once for each path that has its outline or fill intersecting the
+    /// rectangle, according to the fill rule.
+    pub fn paths_in_rect(&self, rect: &Rect, fill_rule: FillRule, callback: &mut dyn FnMut(PathId)) {
+        let min = rect.min();
+        let max = rect.max();
+        self.for_each_path_in_box(min, max, &mut |idx| {
+            let mut found = false;
+            self.for_each_segment_in_box(idx, min, max, &mut |segment| {
+                found = found || segment_intersects_box(&segment.segment, min, max);
+            });
+
+            if !found {
+                // The outline does not intersect the rectangle, so either the rectangle
+                // is entirely inside of the path or entirely outside.
+                let winding = self.winding_number(idx, min);
+                found = match fill_rule {
+                    FillRule::EvenOdd => winding % 2 != 0,
+                    FillRule::NonZero => winding != 0,
+                };
+            }
+
+            if found {
+                callback(PathId(idx));
+            }
+        });
+    }
+
+    /// Finds the closest intersection between a ray and the outlines of the indexed paths.
+    pub fn raycast(&self, ray: &Ray) -> Option<RayHit> {
+        let ray_len = ray.direction.square_length();
+        if ray_len == 0.0 || ray_len.is_nan() || self.path_nodes.is_empty() {
+            return None;
+        }
+
+        let inv_dir = vector(1.0 / ray.direction.x, 1.0 / ray.direction.y);
+        // Shared between the node tests and the leaf callbacks to prune the traversal.
+        let best_t = Cell::new(f32::MAX);
+        let mut best: Option<(IndexedSegment, f32)> = None;
+
+        traverse(
+            &self.path_nodes, 0,
+            &mut |node| ray_box_intersection(ray.origin, inv_dir, node.min, node.max, best_t.get()),
+            &mut |items| {
+                for &idx in &self.path_order[items] {
+                    let info = &self.paths[idx as usize];
+                    if !ray_box_intersection(ray.origin, inv_dir, info.min, info.max, best_t.get()) {
+                        continue;
+                    }
+                    traverse(
+                        &self.segment_nodes, info.root.unwrap(),
+                        &mut |node| ray_box_intersection(ray.origin, inv_dir, node.min, node.max, best_t.get()),
+                        &mut |items| {
+                            for segment in &self.segments[items] {
+                                if let Some(t) = ray_segment_intersection(ray, &segment.segment) {
+                                    if t < best_t.get() {
+                                        best_t.set(t);
+                                        best = Some((*segment, t));
+                                    }
+                                }
+                            }
+                        },
+                    );
+                }
+            },
+        );
+
+        best.map(|(segment, t)| {
+            let v = segment.segment.to_vector();
+            let mut normal = vector(-v.y, v.x);
+            if normal.dot(ray.direction) > 0.0 {
+                normal = -normal;
+            }
+
+            RayHit {
+                path: segment.path,
+                edge: segment.edge,
+                position: ray.origin + ray.direction * t,
+                normal: normal.normalize(),
+                distance: t * ray_len.sqrt(),
+            }
+        })
+    }
+
+    fn for_each_path_in_box(&self, min: Point, max: Point, callback: &mut dyn FnMut(u32)) {
+        if self.path_nodes.is_empty() {
+            return;
+        }
+
+        traverse(
+            &self.path_nodes, 0,
+            &mut |node| boxes_overlap(node.min, node.max, min, max),
+            &mut |items| {
+                for &idx in &self.path_order[items] {
+                    let info = &self.paths[idx as usize];
+                    if boxes_overlap(info.min, info.max, min, max) {
+                        callback(idx);
+                    }
+                }
+            },
+        );
+    }
+
+    fn for_each_segment_in_box(&self, path: u32, min: Point, max: Point, callback: &mut dyn FnMut(&IndexedSegment)) {
+        let root = match self.paths[path as usize].root {
+            Some(root) => root,
+            None => { return; }
+        };
+
+        traverse(
+            &self.segment_nodes, root,
+            &mut |node| boxes_overlap(node.min, node.max, min, max),
+            &mut |items| {
+                for segment in &self.segments[items] {
+                    callback(segment);
+                }
+            },
+        );
+    }
+
+    fn winding_number(&self, path: u32, position: Point) -> i32 {
+        let root = match self.paths[path as usize].root {
+            Some(root) => root,
+            None => { return 0; }
+        };
+
+        // Accumulate the winding of the edges intersecting the horizontal line passing
+        // through the position, left of it (see hit_test::path_winding_number_at_position).
+        let mut winding = 0;
+        traverse(
+            &self.segment_nodes, root,
+            &mut |node| node.min.y <= position.y && node.max.y >= position.y && node.min.x < position.x,
+            &mut |items| {
+                for segment in &self.segments[items] {
+                    hit_test::test_segment(position, &segment.segment, &mut winding);
+                }
+            },
+        );
+
+        winding
+    }
+}
+
+struct BuildItem {
+    min: Point,
+    max: Point,
+    center: Point,
+    index: u32,
+}
+
+impl BuildItem {
+    fn new(a: Point, b: Point, index: u32) -> Self {
+        let min = Point::min(a, b);
+        let max = Point::max(a, b);
+        BuildItem {
+            min,
+            max,
+            center: min.lerp(max, 0.5),
+            index,
+        }
+    }
+}
+
+// Builds a balanced hierarchy by recursively splitting the items at the median along
+// the largest axis of their centers, and returns the index of the root node.
+//
+// The items are reordered so that each leaf refers to a contiguous range of items,
+// starting at `offset`.
+fn build_tree(items: &mut [BuildItem], offset: u32, nodes: &mut Vec<Node>) -> u32 {
+    let root = nodes.len();
+    nodes.push(Node { min: point(0.0, 0.0), max: point(0.0, 0.0), first: 0, count: 0 });
+    build_node(items, offset, nodes, root);
+
+    root as u32
+}
+
+fn build_node(items: &mut [BuildItem], offset: u32, nodes: &mut Vec<Node>, node_idx: usize) {
+    debug_assert!(!items.is_empty());
+
+    let mut min = items[0].min;
+    let mut max = items[0].max;
+    let mut center_min = items[0].center;
+    let mut center_max = items[0].center;
+    for item in &items[1..] {
+        min = Point::min(min, item.min);
+        max = Point::max(max, item.max);
+        center_min = Point::min(center_min, item.center);
+        center_max = Point::max(center_max, item.center);
+    }
+
+    if items.len() <= LEAF_SIZE {
+        nodes[node_idx] = Node { min, max, first: offset, count: items.len() as u32 };
+        return;
+    }
+
+    let extent = center_max - center_min;
+    if extent.x > extent.y {
+        items.sort_unstable_by(|a, b| a.center.x.total_cmp(&b.center.x));
+    } else {
+        items.sort_unstable_by(|a, b| a.center.y.total_cmp(&b.center.y));
+    }
+
+    let mid = items.len() / 2;
+    let children = nodes.len();
+    nodes.push(Node { min, max, first: 0, count: 0 });
+    nodes.push(Node { min, max, first: 0, count: 0 });
+    nodes[node_idx] = Node { min, max, first: children as u32, count: 0 };
+
+    let (left, right) = items.split_at_mut(mid);
+    build_node(left, offset, nodes, children);
+    build_node(right, offset + mid as u32, nodes, children + 1);
+}
+
+// Depth-first traversal of the nodes for which `node_test` returns true, invoking `leaf`
+// with the item range of each visited leaf.
+fn traverse(
+    nodes: &[Node],
+    root: u32,
+    node_test: &mut dyn FnMut(&Node) -> bool,
+    leaf: &mut dyn FnMut(Range<usize>),
+) {
+    let mut stack = [0u32; MAX_DEPTH];
+    stack[0] = root;
+    let mut stack_len = 1;
+
+    while stack_len > 0 {
+        stack_len -= 1;
+        let node = &nodes[stack[stack_len] as usize];
+        if !node_test(node) {
+            continue;
+        }
+
+        if node.is_leaf() {
+            leaf(node.items());
+        } else {
+            stack[stack_len] = node.first + 1;
+            stack[stack_len + 1] = node.first;
+            stack_len += 2;
+        }
+    }
+}
+
+fn boxes_overlap(min1: Point, max1: Point, min2: Point, max2: Point) -> bool {
+    min1.x <= max2.x && max1.x >= min2.x && min1.y <= max2.y && max1.y >= min2.y
+}
+
+// Slab test, returns whether the ray enters the box before `max_t`.
+fn ray_box_intersection(origin: Point, inv_dir: Vector, min: Point, max: Point, max_t: f32) -> bool {
+    let tx1 = (min.x - origin.x) * inv_dir.x;
+    let tx2 = (max.x - origin.x) * inv_dir.x;
+    let ty1 = (min.y - origin.y) * inv_dir.y;
+    let ty2 = (max.y - origin.y) * inv_dir.y;
+
+    // If the ray is parallel to an axis, the products above can be NaN when the origin
+    // is on a side of the box. Treat these as a hit to stay conservative.
+    let (tx_min, tx_max) = if tx1.is_nan() || tx2.is_nan() {
+        (f32::MIN, f32::MAX)
+    } else {
+        (f32::min(tx1, tx2), f32::max(tx1, tx2))
+    };
+    let (ty_min, ty_max) = if ty1.is_nan() || ty2.is_nan() {
+        (f32::MIN, f32::MAX)
+    } else {
+        (f32::min(ty1, ty2), f32::max(ty1, ty2))
+    };
+
+    let t_enter = f32::max(tx_min, ty_min);
+    let t_exit = f32::min(tx_max, ty_max);
+
+    t_exit >= f32::max(t_enter, 0.0) && t_enter <= max_t
+}
+
+// Returns the ray parameter of the intersection, if any, such that the point of contact
+// is `ray.origin + ray.direction * t`.
+pub(crate) fn ray_segment_intersection(ray: &Ray, segment: &LineSegment<f32>) -> Option<f32> {
+    let e = segment.to_vector();
+    let denom = ray.direction.cross(e);
+    if denom == 0.0 {
+        return None;
+    }
+
+    let ao = segment.from - ray.origin;
+    let t = ao.cross(e) / denom;
+    let s = ao.cross(ray.direction) / denom;
+
+    if t < 0.0 || !(0.0..=1.0).contains(&s) {
+        return None;
+    }
+
+    Some(t)
+}
+
+// Liang-Barsky clipping of the segment against the box.
+fn segment_intersects_box(segment: &LineSegment<f32>, min: Point, max: Point) -> bool {
+    let d = segment.to_vector();
+    let mut t0 = 0.0;
+    let mut t1 = 1.0;
+
+    let checks = [
+        (-d.x, segment.from.x - min.x),
+        (d.x, max.x - segment.from.x),
+        (-d.y, segment.from.y - min.y),
+        (d.y, max.y - segment.from.y),
+    ];
+
+    for &(p, q) in &checks {
+        if p == 0.0 {
+            if q < 0.0 {
+                return false;
+            }
+            continue;
+        }
+
+        let r = q / p;
+        if p < 0.0 {
+            if r > t1 {
+                return false;
+            }
+            if r > t0 {
+                t0 = r;
+            }
+        } else {
+            if r < t0 {
+                return false;
+            }
+            if r < t1 {
+                t1 = r;
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+use crate::square;
+
+#[cfg(test)]
+fn grid_index() -> (SpatialIndex, Vec<crate::path::Path>) {
+    use crate::path::Path;
+
+    // A 20x20 grid of 8x8 squares with a 2 units gap between them.
+    let mut paths = Vec::new();
+    let mut builder = SpatialIndex::builder(0.01);
+    for y in 0..20 {
+        for x in 0..20 {
+            let mut path = Path::builder();
+            square(&mut path, x as f32 * 10.0, y as f32 * 10.0, 8.0);
+            let path = path.build();
+            let id = builder.add_path(path.iter());
+            assert_eq!(id, PathId(paths.len() as u32));
+            paths.push(path);
+        }
+    }
+
+    (builder.build(), paths)
+}
+
+#[test]
+fn spatial_index_hit_test() {
+    use crate::hit_test::{hit_test_path, path_winding_number_at_position};
+
+    let (index, paths) = grid_index();
+    assert_eq!(index.num_paths(), 400);
+    assert_eq!(index.num_segments(), 1600);
+
+    let positions = [
+        point(5.0, 5.0), point(9.0, 5.0), point(15.5, 31.0), point(-1.0, 3.0),
+        point(199.0, 199.0), point(197.0, 197.0), point(42.0, 87.0), point(300.0, 2.0),
+    ];
+
+    for position in &positions {
+        let mut hits = Vec::new();
+        index.hit_test(*position, FillRule::EvenOdd, &mut |hit| { hits.push(hit.path); });
+
+        let mut expected = Vec::new();
+        for (idx, path) in paths.iter().enumerate() {
+            if hit_test_path(position, path.iter(), FillRule::EvenOdd, 0.01) {
+                expected.push(PathId(idx as u32));
+            }
+        }
+
+        assert_eq!(hits, expected);
+    }
+
+    let mut hits = Vec::new();
+    index.hit_test(point(45.0, 35.0), FillRule::NonZero, &mut |hit| { hits.push(hit); });
+    assert_eq!(hits, vec![PointHit { path: PathId(64), winding: -1 }]);
+    assert_eq!(
+        index.winding_number_at_position(PathId(64), point(45.0, 35.0)),
+        path_winding_number_at_position(&point(45.0, 35.0), paths[64].iter(), 0.01),
+    );
+}
+
+#[test]
+fn spatial_index_holes() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 0.0, 10.0);
+    square(&mut builder, 2.0, 2.0, 6.0);
+    let path = builder.build();
+
+    let mut index = SpatialIndex::builder(0.01);
+    index.add_path(path.iter());
+    let index = index.build();
+
+    let mut hits = 0;
+    index.hit_test(point(5.0, 5.0), FillRule::EvenOdd, &mut |_| { hits += 1; });
+    assert_eq!(hits, 0);
+    index.hit_test(point(5.0, 5.0), FillRule::NonZero, &mut |_| { hits += 1; });
+    assert_eq!(hits, 1);
+    index.hit_test(point(1.0, 5.0), FillRule::EvenOdd, &mut |_| { hits += 1; });
+    assert_eq!(hits, 2);
+}
+
+#[test]
+fn spatial_index_nan() {
+    use crate::path::Path;
+    use crate::geom::LineSegment;
+
+    // NaN coordinates don't make the sort panic. The path builder rejects them in
+    // debug builds so the events are provided directly.
+    let points = [
+        point(0.0, 0.0),
+        point(f32::NAN, f32::NAN),
+        point(f32::NAN, f32::NAN),
+        point(0.0, 4.0),
+        point(2.0, 4.0),
+        point(2.0, 0.0),
+    ];
+    let mut events = vec![PathEvent::MoveTo(points[0])];
+    for i in 1..points.len() {
+        events.push(PathEvent::Line(LineSegment { from: points[i - 1], to: points[i] }));
+    }
+    events.push(PathEvent::Close(LineSegment { from: points[5], to: points[0] }));
+
+    let mut builder = SpatialIndex::builder(0.01);
+    for i in 0..4 {
+        let mut path = Path::builder();
+        square(&mut path, i as f32 * 10.0, 0.0, 8.0);
+        builder.add_path(path.build().iter());
+    }
+    builder.add_path(events.into_iter());
+    let index = builder.build();
+
+    let mut hits = Vec::new();
+    index.hit_test(point(34.0, 4.0), FillRule::EvenOdd, &mut |hit| { hits.push(hit.path); });
+    assert_eq!(hits, vec![PathId(3)]);
+}
+
+#[test]
+fn spatial_index_rect_query() {
+    use crate::math::rect;
+
+    let (index, _) = grid_index();
+
+    // Only overlaps the outline of the square at (10, 10).
+    let mut segments = Vec::new();
+    index.segments_in_rect(&rect(17.0, 12.0, 2.0, 2.0), &mut |s| { segments.push((s.path, s.edge)); });
+    assert_eq!(segments, vec![(PathId(21), 1)]);
+
+    // In the gap between squares.
+    let mut paths = Vec::new();
+    index.paths_in_rect(&rect(18.5, 10.0, 1.0, 30.0), FillRule::EvenOdd, &mut |id| { paths.push(id); });
+    assert!(paths.is_empty());
+
+    // Inside of a square, not touching its outline.
+    index.paths_in_rect(&rect(31.0, 31.0, 1.0, 1.0), FillRule::EvenOdd, &mut |id| { paths.push(id); });
+    assert_eq!(paths, vec![PathId(63)]);
+
+    // Covering four squares.
+    paths.clear();
+    index.paths_in_rect(&rect(5.0, 5.0, 10.0, 10.0), FillRule::EvenOdd, &mut |id| { paths.push(id); });
+    paths.sort();
+    assert_eq!(paths, vec![PathId(0), PathId(1), PathId(20), PathId(21)]);
+}
+
+#[test]
+fn spatial_index_raycast() {
+    use crate::raycast::raycast_path;
+    use crate::geom::euclid::approxeq::ApproxEq;
+
+    let (index, paths) = grid_index();
+
+    let rays = [
+        Ray { origin: point(-5.0, 3.0), direction: vector(1.0, 0.0) },
+        Ray { origin: point(-5.0, 9.0), direction: vector(1.0, 0.0) },
+        Ray { origin: point(55.0, 55.0), direction: vector(-1.0, 0.0) },
+        Ray { origin: point(55.0, 55.0), direction: vector(0.0, 3.0) },
+        Ray { origin: point(-3.0, -1.0), direction: vector(1.0, 1.0) },
+        Ray { origin: point(250.0, 43.0), direction: vector(-2.0, 0.1) },
+        Ray { origin: point(250.0, 43.0), direction: vector(2.0, 0.1) },
+    ];
+
+    for ray in &rays {
+        let hit = index.raycast(ray);
+
+        let mut expected: Option<(PathId, Point)> = None;
+        let mut expected_dist = f32::MAX;
+        for (idx, path) in paths.iter().enumerate() {
+            if let Some(hit) = raycast_path(ray, path.iter(), 0.01) {
+                let d = (hit.position - ray.origin).length();
+                if d < expected_dist {
+                    expected_dist = d;
+                    expected = Some((PathId(idx as u32), hit.position));
+                }
+            }
+        }
+
+        match (hit, expected) {
+            (Some(hit), Some((id, position))) => {
+                assert_eq!(hit.path, id);
+                assert!(hit.position.approx_eq(&position));
+                assert!((hit.distance - expected_dist).abs() < 0.001);
+                assert!(hit.normal.dot(ray.direction) <= 0.0);
+            }
+            (None, None) => {}
+            _ => { panic!("{:?} / {:?}", hit, expected); }
+        }
+    }
+
+    let hit = index.raycast(&Ray { origin: point(-5.0, 3.0), direction: vector(2.0, 0.0) }).unwrap();
+    assert_eq!(hit.path, PathId(0));
+    assert_eq!(hit.edge, 3);
+    assert!(hit.position.approx_eq(&point(0.0, 3.0)));
+    assert!(hit.normal.approx_eq(&vector(-1.0, 0.0)));
+    assert!(hit.distance.approx_eq(&5.0));
+}
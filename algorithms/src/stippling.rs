@@ -0,0 +1,585 @@
+//! Fill a path with a blue noise distribution of dots (stippling).
+//!
+//! The dots are placed using Poisson-disk sampling: they are randomly distributed,
+//! but no two dots are closer than a minimum distance. Unlike the grid patterns of
+//! `Hatcher::dot_path`, this doesn't produce visible regular structures such as
+//! moiré patterns.
+//!
+//! The distribution is deterministic: a given path, set of options and seed always
+//! produce the same dots.
+//!
+//! An optional density function can modulate the spacing between the dots: a density
+//! of `1.0` corresponds to the minimum distance, a density of `0.25` to twice the
+//! minimum distance, and no dot is placed where the density is zero.
+//!
+//! # Examples
+//!
+//! ```
+//! use lyon_algorithms::stippling::{Stippler, StipplingOptions};
+//! use lyon_algorithms::path::Path;
+//! use lyon_algorithms::math::point;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let mut dots = Vec::new();
+//! let mut stippler = Stippler::new();
+//! stippler.stipple_path(
+//!     path.iter(),
+//!     &StipplingOptions::min_distance(0.5).with_seed(42),
+//!     &mut |position| { dots.push(position); },
+//! );
+//! ```
+
+use crate::path::{PathEvent, FillRule};
+use crate::hit_test::test_segment;
+use crate::geom::LineSegment;
+use crate::math::{Point, point, vector};
+
+use std::f32;
+use std::cmp::Ordering;
+
+/// Parameters for the stippler.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StipplingOptions {
+    /// Maximum allowed distance to the path when building an approximation.
+    ///
+    /// See [Flattening and tolerance](index.html#flattening-and-tolerance).
+    ///
+    /// Default value: `StipplingOptions::DEFAULT_TOLERANCE`.
+    pub tolerance: f32,
+    /// The fill rule used to determine whether a dot is inside of the path.
+    ///
+    /// Default value: `FillRule::EvenOdd`.
+    pub fill_rule: FillRule,
+    /// Minimum distance between two dots where the density is `1.0`.
+    ///
+    /// No dots are generated if the distance is so small compared to the size of the
+    /// path that the acceleration grid would have more than 2^24 cells.
+    ///
+    /// Default value: `StipplingOptions::DEFAULT_MIN_DISTANCE`.
+    pub min_distance: f32,
+    /// Seed of the pseudo-random number generator.
+    ///
+    /// Default value: `0`.
+    pub seed: u64,
+    /// Number of candidates generated around each dot before giving up on placing
+    /// new dots in its neighborhood.
+    ///
+    /// Higher values produce tighter distributions at the expense of speed.
+    ///
+    /// Default value: `StipplingOptions::DEFAULT_MAX_ATTEMPTS`.
+    pub max_attempts: u32,
+
+    // To be able to add fields without making it a breaking change, add an empty private field
+    // which makes it impossible to create a StipplingOptions without calling the constructor.
+    _private: (),
+}
+
+impl Default for StipplingOptions {
+    fn default() -> Self { Self::DEFAULT }
+}
+
+impl StipplingOptions {
+    /// Default flattening tolerance.
+    pub const DEFAULT_TOLERANCE: f32 = 0.1;
+    /// Default minimum distance between the dots.
+    pub const DEFAULT_MIN_DISTANCE: f32 = 1.0;
+    /// Default number of candidates per dot.
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 30;
+
+    pub const DEFAULT: Self = StipplingOptions {
+        tolerance: Self::DEFAULT_TOLERANCE,
+        fill_rule: FillRule::EvenOdd,
+        min_distance: Self::DEFAULT_MIN_DISTANCE,
+        seed: 0,
+        max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+        _private: (),
+    };
+
+    #[inline]
+    pub fn tolerance(tolerance: f32) -> Self {
+        Self::DEFAULT.with_tolerance(tolerance)
+    }
+
+    #[inline]
+    pub fn min_distance(min_distance: f32) -> Self {
+        Self::DEFAULT.with_min_distance(min_distance)
+    }
+
+    #[inline]
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    #[inline]
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    #[inline]
+    pub fn with_min_distance(mut self, min_distance: f32) -> Self {
+        self.min_distance = min_distance;
+        self
+    }
+
+    #[inline]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+}
+
+// Densities below this value are considered to be zero, which avoids searching
+// enormous neighborhoods for very sparse dots.
+const MIN_DENSITY: f32 = 1.0 / 256.0;
+
+const EMPTY_CELL: u32 = u32::MAX;
+
+// Maximum number of cells of the acceleration grid.
+const MAX_GRID_CELLS: usize = 1 << 24;
+
+struct Dot {
+    position: Point,
+    radius: f32,
+}
+
+/// A context object that can fill a path with a blue noise distribution of dots.
+pub struct Stippler {
+    // The flattened edges of the path, sorted by their minimum y coordinate.
+    edges: Vec<LineSegment<f32>>,
+    dots: Vec<Dot>,
+    active: Vec<u32>,
+    grid: Vec<u32>,
+}
+
+impl Stippler {
+    /// Constructor.
+    pub fn new() -> Self {
+        Stippler {
+            edges: Vec::new(),
+            dots: Vec::new(),
+            active: Vec::new(),
+            grid: Vec::new(),
+        }
+    }
+
+    /// Generate dots with a uniform density for a path.
+    pub fn stipple_path<Iter>(
+        &mut self,
+        it: Iter,
+        options: &StipplingOptions,
+        output: &mut dyn FnMut(Point),
+    )
+    where
+        Iter: Iterator<Item = PathEvent>,
+    {
+        self.stipple_path_with_density(it, options, &mut |_| 1.0, output);
+    }
+
+    /// Generate dots for a path, with a spacing that depends on a density function.
+    ///
+    /// The density is expected to be between `0.0` and `1.0`. The distance between the
+    /// dots around a given position is `options.min_distance / density.sqrt()`, so that
+    /// the number of dots per unit of area is roughly proportional to the density.
+    pub fn stipple_path_with_density<Iter>(
+        &mut self,
+        it: Iter,
+        options: &StipplingOptions,
+        density: &mut dyn FnMut(Point) -> f32,
+        output: &mut dyn FnMut(Point),
+    )
+    where
+        Iter: Iterator<Item = PathEvent>,
+    {
+        self.set_path(it, options.tolerance);
+        self.dots.clear();
+        self.active.clear();
+        self.grid.clear();
+
+        if self.edges.is_empty() || options.min_distance.is_nan() || options.min_distance <= 0.0 {
+            return;
+        }
+
+        let mut min = point(f32::MAX, f32::MAX);
+        let mut max = point(f32::MIN, f32::MIN);
+        for edge in &self.edges {
+            min = min.min(edge.from).min(edge.to);
+            max = max.max(edge.from).max(edge.to);
+        }
+
+        // With a cell size of r / sqrt(2) a cell contains at most one dot.
+        let cell_size = options.min_distance * f32::consts::FRAC_1_SQRT_2;
+        let grid = Grid {
+            origin: min,
+            cell_size,
+            columns: ((max.x - min.x) / cell_size).min(MAX_GRID_CELLS as f32) as usize + 1,
+            rows: ((max.y - min.y) / cell_size).min(MAX_GRID_CELLS as f32) as usize + 1,
+        };
+        let num_cells = match grid.columns.checked_mul(grid.rows) {
+            Some(num_cells) if num_cells <= MAX_GRID_CELLS => num_cells,
+            _ => { return; }
+        };
+        self.grid.resize(num_cells, EMPTY_CELL);
+
+        let mut rng = Rng::new(options.seed);
+        let mut max_radius = options.min_distance;
+
+        // Scanning the cells makes sure that all disjoint parts of the path get some
+        // dots, the rest of the dots are placed around the existing ones.
+        for cell_y in 0..grid.rows {
+            for cell_x in 0..grid.columns {
+                if self.grid[cell_y * grid.columns + cell_x] != EMPTY_CELL {
+                    continue;
+                }
+
+                let candidate = point(
+                    grid.origin.x + (cell_x as f32 + rng.next_f32()) * cell_size,
+                    grid.origin.y + (cell_y as f32 + rng.next_f32()) * cell_size,
+                );
+                if let Some(r) = self.try_add_dot(candidate, &grid, options, max_radius, density, output) {
+                    max_radius = f32::max(max_radius, r);
+                }
+
+                while let Some(idx) = self.pop_active(&mut rng) {
+                    let center = self.dots[idx as usize].position;
+                    let radius = self.dots[idx as usize].radius;
+                    let mut found = false;
+                    for _ in 0..options.max_attempts {
+                        let angle = rng.next_f32() * 2.0 * f32::consts::PI;
+                        let distance = radius * (1.0 + rng.next_f32());
+                        let candidate = center + vector(angle.cos(), angle.sin()) * distance;
+                        if let Some(r) = self.try_add_dot(candidate, &grid, options, max_radius, density, output) {
+                            max_radius = f32::max(max_radius, r);
+                            found = true;
+                            break;
+                        }
+                    }
+                    if found {
+                        self.active.push(idx);
+                    }
+                }
+            }
+        }
+    }
+
+    // Removes a random dot from the active list.
+    fn pop_active(&mut self, rng: &mut Rng) -> Option<u32> {
+        if self.active.is_empty() {
+            return None;
+        }
+
+        let idx = rng.next_u32() as usize % self.active.len();
+        Some(self.active.swap_remove(idx))
+    }
+
+    // Adds a dot at the candidate position if it is inside of the path and far enough
+    // from the other dots, returning its radius.
+    fn try_add_dot(
+        &mut self,
+        candidate: Point,
+        grid: &Grid,
+        options: &StipplingOptions,
+        max_radius: f32,
+        density: &mut dyn FnMut(Point) -> f32,
+        output: &mut dyn FnMut(Point),
+    ) -> Option<f32> {
+        let (cell_x, cell_y) = grid.cell(candidate)?;
+        if self.grid[cell_y * grid.columns + cell_x] != EMPTY_CELL {
+            return None;
+        }
+
+        if !self.is_inside(candidate, options.fill_rule) {
+            return None;
+        }
+
+        let d = density(candidate);
+        if d.is_nan() || d < MIN_DENSITY {
+            return None;
+        }
+        let radius = options.min_distance / d.min(1.0).sqrt();
+
+        // Look for dots that are too close, taking into account that their radius
+        // can be larger than the candidate's.
+        let search_radius = f32::max(radius, max_radius);
+        let range = (search_radius / grid.cell_size).ceil() as isize;
+        for y in (cell_y as isize - range)..=(cell_y as isize + range) {
+            if y < 0 || y >= grid.rows as isize {
+                continue;
+            }
+            for x in (cell_x as isize - range)..=(cell_x as isize + range) {
+                if x < 0 || x >= grid.columns as isize {
+                    continue;
+                }
+                let idx = self.grid[y as usize * grid.columns + x as usize];
+                if idx == EMPTY_CELL {
+                    continue;
+                }
+                let dot = &self.dots[idx as usize];
+                let min_distance = f32::max(radius, dot.radius);
+                if (dot.position - candidate).square_length() < min_distance * min_distance {
+                    return None;
+                }
+            }
+        }
+
+        let idx = self.dots.len() as u32;
+        self.dots.push(Dot { position: candidate, radius });
+        self.active.push(idx);
+        self.grid[cell_y * grid.columns + cell_x] = idx;
+        output(candidate);
+
+        Some(radius)
+    }
+
+    fn is_inside(&self, position: Point, fill_rule: FillRule) -> bool {
+        let mut winding = 0;
+        for edge in &self.edges {
+            if f32::min(edge.from.y, edge.to.y) > position.y {
+                break;
+            }
+            test_segment(position, edge, &mut winding);
+        }
+
+        match fill_rule {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+
+    fn set_path<Iter>(&mut self, it: Iter, tolerance: f32)
+    where
+        Iter: Iterator<Item = PathEvent>,
+    {
+        self.edges.clear();
+
+        let mut first = point(0.0, 0.0);
+        let mut last = first;
+        for evt in it {
+            match evt {
+                PathEvent::MoveTo(to) => {
+                    // Sub-paths are implicitly closed.
+                    self.add_edge(last, first);
+                    first = to;
+                    last = to;
+                }
+                PathEvent::Line(segment) => {
+                    self.add_edge(segment.from, segment.to);
+                    last = segment.to;
+                }
+                PathEvent::Quadratic(segment) => {
+                    let mut prev = segment.from;
+                    let edges = &mut self.edges;
+                    segment.for_each_flattened(tolerance, &mut |p| {
+                        edges.push(LineSegment { from: prev, to: p });
+                        prev = p;
+                    });
+                    last = segment.to;
+                }
+                PathEvent::Cubic(segment) => {
+                    let mut prev = segment.from;
+                    let edges = &mut self.edges;
+                    segment.for_each_flattened(tolerance, &mut |p| {
+                        edges.push(LineSegment { from: prev, to: p });
+                        prev = p;
+                    });
+                    last = segment.to;
+                }
+                PathEvent::Conic(segment) => {
+                    let mut prev = segment.from;
+                    let edges = &mut self.edges;
+                    segment.for_each_flattened(tolerance, &mut |p| {
+                        edges.push(LineSegment { from: prev, to: p });
+                        prev = p;
+                    });
+                    last = segment.to;
+                }
+                PathEvent::Close(segment) => {
+                    self.add_edge(segment.from, segment.to);
+                    last = first;
+                }
+            }
+        }
+        self.add_edge(last, first);
+
+        self.edges.sort_by(|a, b| {
+            f32::min(a.from.y, a.to.y)
+                .partial_cmp(&f32::min(b.from.y, b.to.y))
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+
+    fn add_edge(&mut self, from: Point, to: Point) {
+        if from != to {
+            self.edges.push(LineSegment { from, to });
+        }
+    }
+}
+
+impl Default for Stippler {
+    fn default() -> Self { Self::new() }
+}
+
+struct Grid {
+    origin: Point,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+}
+
+impl Grid {
+    fn cell(&self, position: Point) -> Option<(usize, usize)> {
+        let x = (position.x - self.origin.x) / self.cell_size;
+        let y = (position.y - self.origin.y) / self.cell_size;
+        if x.is_nan() || y.is_nan() || x < 0.0 || y < 0.0 {
+            return None;
+        }
+
+        let (x, y) = (x as usize, y as usize);
+        if x >= self.columns || y >= self.rows {
+            return None;
+        }
+
+        Some((x, y))
+    }
+}
+
+// A small pseudo-random number generator (xorshift64*), so that the distribution
+// only depends on the seed.
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Scramble the seed (splitmix64) so that nearby seeds produce unrelated
+        // sequences. The state must not be zero.
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        Rng { state: if z == 0 { 1 } else { z } }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    // Returns a number in [0, 1).
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+fn stipple(path: &crate::path::Path, options: &StipplingOptions, density: &mut dyn FnMut(Point) -> f32) -> Vec<Point> {
+    let mut dots = Vec::new();
+    Stippler::new().stipple_path_with_density(
+        path.iter(),
+        options,
+        density,
+        &mut |p| { dots.push(p); },
+    );
+
+    dots
+}
+
+#[cfg(test)]
+fn square_with_hole() -> crate::path::Path {
+    let mut builder = crate::path::Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(3.0, 3.0));
+    builder.line_to(point(7.0, 3.0));
+    builder.line_to(point(7.0, 7.0));
+    builder.line_to(point(3.0, 7.0));
+    builder.close();
+
+    builder.build()
+}
+
+#[test]
+fn stippling_min_distance() {
+    let path = square_with_hole();
+    let dots = stipple(&path, &StipplingOptions::min_distance(0.5), &mut |_| 1.0);
+
+    // The area is 84 and each dot covers at most r² * sqrt(3) / 2.
+    assert!(dots.len() > 150, "{}", dots.len());
+    assert!(dots.len() < 390, "{}", dots.len());
+
+    for (i, a) in dots.iter().enumerate() {
+        assert!(a.x >= 0.0 && a.x <= 10.0 && a.y >= 0.0 && a.y <= 10.0, "{:?}", a);
+        assert!(!(a.x > 3.0 && a.x < 7.0 && a.y > 3.0 && a.y < 7.0), "{:?}", a);
+        for b in &dots[i + 1..] {
+            assert!((*a - *b).length() >= 0.5, "{:?} {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn stippling_seed() {
+    let path = square_with_hole();
+    let options = StipplingOptions::min_distance(0.5);
+
+    let a = stipple(&path, &options.with_seed(1), &mut |_| 1.0);
+    let b = stipple(&path, &options.with_seed(1), &mut |_| 1.0);
+    let c = stipple(&path, &options.with_seed(2), &mut |_| 1.0);
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn stippling_density() {
+    let path = square_with_hole();
+    let dots = stipple(
+        &path,
+        &StipplingOptions::min_distance(0.5),
+        &mut |p| if p.x < 5.0 { 1.0 } else if p.y < 5.0 { 0.25 } else { 0.0 },
+    );
+
+    let left: Vec<Point> = dots.iter().cloned().filter(|p| p.x < 5.0).collect();
+    let right: Vec<Point> = dots.iter().cloned().filter(|p| p.x >= 5.0).collect();
+
+    assert!(dots.iter().all(|p| p.x < 5.0 || p.y < 5.0));
+    // The right side has a quarter of the density and half of the area.
+    assert!(right.len() * 4 < left.len(), "{} {}", left.len(), right.len());
+
+    for (i, a) in right.iter().enumerate() {
+        for b in &right[i + 1..] {
+            assert!((*a - *b).length() >= 1.0, "{:?} {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn stippling_large_grid() {
+    // The grid would have far too many cells.
+    let path = square_with_hole();
+    let dots = stipple(&path, &StipplingOptions::min_distance(1.0e-6), &mut |_| 1.0);
+    assert!(dots.is_empty());
+}
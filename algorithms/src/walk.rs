@@ -9,6 +9,9 @@
 //! sizes), the path walker makes it possible to advance by a certain
 //! distance along the path.
 //!
+//! `layout_along_path` builds on top of the path walker to place a sequence
+//! of items of varying sizes (for example glyphs) along a path.
+//!
 //! ## Example
 //!
 //! ```
@@ -233,6 +236,245 @@ where Cb: FnMut(Point, Vector, f32) -> Option<f32> {
     }
 }
 
+/// A pattern that places a sequence of items of varying sizes along the path,
+/// for example the glyphs of a string of text.
+///
+/// Each item is centered on the path: the walk must start at the distance of the
+/// center of the item at `index`, and the pattern then advances by half of the
+/// current item plus half of the next one.
+///
+/// For each item, the callback receives its index and a transform from the item's
+/// local space to the path's space. In the local space, the item starts at the
+/// origin and its advance goes along the positive x axis. If the callback returns
+/// false, path walking stops.
+///
+/// See also `layout_along_path` which handles alignment and overflow.
+pub struct AdvancePattern<'l, Cb> {
+    /// The function to call for each item.
+    pub callback: Cb,
+    /// The advance (width) of each item.
+    pub advances: &'l[f32],
+    /// A uniform scale applied to the items and their advances.
+    pub scale: f32,
+    /// The index of the next item in the sequence.
+    pub index: usize,
+}
+
+impl<'l, Cb> Pattern for AdvancePattern<'l, Cb>
+where Cb: FnMut(usize, &Transform2D) -> bool {
+    #[inline]
+    fn next(&mut self, position: Point, tangent: Vector, _distance: f32) -> Option<f32> {
+        let idx = self.index;
+        if idx >= self.advances.len() {
+            return None;
+        }
+
+        let transform = item_transform(self.advances[idx], self.scale, position, tangent);
+        if !(self.callback)(idx, &transform) {
+            return None;
+        }
+
+        self.index += 1;
+        if self.index >= self.advances.len() {
+            return None;
+        }
+
+        Some((self.advances[idx] + self.advances[idx + 1]) * 0.5 * self.scale)
+    }
+}
+
+/// Where to place a sequence of items along a path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// The first item starts at the beginning of the path.
+    Start,
+    /// The items are centered on the middle of the path.
+    Middle,
+    /// The last item ends at the end of the path.
+    End,
+}
+
+/// What to do with items that don't fit on the path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Items that extend beyond the beginning or the end of the path are skipped.
+    Truncate,
+    /// Items beyond the ends of the path are placed on the straight lines that
+    /// extend the path along its tangent at each end.
+    Extend,
+    /// The items and their advances are uniformly scaled down so that they fit on
+    /// the path.
+    Fit,
+}
+
+/// Lays out a sequence of items of varying advances along a path, for example the
+/// glyphs of a string of text.
+///
+/// The callback receives the index of each item and a transform from the item's local
+/// space to the path's space (see `AdvancePattern`). The items are always laid out
+/// in order. If the callback returns false, the layout stops.
+///
+/// The path is traversed twice, its events are buffered internally.
+pub fn layout_along_path<Iter>(
+    path: Iter,
+    advances: &[f32],
+    alignment: Alignment,
+    overflow: Overflow,
+    callback: &mut dyn FnMut(usize, &Transform2D) -> bool,
+)
+where
+    Iter: Iterator<Item=FlattenedEvent>,
+{
+    let events: Vec<FlattenedEvent> = path.collect();
+    let ends = match PathEnds::measure(events.iter().cloned()) {
+        Some(ends) => ends,
+        None => { return; }
+    };
+
+    let length = ends.length;
+    let total: f32 = advances.iter().sum();
+    let scale = if overflow == Overflow::Fit && total > length && total > 0.0 {
+        length / total
+    } else {
+        1.0
+    };
+
+    let start = match alignment {
+        Alignment::Start => 0.0,
+        Alignment::Middle => (length - total * scale) * 0.5,
+        Alignment::End => length - total * scale,
+    };
+
+    // Allow some imprecision for items that fit exactly.
+    let epsilon = length * 1e-5;
+
+    let mut item_start = start;
+    let mut placements = Vec::with_capacity(advances.len());
+    for advance in advances {
+        let item_end = item_start + advance * scale;
+        let fits = item_start >= -epsilon && item_end <= length + epsilon;
+        if fits || overflow == Overflow::Extend {
+            placements.push((item_start + item_end) * 0.5);
+        } else {
+            placements.push(f32::NAN);
+        }
+        item_start = item_end;
+    }
+
+    let mut stopped = false;
+    let mut idx = 0;
+
+    // Before the beginning of the path.
+    while idx < advances.len() && (placements[idx].is_nan() || placements[idx] < 0.0) {
+        if placements[idx] < 0.0 {
+            let position = ends.first_position + ends.first_tangent * placements[idx];
+            let transform = item_transform(advances[idx], scale, position, ends.first_tangent);
+            if !callback(idx, &transform) {
+                return;
+            }
+        }
+        idx += 1;
+    }
+
+    // Along the path.
+    let mut last = idx;
+    while last < advances.len() && placements[last] <= length {
+        last += 1;
+    }
+
+    if idx < last {
+        let mut pattern = AdvancePattern {
+            callback: |i, transform: &Transform2D| {
+                stopped = !callback(i, transform);
+                !stopped
+            },
+            advances: &advances[..last],
+            scale,
+            index: idx,
+        };
+        walk_along_path(events.into_iter(), placements[idx], &mut pattern);
+        idx = pattern.index;
+    }
+
+    if stopped {
+        return;
+    }
+
+    // After the end of the path, including the items that the walker could not reach
+    // because of floating point imprecision.
+    while idx < advances.len() {
+        if !placements[idx].is_nan() {
+            let distance = f32::max(placements[idx] - length, 0.0);
+            let position = ends.last_position + ends.last_tangent * distance;
+            let transform = item_transform(advances[idx], scale, position, ends.last_tangent);
+            if !callback(idx, &transform) {
+                return;
+            }
+        }
+        idx += 1;
+    }
+}
+
+fn item_transform(advance: f32, scale: f32, position: Point, tangent: Vector) -> Transform2D {
+    // The local x axis follows the tangent and the item's center is at the position.
+    let x = tangent * scale;
+    let origin = position - x * (advance * 0.5);
+    Transform2D::row_major(
+        x.x, x.y,
+        -x.y, x.x,
+        origin.x, origin.y,
+    )
+}
+
+// The length of a flattened path and the positions and tangents at both of its ends.
+struct PathEnds {
+    length: f32,
+    first_position: Point,
+    first_tangent: Vector,
+    last_position: Point,
+    last_tangent: Vector,
+}
+
+impl PathEnds {
+    fn measure<Iter>(path: Iter) -> Option<Self>
+    where
+        Iter: Iterator<Item=FlattenedEvent>,
+    {
+        let mut length = 0.0;
+        let mut first: Option<(Point, Vector)> = None;
+        let mut last = None;
+        for evt in path {
+            let segment = match evt {
+                FlattenedEvent::MoveTo(..) => { continue; }
+                FlattenedEvent::Line(segment) | FlattenedEvent::Close(segment) => segment,
+            };
+
+            let d = segment.length();
+            if d < 1e-5 {
+                continue;
+            }
+
+            let tangent = segment.to_vector() / d;
+            length += d;
+            if first.is_none() {
+                first = Some((segment.from, tangent));
+            }
+            last = Some((segment.to, tangent));
+        }
+
+        let (first_position, first_tangent) = first?;
+        let (last_position, last_tangent) = last?;
+
+        Some(PathEnds {
+            length,
+            first_position,
+            first_tangent,
+            last_position,
+            last_tangent,
+        })
+    }
+}
+
 #[test]
 fn walk_square() {
     let expected = [
@@ -317,3 +559,110 @@ fn walk_starting_after() {
     walker.move_to(point(0.0, 0.0));
     walker.line_to(point(5.0, 0.0));
 }
+
+#[cfg(test)]
+fn layout_positions(
+    path: &crate::path::Path,
+    advances: &[f32],
+    alignment: Alignment,
+    overflow: Overflow,
+) -> Vec<(usize, Point, Vector)> {
+    use crate::path::iterator::PathIterator;
+
+    let mut items = Vec::new();
+    layout_along_path(
+        path.iter().flattened(0.01),
+        advances,
+        alignment,
+        overflow,
+        &mut |idx, transform| {
+            // The center of the item on the baseline and its direction.
+            let center = transform.transform_point(point(advances[idx] * 0.5, 0.0));
+            let direction = transform.transform_vector(vector(1.0, 0.0));
+            items.push((idx, center, direction));
+            true
+        },
+    );
+
+    items
+}
+
+#[test]
+fn layout_along_path_alignment() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    let path = builder.build();
+
+    let advances = [2.0, 1.0, 3.0];
+
+    let check = |items: &[(usize, Point, Vector)], expected: &[(usize, Point, Vector)]| {
+        assert_eq!(items.len(), expected.len(), "{:?}", items);
+        for (item, expected) in items.iter().zip(expected.iter()) {
+            assert_eq!(item.0, expected.0);
+            assert!((item.1 - expected.1).length() < 1e-4, "{:?} {:?}", item, expected);
+            assert!((item.2 - expected.2).length() < 1e-4, "{:?} {:?}", item, expected);
+        }
+    };
+
+    check(
+        &layout_positions(&path, &advances, Alignment::Start, Overflow::Truncate),
+        &[
+            (0, point(1.0, 0.0), vector(1.0, 0.0)),
+            (1, point(2.5, 0.0), vector(1.0, 0.0)),
+            (2, point(4.5, 0.0), vector(1.0, 0.0)),
+        ],
+    );
+
+    check(
+        &layout_positions(&path, &advances, Alignment::Middle, Overflow::Truncate),
+        &[
+            (0, point(8.0, 0.0), vector(1.0, 0.0)),
+            (1, point(9.5, 0.0), vector(1.0, 0.0)),
+            (2, point(10.0, 1.5), vector(0.0, 1.0)),
+        ],
+    );
+
+    check(
+        &layout_positions(&path, &advances, Alignment::End, Overflow::Truncate),
+        &[
+            (0, point(10.0, 5.0), vector(0.0, 1.0)),
+            (1, point(10.0, 6.5), vector(0.0, 1.0)),
+            (2, point(10.0, 8.5), vector(0.0, 1.0)),
+        ],
+    );
+}
+
+#[test]
+fn layout_along_path_overflow() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(4.0, 0.0));
+    let path = builder.build();
+
+    let advances = [2.0, 2.0, 2.0, 2.0];
+
+    let truncated = layout_positions(&path, &advances, Alignment::Middle, Overflow::Truncate);
+    let indices: Vec<usize> = truncated.iter().map(|item| item.0).collect();
+    assert_eq!(indices, vec![1, 2]);
+
+    let extended = layout_positions(&path, &advances, Alignment::Middle, Overflow::Extend);
+    let positions: Vec<Point> = extended.iter().map(|item| item.1).collect();
+    assert_eq!(positions.len(), 4);
+    for (position, expected) in positions.iter().zip([-1.0, 1.0, 3.0, 5.0].iter()) {
+        assert!((*position - point(*expected, 0.0)).length() < 1e-4, "{:?}", positions);
+    }
+
+    let fit = layout_positions(&path, &advances, Alignment::Start, Overflow::Fit);
+    assert_eq!(fit.len(), 4);
+    for (i, item) in fit.iter().enumerate() {
+        assert!((item.1 - point(0.5 + i as f32, 0.0)).length() < 1e-4, "{:?}", fit);
+        // The items are scaled down by half.
+        assert!((item.2.length() - 0.5).abs() < 1e-4, "{:?}", fit);
+    }
+}
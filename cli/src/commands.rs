@@ -1,5 +1,8 @@
 use std::io;
+use std::path::PathBuf;
+use lyon::math::Transform2D;
 use lyon::path::Path;
+use lyon::svg::paint::Color;
 use lyon::tessellation::{FillOptions, StrokeOptions};
 use lyon::algorithms::hatching::{HatchingOptions, DotOptions};
 
@@ -47,6 +50,17 @@ pub struct RenderCmd {
     pub debugger: Option<u32>,
 }
 
+#[derive(Clone, Debug)]
+pub struct HeadlessRenderCmd {
+    pub output: PathBuf,
+    pub width: usize,
+    pub height: usize,
+    pub background: Color,
+    /// Transforms the path into pixel coordinates. If `None`, the path is centered and
+    /// scaled to fit in the image.
+    pub transform: Option<Transform2D>,
+}
+
 pub struct PathCmd {
     pub path: Path,
     pub output: Box<io::Write>,
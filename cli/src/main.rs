@@ -14,13 +14,17 @@ mod tessellate;
 mod fuzzing;
 mod flatten;
 mod show;
+mod render;
 
 use clap::*;
 use commands::*;
 
 use std::fs::File;
 use std::io::{Read, Write, stdout, stderr};
+use std::path::PathBuf;
 use lyon::svg::path_utils::build_path;
+use lyon::svg::attributes::{parse_transform, AttributeError};
+use lyon::svg::paint::{parse_color, Color};
 use lyon::path::Path;
 use lyon::tessellation::{FillOptions, StrokeOptions, LineJoin, LineCap};
 use lyon::algorithms::hatching::{HatchingOptions, DotOptions};
@@ -154,6 +158,46 @@ fn main() {
                 .takes_value(true)
             )
         )
+        .subcommand(
+            declare_tess_params(SubCommand::with_name("render"))
+            .about("Renders a path into an image file, without opening a window")
+            .arg(Arg::with_name("OUTPUT")
+                .short("o")
+                .long("output")
+                .help("Sets the output file to use, in the PNG format if it ends with .png and PPM otherwise")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(true)
+            )
+            .arg(Arg::with_name("WIDTH")
+                .long("width")
+                .help("Sets the width of the image (800 by default)")
+                .value_name("WIDTH")
+                .takes_value(true)
+                .validator(validate_image_size)
+            )
+            .arg(Arg::with_name("HEIGHT")
+                .long("height")
+                .help("Sets the height of the image (800 by default)")
+                .value_name("HEIGHT")
+                .takes_value(true)
+                .validator(validate_image_size)
+            )
+            .arg(Arg::with_name("BACKGROUND")
+                .long("background")
+                .value_name("Blue|Dark|Clear|COLOR")
+                .help("Change the color of the background, using the presets of the show command or any SVG color")
+                .takes_value(true)
+                .validator(|value| parse_background_color(&value).map(|_| ()).map_err(|e| e.to_string()))
+            )
+            .arg(Arg::with_name("TRANSFORM")
+                .long("transform")
+                .value_name("TRANSFORM")
+                .help("Sets the view transform with the syntax of the SVG transform attribute (the path is fitted to the image by default)")
+                .takes_value(true)
+                .validator(|value| parse_transform(&value).map(|_| ()).map_err(|e| e.to_string()))
+            )
+        )
         .get_matches();
 
     if let Some(command) = matches.subcommand_matches("tessellate") {
@@ -222,6 +266,15 @@ fn main() {
         let render_params = get_render_params(command);
         show::show_path(cmd, render_params);
     }
+
+    if let Some(command) = matches.subcommand_matches("render") {
+        let cmd = get_tess_command(command);
+        let render_params = get_headless_render_params(command);
+        if let Err(e) = render::render_path(cmd, render_params) {
+            writeln!(&mut stderr(), "Error while rendering: {}", e).unwrap();
+            ::std::process::exit(1);
+        }
+    }
 }
 
 fn declare_input_path<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
@@ -334,7 +387,7 @@ fn get_path(matches: &ArgMatches) -> Option<Path> {
         Ok(path) => { Some(path) }
         Err(e) => {
             println!("Error while parsing path: {}", path_str);
-            println!("{:?}", e);
+            println!("{}", e);
             None
         }
     }
@@ -357,6 +410,28 @@ fn get_render_params(matches: &ArgMatches) -> RenderCmd {
     }
 }
 
+fn validate_image_size(value: String) -> ::std::result::Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(size) if size > 0 => Ok(()),
+        _ => Err("The image size must be a positive integer".to_string()),
+    }
+}
+
+// The values are checked by the validators of the arguments.
+fn get_headless_render_params(matches: &ArgMatches) -> HeadlessRenderCmd {
+    let get_size = |name| {
+        matches.value_of(name).map_or(800, |s| s.parse::<usize>().unwrap())
+    };
+
+    HeadlessRenderCmd {
+        output: PathBuf::from(matches.value_of("OUTPUT").unwrap()),
+        width: get_size("WIDTH"),
+        height: get_size("HEIGHT"),
+        background: parse_background_color(matches.value_of("BACKGROUND").unwrap_or("Blue")).unwrap(),
+        transform: matches.value_of("TRANSFORM").map(|s| parse_transform(s).unwrap()),
+    }
+}
+
 fn get_tess_command(command: &ArgMatches) -> TessellateCmd {
     let path = get_path(command).expect("Need a path to tessellate");
     let stroke = get_stroke(command);
@@ -427,6 +502,16 @@ fn get_background(matches: &ArgMatches) -> Background {
     Background::Blue
 }
 
+// Same colors as the backgrounds of the show command.
+fn parse_background_color(value: &str) -> ::std::result::Result<Color, AttributeError> {
+    match value {
+        "Blue" | "blue" => Ok(Color::rgb(0, 120, 230)),
+        "Dark" | "dark" => Ok(Color::rgb(13, 13, 13)),
+        "Clear" | "clear" => Ok(Color::rgb(230, 230, 230)),
+        _ => parse_color(value, Color::BLACK),
+    }
+}
+
 fn get_debugger(matches: &ArgMatches) -> Option<u32> {
     if let Some(param) = matches.value_of("DEBUGGER_2D") {
         return match &param {
@@ -0,0 +1,147 @@
+use lyon::math::*;
+use lyon::tessellation::geometry_builder::{VertexConstructor, VertexBuffers, BuffersBuilder, Identity};
+use lyon::tessellation::{FillTessellator, StrokeTessellator, FillVertex, StrokeVertex, TessellationError};
+use lyon::algorithms::aabb::bounding_rect;
+use lyon::extra::image::{Image, Rgba8};
+use lyon::extra::image_io::{save_image, ImageError};
+use lyon::extra::triangle_rasterizer::{rasterize_triangles, ColorTarget, FillConstantColor, GetColor};
+use lyon::tess2;
+use commands::{TessellateCmd, HeadlessRenderCmd, Tessellator};
+use tessellate::tessellate_patterns;
+use std::fmt;
+
+// Same colors as the show command.
+const FILL_COLOR: Rgba8 = [255, 255, 255, 255];
+const STROKE_COLOR: Rgba8 = [0, 0, 0, 255];
+
+#[derive(Debug)]
+pub enum RenderError {
+    Tessellation(TessellationError),
+    Tess2,
+    Image(ImageError),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RenderError::Tessellation(ref e) => write!(f, "tessellation failed: {:?}", e),
+            RenderError::Tess2 => write!(f, "tessellation failed"),
+            RenderError::Image(ref e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<TessellationError> for RenderError {
+    fn from(err: TessellationError) -> Self { RenderError::Tessellation(err) }
+}
+
+impl From<ImageError> for RenderError {
+    fn from(err: ImageError) -> Self { RenderError::Image(err) }
+}
+
+/// Tessellates the path and rasterizes the result on the CPU into an image file.
+pub fn render_path(cmd: TessellateCmd, render_options: HeadlessRenderCmd) -> Result<(), RenderError> {
+    let mut fill_geometry: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let mut stroke_geometry: VertexBuffers<Point, u32> = VertexBuffers::new();
+    // Without a GPU to apply the line width, the strokes are tessellated with it.
+    let mut max_line_width: f32 = 0.0;
+
+    if let Some(mut options) = cmd.stroke {
+        options.apply_line_width = true;
+        max_line_width = max_line_width.max(options.line_width);
+        StrokeTessellator::new().tessellate_path(
+            cmd.path.iter(),
+            &options,
+            &mut BuffersBuilder::new(&mut stroke_geometry, Positions)
+        )?;
+    }
+
+    let patterns = cmd.hatch.iter().map(|hatch| &hatch.stroke)
+        .chain(cmd.dots.iter().map(|dots| &dots.stroke));
+    for options in patterns {
+        max_line_width = max_line_width.max(options.line_width);
+    }
+    tessellate_patterns(&cmd, true, &mut BuffersBuilder::new(&mut stroke_geometry, Positions))?;
+
+    if let Some(options) = cmd.fill {
+        match cmd.tessellator {
+            Tessellator::Default => {
+                FillTessellator::new().tessellate_path(
+                    cmd.path.iter(),
+                    &options,
+                    &mut BuffersBuilder::new(&mut fill_geometry, Positions)
+                )?;
+            }
+            Tessellator::Tess2 => {
+                tess2::FillTessellator::new().tessellate_path(
+                    cmd.path.iter(),
+                    &options,
+                    &mut BuffersBuilder::new(&mut fill_geometry, Identity)
+                ).map_err(|()| RenderError::Tess2)?;
+            }
+        }
+    }
+
+    let width = render_options.width;
+    let height = render_options.height;
+    let transform = match render_options.transform {
+        Some(transform) => transform,
+        None => {
+            let aabb = bounding_rect(cmd.path.iter());
+            let aabb = aabb.inflate(max_line_width * 0.5, max_line_width * 0.5);
+            fit_transform(&aabb, width as f32, height as f32)
+        }
+    };
+
+    let background = render_options.background;
+    let mut image = Image::new(width, height, [background.r, background.g, background.b, background.a]);
+
+    for &(ref geometry, color) in &[(fill_geometry, FILL_COLOR), (stroke_geometry, STROKE_COLOR)] {
+        let vertices: Vec<Point> = geometry.vertices.iter()
+            .map(|p| transform.transform_point(*p))
+            .collect();
+
+        rasterize_triangles(
+            &vertices,
+            &geometry.indices,
+            &SolidColor(color),
+            &mut ColorTarget::new(&mut image.as_mut_slice(), FillConstantColor),
+        );
+    }
+
+    save_image(&image.as_slice(), &render_options.output)?;
+
+    Ok(())
+}
+
+// Centers the rectangle in the image and scales it to fit with a small margin.
+fn fit_transform(rect: &Rect, width: f32, height: f32) -> Transform2D {
+    let margin = width.min(height) * 0.05;
+    let scale_x = (width - 2.0 * margin) / rect.size.width;
+    let scale_y = (height - 2.0 * margin) / rect.size.height;
+    let mut scale = scale_x.min(scale_y);
+    if !scale.is_finite() || scale <= 0.0 {
+        scale = 1.0;
+    }
+
+    let center = rect.center();
+    Transform2D::create_translation(-center.x, -center.y)
+        .post_scale(scale, scale)
+        .post_translate(vector(width * 0.5, height * 0.5))
+}
+
+struct SolidColor(Rgba8);
+
+impl GetColor<Rgba8> for SolidColor {
+    fn get_color(&self) -> Rgba8 { self.0 }
+}
+
+struct Positions;
+
+impl VertexConstructor<StrokeVertex, Point> for Positions {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> Point { vertex.position }
+}
+
+impl VertexConstructor<FillVertex, Point> for Positions {
+    fn new_vertex(&mut self, vertex: FillVertex) -> Point { vertex.position }
+}
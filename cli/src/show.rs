@@ -4,10 +4,10 @@ use lyon::tessellation::basic_shapes::*;
 use lyon::tessellation::{FillTessellator, StrokeTessellator, FillOptions, StrokeOptions};
 use lyon::tessellation::debugger::*;
 use lyon::tessellation;
-use lyon::algorithms::hatching::*;
 use lyon::algorithms::aabb::bounding_rect;
 use lyon::path::Path;
 use commands::{TessellateCmd, AntiAliasing, RenderCmd, Tessellator, Background};
+use tessellate::tessellate_patterns;
 use lyon::tess2;
 
 use gfx;
@@ -33,52 +33,7 @@ pub fn show_path(cmd: TessellateCmd, render_options: RenderCmd) {
         ).unwrap();
     }
 
-    if let Some(hatch) = cmd.hatch {
-        let mut path = Path::builder();
-        let mut hatcher = Hatcher::new();
-        hatcher.hatch_path(
-            cmd.path.iter(),
-            &hatch.options,
-            &mut RegularHatchingPattern {
-                interval: hatch.spacing,
-                callback: &mut|segment: &HatchSegment| {
-                    path.move_to(segment.a.position);
-                    path.line_to(segment.b.position);
-                }
-            },
-
-        );
-        let hatched_path = path.build();
-
-        StrokeTessellator::new().tessellate_path(
-            hatched_path.iter(),
-            &hatch.stroke,
-            &mut BuffersBuilder::new(&mut geometry, WithId(1))
-        ).unwrap();
-    }
-
-    if let Some(dots) = cmd.dots {
-        let mut path = Path::builder();
-        let mut hatcher = Hatcher::new();
-        hatcher.dot_path(
-            cmd.path.iter(),
-            &dots.options,
-            &mut RegularDotPattern {
-                row_interval: dots.spacing,
-                column_interval: dots.spacing,
-                callback: &mut|dot: &Dot| {
-                    path.move_to(dot.position);
-                }
-            },
-        );
-        let dotted_path = path.build();
-
-        StrokeTessellator::new().tessellate_path(
-            dotted_path.iter(),
-            &dots.stroke,
-            &mut BuffersBuilder::new(&mut geometry, WithId(1))
-        ).unwrap();
-    }
+    tessellate_patterns(&cmd, false, &mut BuffersBuilder::new(&mut geometry, WithId(1))).unwrap();
 
     let mut debug_trace = Trace::new();
     if let Some(options) = cmd.fill {
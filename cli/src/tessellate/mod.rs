@@ -1,10 +1,15 @@
 use commands::{TessellateCmd, Tessellator};
 use lyon::math::*;
-use lyon::tessellation::geometry_builder::{VertexBuffers, BuffersBuilder, VertexConstructor, Identity};
+use lyon::tessellation::geometry_builder::{
+    VertexBuffers, BuffersBuilder, VertexConstructor, Identity, GeometryBuilder
+};
 use lyon::tessellation::{
     FillVertex, StrokeVertex,
-    StrokeTessellator, FillTessellator
+    StrokeTessellator, FillTessellator,
+    TessellationError,
 };
+use lyon::algorithms::hatching::*;
+use lyon::path::Path;
 use lyon::tess2;
 use std::io;
 
@@ -66,6 +71,60 @@ pub fn tessellate_path(cmd: TessellateCmd) -> Result<VertexBuffers<Point, u16>,
     Ok(buffers)
 }
 
+/// Tessellates the hatching and dot patterns of the command as strokes.
+///
+/// If `apply_line_width` is true, the line width is applied to the stroke vertices
+/// rather than left to the renderer.
+pub fn tessellate_patterns(
+    cmd: &TessellateCmd,
+    apply_line_width: bool,
+    output: &mut dyn GeometryBuilder<StrokeVertex>,
+) -> Result<(), TessellationError> {
+    if let Some(ref hatch) = cmd.hatch {
+        let mut path = Path::builder();
+        let mut hatcher = Hatcher::new();
+        hatcher.hatch_path(
+            cmd.path.iter(),
+            &hatch.options,
+            &mut RegularHatchingPattern {
+                interval: hatch.spacing,
+                callback: &mut|segment: &HatchSegment| {
+                    path.move_to(segment.a.position);
+                    path.line_to(segment.b.position);
+                }
+            },
+        );
+        let hatched_path = path.build();
+
+        let mut options = hatch.stroke;
+        options.apply_line_width = apply_line_width;
+        StrokeTessellator::new().tessellate_path(hatched_path.iter(), &options, output)?;
+    }
+
+    if let Some(ref dots) = cmd.dots {
+        let mut path = Path::builder();
+        let mut hatcher = Hatcher::new();
+        hatcher.dot_path(
+            cmd.path.iter(),
+            &dots.options,
+            &mut RegularDotPattern {
+                row_interval: dots.spacing,
+                column_interval: dots.spacing,
+                callback: &mut|dot: &Dot| {
+                    path.move_to(dot.position);
+                }
+            },
+        );
+        let dotted_path = path.build();
+
+        let mut options = dots.stroke;
+        options.apply_line_width = apply_line_width;
+        StrokeTessellator::new().tessellate_path(dotted_path.iter(), &options, output)?;
+    }
+
+    Ok(())
+}
+
 pub fn write_output(
     buffers: VertexBuffers<Point, u16>,
     count: bool,
@@ -96,3 +96,197 @@ impl<'l, Pixel: Copy + 'static> MutableImageSlice<'l, Pixel> {
         }
     }
 }
+
+impl<'l, Pixel: Copy + 'static> MutableImageSlice<'l, Pixel> {
+    pub fn as_slice(&self) -> ImageSlice<'_, Pixel> {
+        ImageSlice::with_stride(self.width, self.height, self.stride, self.pixels)
+    }
+}
+
+/// A view on a read-only image in memory.
+#[derive(Copy, Clone)]
+pub struct ImageSlice<'l, Pixel: Copy + 'static> {
+    pub width: usize,
+    pub height: usize,
+    pub stride: usize,
+    pub pixels: &'l [Pixel],
+}
+
+impl<'l, Pixel: Copy + 'static> ImageSlice<'l, Pixel> {
+    pub fn new(width: usize, height: usize, pixels: &'l [Pixel]) -> ImageSlice<'l, Pixel> {
+        ImageSlice::with_stride(width, height, width, pixels)
+    }
+
+    pub fn with_stride(
+        width: usize,
+        height: usize,
+        stride: usize,
+        pixels: &'l [Pixel],
+    ) -> ImageSlice<'l, Pixel> {
+        assert!(width <= stride);
+        assert!(pixels.len() >= height * stride);
+
+        ImageSlice { width, height, stride, pixels }
+    }
+
+    pub fn pixel_offset(&self, x: usize, y: usize) -> usize { x + y * self.stride }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Pixel { self.pixels[self.pixel_offset(x, y)] }
+
+    /// Returns the pixels of a row, without the padding.
+    pub fn row(&self, y: usize) -> &'l [Pixel] {
+        let start = y * self.stride;
+        &self.pixels[start..start + self.width]
+    }
+}
+
+/// An image owning its pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image<Pixel: Copy + 'static> {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Pixel>,
+}
+
+impl<Pixel: Copy + 'static> Image<Pixel> {
+    /// Creates an image with all of its pixels set to the same value.
+    pub fn new(width: usize, height: usize, value: Pixel) -> Image<Pixel> {
+        Image { width, height, pixels: vec![value; width * height] }
+    }
+
+    pub fn as_slice(&self) -> ImageSlice<'_, Pixel> {
+        ImageSlice::new(self.width, self.height, &self.pixels)
+    }
+
+    pub fn as_mut_slice(&mut self) -> MutableImageSlice<'_, Pixel> {
+        MutableImageSlice::new(self.width, self.height, &mut self.pixels)
+    }
+}
+
+/// An 8 bits per channel red, green, blue and alpha pixel.
+pub type Rgba8 = [u8; 4];
+
+/// The pixel formats that can be encoded, decoded and compared.
+///
+/// `u8` is the A8 format. It is stored as an 8 bits grayscale image.
+pub trait PixelFormat: Copy + PartialEq + 'static {
+    /// Number of channels stored when encoding the image: 1 (grayscale) or 4 (RGBA).
+    const CHANNELS: usize;
+
+    fn to_rgba8(self) -> Rgba8;
+    fn from_rgba8(color: Rgba8) -> Self;
+}
+
+impl PixelFormat for u8 {
+    const CHANNELS: usize = 1;
+
+    fn to_rgba8(self) -> Rgba8 { [self, self, self, 255] }
+
+    fn from_rgba8(color: Rgba8) -> u8 {
+        ((color[0] as u32 + color[1] as u32 + color[2] as u32 + 1) / 3) as u8
+    }
+}
+
+impl PixelFormat for Rgba8 {
+    const CHANNELS: usize = 4;
+
+    fn to_rgba8(self) -> Rgba8 { self }
+
+    fn from_rgba8(color: Rgba8) -> Rgba8 { color }
+}
+
+/// The result of `compare_images`.
+pub struct ImageComparison {
+    /// Number of pixels that differ by more than the tolerance.
+    pub different_pixels: usize,
+    /// Largest difference between two channels of a pixel.
+    pub max_difference: u8,
+    /// A visualization of the differences.
+    ///
+    /// Identical pixels are shown as a faded version of the reference, pixels that
+    /// differ within the tolerance in yellow and the other ones in red.
+    pub diff: Image<Rgba8>,
+}
+
+impl ImageComparison {
+    pub fn is_match(&self) -> bool { self.different_pixels == 0 }
+}
+
+/// Compares an image with a reference, pixel per pixel.
+///
+/// Two pixels match if none of their channels differ by more than `tolerance`.
+/// If the sizes differ the comparison covers both images and pixels that are only in one
+/// of them never match.
+pub fn compare_images<Pixel: PixelFormat>(
+    image: &ImageSlice<Pixel>,
+    reference: &ImageSlice<Pixel>,
+    tolerance: u8,
+) -> ImageComparison {
+    let width = image.width.max(reference.width);
+    let height = image.height.max(reference.height);
+    let mut diff = Image::new(width, height, [255, 0, 0, 255]);
+    let mut different_pixels = 0;
+    let mut max_difference = 0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let in_image = x < image.width && y < image.height;
+            let in_reference = x < reference.width && y < reference.height;
+            if !in_image || !in_reference {
+                different_pixels += 1;
+                max_difference = 255;
+                continue;
+            }
+
+            let a = image.pixel(x, y).to_rgba8();
+            let b = reference.pixel(x, y).to_rgba8();
+            let difference = (0..4)
+                .map(|c| (a[c] as i32 - b[c] as i32).unsigned_abs() as u8)
+                .max()
+                .unwrap();
+            max_difference = max_difference.max(difference);
+
+            diff.pixels[y * width + x] = if difference == 0 {
+                let luminance = (b[0] as u32 * 2 + b[1] as u32 * 5 + b[2] as u32) / 8;
+                let faded = (192 + luminance / 4) as u8;
+                [faded, faded, faded, 255]
+            } else if difference <= tolerance {
+                [255, 200, 0, 255]
+            } else {
+                different_pixels += 1;
+                [255, 0, 0, 255]
+            };
+        }
+    }
+
+    ImageComparison { different_pixels, max_difference, diff }
+}
+
+#[test]
+fn compare() {
+    let reference = Image::new(4, 3, 100u8);
+    let mut image = reference.clone();
+    image.pixels[1] = 104;
+    image.pixels[6] = 0;
+
+    let result = compare_images(&image.as_slice(), &reference.as_slice(), 4);
+    assert!(!result.is_match());
+    assert_eq!(result.different_pixels, 1);
+    assert_eq!(result.max_difference, 100);
+    assert_eq!(result.diff.pixels[1], [255, 200, 0, 255]);
+    assert_eq!(result.diff.pixels[6], [255, 0, 0, 255]);
+    assert_eq!(result.diff.pixels[0], [217, 217, 217, 255]);
+
+    let result = compare_images(&image.as_slice(), &reference.as_slice(), 100);
+    assert!(result.is_match());
+
+    // Sizes don't match.
+    let mut pixels = vec![[0, 0, 0, 255]; 4 * 3];
+    pixels[3] = [1, 2, 3, 4];
+    let image = ImageSlice::with_stride(3, 3, 4, &pixels);
+    let reference = Image::new(3, 4, [0, 0, 0, 255]);
+    let result = compare_images(&image, &reference.as_slice(), 0);
+    assert_eq!(result.diff.width, 3);
+    assert_eq!(result.diff.height, 4);
+    assert_eq!(result.different_pixels, 3);
+}
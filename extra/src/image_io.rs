@@ -0,0 +1,300 @@
+//! Saving and loading images in the PNG and binary PPM/PGM formats.
+//!
+//! RGBA8 images are stored as RGBA PNGs or PPM files (P6, which drops the alpha channel),
+//! and A8 images as grayscale PNGs or PGM files (P5). Loading converts the stored image
+//! into the requested pixel format.
+
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use png;
+
+use image::{Image, ImageSlice, PixelFormat, Rgba8};
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    /// The data is not a valid or supported image.
+    InvalidFormat(String),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ImageError::Io(ref e) => write!(f, "{}", e),
+            ImageError::InvalidFormat(ref msg) => write!(f, "invalid image: {}", msg),
+        }
+    }
+}
+
+impl error::Error for ImageError {}
+
+impl From<io::Error> for ImageError {
+    fn from(e: io::Error) -> Self { ImageError::Io(e) }
+}
+
+impl From<png::EncodingError> for ImageError {
+    fn from(e: png::EncodingError) -> Self {
+        match e {
+            png::EncodingError::IoError(e) => ImageError::Io(e),
+            e => ImageError::InvalidFormat(e.to_string()),
+        }
+    }
+}
+
+impl From<png::DecodingError> for ImageError {
+    fn from(e: png::DecodingError) -> Self {
+        match e {
+            png::DecodingError::IoError(e) => ImageError::Io(e),
+            e => ImageError::InvalidFormat(e.to_string()),
+        }
+    }
+}
+
+fn invalid<T>(msg: &str) -> Result<T, ImageError> {
+    Err(ImageError::InvalidFormat(msg.to_string()))
+}
+
+/// Writes the image in the binary PGM (A8) or PPM (RGBA8) format.
+pub fn encode_ppm<Pixel: PixelFormat, W: Write>(
+    image: &ImageSlice<Pixel>,
+    output: &mut W,
+) -> Result<(), ImageError> {
+    let (magic, channels) = if Pixel::CHANNELS == 1 { ("P5", 1) } else { ("P6", 3) };
+    write!(output, "{}\n{} {}\n255\n", magic, image.width, image.height)?;
+
+    let mut row = Vec::with_capacity(image.width * channels);
+    for y in 0..image.height {
+        row.clear();
+        for pixel in image.row(y) {
+            row.extend_from_slice(&pixel.to_rgba8()[..channels]);
+        }
+        output.write_all(&row)?;
+    }
+
+    Ok(())
+}
+
+/// Reads a binary PGM (P5) or PPM (P6) image.
+pub fn decode_ppm<Pixel: PixelFormat, R: Read>(input: R) -> Result<Image<Pixel>, ImageError> {
+    let mut data = Vec::new();
+    BufReader::new(input).read_to_end(&mut data)?;
+
+    let mut position = 0;
+    let mut header = [0usize; 4];
+    for (i, value) in header.iter_mut().enumerate() {
+        // Skip whitespace and comments.
+        loop {
+            match data.get(position) {
+                Some(b'#') => {
+                    while position < data.len() && data[position] != b'\n' {
+                        position += 1;
+                    }
+                }
+                Some(c) if c.is_ascii_whitespace() => position += 1,
+                _ => break,
+            }
+        }
+        let start = position;
+        while position < data.len() && !data[position].is_ascii_whitespace() {
+            position += 1;
+        }
+        let token = &data[start..position];
+        *value = if i == 0 {
+            match token {
+                b"P5" => 1,
+                b"P6" => 3,
+                _ => return invalid("expected a binary PGM or PPM image"),
+            }
+        } else {
+            match ::std::str::from_utf8(token).ok().and_then(|s| s.parse().ok()) {
+                Some(v) => v,
+                None => return invalid("malformed header"),
+            }
+        };
+    }
+    // A single whitespace character separates the header from the pixels.
+    position += 1;
+
+    let [channels, width, height, max_value] = header;
+    if max_value == 0 || max_value > 255 {
+        return invalid("only 8 bits images are supported");
+    }
+    let size = match width.checked_mul(height).and_then(|n| n.checked_mul(channels)) {
+        Some(size) => size,
+        None => return invalid("image too large"),
+    };
+    if data.len() < position || data.len() - position < size {
+        return invalid("unexpected end of file");
+    }
+
+    let scale = |v: u8| (v as usize * 255 / max_value).min(255) as u8;
+    let pixels = data[position..position + size].chunks(channels).map(|c| {
+        if channels == 1 {
+            let v = scale(c[0]);
+            Pixel::from_rgba8([v, v, v, 255])
+        } else {
+            Pixel::from_rgba8([scale(c[0]), scale(c[1]), scale(c[2]), 255])
+        }
+    }).collect();
+
+    Ok(Image { width, height, pixels })
+}
+
+/// Writes the image in the PNG format, as grayscale (A8) or RGBA (RGBA8).
+pub fn encode_png<Pixel: PixelFormat, W: Write>(
+    image: &ImageSlice<Pixel>,
+    output: W,
+) -> Result<(), ImageError> {
+    let mut encoder = png::Encoder::new(output, image.width as u32, image.height as u32);
+    encoder.set_color(if Pixel::CHANNELS == 1 {
+        png::ColorType::Grayscale
+    } else {
+        png::ColorType::RGBA
+    });
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut data = Vec::with_capacity(image.width * image.height * Pixel::CHANNELS);
+    for y in 0..image.height {
+        for pixel in image.row(y) {
+            data.extend_from_slice(&pixel.to_rgba8()[..Pixel::CHANNELS]);
+        }
+    }
+
+    encoder.write_header()?.write_image_data(&data)?;
+
+    Ok(())
+}
+
+/// Reads a PNG image.
+///
+/// All color types are supported, 16 bits images lose their extra precision.
+pub fn decode_png<Pixel: PixelFormat, R: Read>(input: R) -> Result<Image<Pixel>, ImageError> {
+    // The default transformations expand the image to 8 bits per channel.
+    let (info, mut reader) = png::Decoder::new(input).read_info()?;
+    let mut data = vec![0; info.buffer_size()];
+    reader.next_frame(&mut data)?;
+
+    let to_rgba8: fn(&[u8]) -> Rgba8 = match info.color_type {
+        png::ColorType::Grayscale => |c| [c[0], c[0], c[0], 255],
+        png::ColorType::GrayscaleAlpha => |c| [c[0], c[0], c[0], c[1]],
+        png::ColorType::RGB => |c| [c[0], c[1], c[2], 255],
+        png::ColorType::RGBA => |c| [c[0], c[1], c[2], c[3]],
+        png::ColorType::Indexed => return invalid("unexpanded palette"),
+    };
+    let channels = info.color_type.samples();
+
+    let width = info.width as usize;
+    let height = info.height as usize;
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in data.chunks(info.line_size).take(height) {
+        for c in row[..width * channels].chunks(channels) {
+            pixels.push(Pixel::from_rgba8(to_rgba8(c)));
+        }
+    }
+
+    Ok(Image { width, height, pixels })
+}
+
+fn is_png(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+}
+
+/// Saves an image, in the PNG format if the file name ends with `.png` and in the
+/// PPM/PGM format otherwise.
+pub fn save_image<Pixel: PixelFormat>(
+    image: &ImageSlice<Pixel>,
+    path: &Path,
+) -> Result<(), ImageError> {
+    let mut output = BufWriter::new(File::create(path)?);
+    if is_png(path) {
+        encode_png(image, &mut output)?;
+    } else {
+        encode_ppm(image, &mut output)?;
+    }
+    output.flush()?;
+
+    Ok(())
+}
+
+/// Loads an image saved in the PNG or PPM/PGM format, depending on its file name.
+pub fn load_image<Pixel: PixelFormat>(path: &Path) -> Result<Image<Pixel>, ImageError> {
+    let input = BufReader::new(File::open(path)?);
+    if is_png(path) {
+        decode_png(input)
+    } else {
+        decode_ppm(input)
+    }
+}
+
+#[cfg(test)]
+fn test_image() -> Image<Rgba8> {
+    let mut image = Image::new(5, 3, [0, 0, 0, 255]);
+    for (i, pixel) in image.pixels.iter_mut().enumerate() {
+        let i = i as u8;
+        *pixel = [i * 10, 255 - i, i * 3, 100 + i];
+    }
+    image
+}
+
+#[test]
+fn png_round_trip() {
+    let image = test_image();
+    let mut data = Vec::new();
+    encode_png(&image.as_slice(), &mut data).unwrap();
+    assert_eq!(&data[1..4], b"PNG");
+    assert_eq!(decode_png::<Rgba8, _>(&data[..]).unwrap(), image);
+
+    let gray = Image { width: 3, height: 2, pixels: vec![0u8, 10, 20, 30, 40, 255] };
+    let mut data = Vec::new();
+    encode_png(&gray.as_slice(), &mut data).unwrap();
+    assert_eq!(decode_png::<u8, _>(&data[..]).unwrap(), gray);
+
+    // Loading a grayscale image as RGBA.
+    let rgba = decode_png::<Rgba8, _>(&data[..]).unwrap();
+    assert_eq!(rgba.pixels[1], [10, 10, 10, 255]);
+
+    assert!(decode_png::<u8, _>(&b"P5 1 1 255 x"[..]).is_err());
+}
+
+#[test]
+fn ppm_round_trip() {
+    let image = test_image();
+    let mut data = Vec::new();
+    encode_ppm(&image.as_slice(), &mut data).unwrap();
+    assert_eq!(&data[..11], b"P6\n5 3\n255\n");
+    assert_eq!(data.len(), 11 + 5 * 3 * 3);
+
+    // The alpha channel is lost.
+    let decoded = decode_ppm::<Rgba8, _>(&data[..]).unwrap();
+    assert_eq!(decoded.width, 5);
+    assert_eq!(decoded.height, 3);
+    for (a, b) in decoded.pixels.iter().zip(image.pixels.iter()) {
+        assert_eq!(a[..3], b[..3]);
+        assert_eq!(a[3], 255);
+    }
+
+    let pixels = [1u8, 2, 3, 0, 4, 5, 6, 0];
+    let gray = ImageSlice::with_stride(3, 2, 4, &pixels);
+    let mut data = Vec::new();
+    encode_ppm(&gray, &mut data).unwrap();
+    assert_eq!(&data[..], b"P5\n3 2\n255\n\x01\x02\x03\x04\x05\x06");
+
+    // Comments and other maximum values in the header.
+    let decoded = decode_ppm::<u8, _>(&b"P5 # comment\n2\n# size\n 1 127\n\x7f\x00"[..]).unwrap();
+    assert_eq!(decoded.pixels, vec![255, 0]);
+
+    assert!(decode_ppm::<u8, _>(&b"P3 1 1 255 1"[..]).is_err());
+    assert!(decode_ppm::<u8, _>(&b"P5 2 2 255 \x00"[..]).is_err());
+    assert!(decode_ppm::<u8, _>(&b"P5 2 x 255 \x00"[..]).is_err());
+    assert!(decode_ppm::<u8, _>(&b"P5 2 2 255"[..]).is_err());
+    match decode_ppm::<u8, _>(&b"P6 18446744073709551615 2 255 \x00"[..]) {
+        Err(ImageError::InvalidFormat(_)) => {}
+        result => panic!("{:?}", result.map(|image| image.width)),
+    }
+}
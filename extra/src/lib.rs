@@ -6,6 +6,7 @@
 
 extern crate lyon_path as path;
 extern crate lyon_svg as svg;
+extern crate png;
 
 pub use path::geom::math;
 pub use path::geom::euclid;
@@ -14,3 +15,5 @@ pub mod rust_logo;
 pub mod triangle_rasterizer;
 pub mod debugging;
 pub mod image;
+pub mod image_io;
+pub mod path_rasterizer;
@@ -0,0 +1,343 @@
+//! An anti-aliased path rasterizer computing the exact area coverage of each pixel.
+//!
+//! The implementation follows the signed-area accumulation approach of
+//! [font-rs](https://github.com/raphlinus/font-rs): each edge adds the signed area it
+//! covers to an accumulation buffer and a prefix sum along each row yields the winding
+//! of every pixel weighted by its coverage. It is simple and precise, but not fast, which
+//! makes it a good reference renderer for tests and thumbnails.
+
+use math::*;
+use path::FillRule;
+use path::FlattenedEvent;
+use path::iterator::PathIterator;
+use image::MutableImageSlice;
+
+/// Maximum distance in pixels between curves and their flattened approximation.
+pub const FLATTENING_TOLERANCE: f32 = 0.05;
+
+/// Renders the coverage of a path into an 8 bits alpha mask.
+///
+/// The path is expressed in pixel coordinates, with the top-left corner of the image at
+/// the origin. Sub-paths are implicitly closed. Each pixel of the target is overwritten
+/// with the fraction of its area that is covered by the path, from 0 to 255.
+///
+/// The coverage is exact for non-zero filling. With the even-odd fill rule, pixels that
+/// are crossed by several edges of the path can be slightly off.
+///
+/// # Example
+///
+/// ```
+/// # extern crate lyon_extra;
+/// # extern crate lyon_path;
+/// # use lyon_extra::path_rasterizer::rasterize_path;
+/// # use lyon_extra::image::MutableImageSlice;
+/// # use lyon_path::{Path, FillRule};
+/// # use lyon_path::builder::*;
+/// # use lyon_path::math::point;
+/// # fn main() {
+/// let mut builder = Path::builder();
+/// builder.move_to(point(1.5, 1.0));
+/// builder.line_to(point(3.0, 1.0));
+/// builder.line_to(point(3.0, 3.0));
+/// builder.line_to(point(1.5, 3.0));
+/// builder.close();
+/// let path = builder.build();
+///
+/// let mut pixels = vec![0u8; 4 * 4];
+/// rasterize_path(path.iter(), FillRule::NonZero, &mut MutableImageSlice::new(4, 4, &mut pixels));
+///
+/// assert_eq!(&pixels[4..8], &[0, 128, 255, 0]);
+/// # }
+/// ```
+pub fn rasterize_path<Iter: PathIterator>(
+    path: Iter,
+    fill_rule: FillRule,
+    target: &mut MutableImageSlice<u8>,
+) {
+    let mut accumulator = Accumulator::new(target.width, target.height);
+
+    let mut first = point(0.0, 0.0);
+    let mut current = first;
+    for evt in path.flattened(FLATTENING_TOLERANCE) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                accumulator.add_line(current, first);
+                first = to;
+                current = to;
+            }
+            FlattenedEvent::Line(segment) => {
+                accumulator.add_line(segment.from, segment.to);
+                current = segment.to;
+            }
+            FlattenedEvent::Close(segment) => {
+                accumulator.add_line(segment.from, segment.to);
+                current = first;
+            }
+        }
+    }
+    accumulator.add_line(current, first);
+
+    accumulator.resolve(fill_rule, target);
+}
+
+struct Accumulator {
+    width: usize,
+    height: usize,
+    // Two extra cells per row receive the contributions of the edges at the right
+    // border of the image, so that rows never bleed into each other.
+    stride: usize,
+    cells: Vec<f32>,
+}
+
+impl Accumulator {
+    fn new(width: usize, height: usize) -> Self {
+        let stride = width + 2;
+        Accumulator {
+            width,
+            height,
+            stride,
+            cells: vec![0.0; stride * height],
+        }
+    }
+
+    // Splits the edge where it crosses the left and right sides of the image.
+    // The parts on the left only matter through the winding they add to the rest of the
+    // row so they are projected onto the left side, while the parts on the right can't
+    // affect any pixel and are projected onto the right side.
+    fn add_line(&mut self, from: Point, to: Point) {
+        if from.y == to.y {
+            return;
+        }
+
+        let max_x = self.width as f32;
+        let mut splits = [0.0, 1.0, 1.0, 1.0];
+        let mut num_splits = 1;
+        if from.x != to.x {
+            for &x in &[0.0, max_x] {
+                let t = (x - from.x) / (to.x - from.x);
+                if t > 0.0 && t < 1.0 {
+                    splits[num_splits] = t;
+                    num_splits += 1;
+                }
+            }
+        }
+        if num_splits == 3 && splits[1] > splits[2] {
+            splits.swap(1, 2);
+        }
+        splits[num_splits] = 1.0;
+
+        let clamp = |p: Point| point(p.x.max(0.0).min(max_x), p.y);
+        let mut prev = clamp(from);
+        for &t in &splits[1..=num_splits] {
+            let next = if t == 1.0 {
+                clamp(to)
+            } else {
+                clamp(from.lerp(to, t))
+            };
+            self.add_clipped_line(prev, next);
+            prev = next;
+        }
+    }
+
+    // Accumulates the signed area covered by an edge that doesn't leave the horizontal
+    // range of the image, one row at a time.
+    fn add_clipped_line(&mut self, from: Point, to: Point) {
+        if from.y == to.y {
+            return;
+        }
+
+        let (dir, p0, p1) = if from.y < to.y { (1.0, from, to) } else { (-1.0, to, from) };
+        if p1.y <= 0.0 || p0.y >= self.height as f32 {
+            return;
+        }
+
+        let dxdy = (p1.x - p0.x) / (p1.y - p0.y);
+        let mut x = p0.x;
+        if p0.y < 0.0 {
+            x -= p0.y * dxdy;
+        }
+
+        let y_start = p0.y.max(0.0) as usize;
+        let y_end = (p1.y.ceil() as usize).min(self.height);
+        for y in y_start..y_end {
+            let row = &mut self.cells[y * self.stride..(y + 1) * self.stride];
+            let dy = ((y + 1) as f32).min(p1.y) - (y as f32).max(p0.y);
+            let x_next = (x + dxdy * dy).max(0.0).min(self.width as f32);
+            let d = dy * dir;
+
+            let (x0, x1) = if x < x_next { (x, x_next) } else { (x_next, x) };
+            let x0_floor = x0.floor();
+            let x0i = x0_floor as usize;
+            let x1_ceil = x1.ceil();
+            let x1i = x1_ceil as usize;
+
+            if x1i <= x0i + 1 {
+                // The edge doesn't leave the pixel on this row.
+                let xm = 0.5 * (x + x_next) - x0_floor;
+                row[x0i] += d - d * xm;
+                row[x0i + 1] += d * xm;
+            } else {
+                let s = (x1 - x0).recip();
+                let x0f = x0 - x0_floor;
+                let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+                let x1f = x1 - x1_ceil + 1.0;
+                let am = 0.5 * s * x1f * x1f;
+
+                row[x0i] += d * a0;
+                if x1i == x0i + 2 {
+                    row[x0i + 1] += d * (1.0 - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    row[x0i + 1] += d * (a1 - a0);
+                    for cell in &mut row[x0i + 2..x1i - 1] {
+                        *cell += d * s;
+                    }
+                    let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                    row[x1i - 1] += d * (1.0 - a2 - am);
+                }
+                row[x1i] += d * am;
+            }
+
+            x = x_next;
+        }
+    }
+
+    fn resolve(&self, fill_rule: FillRule, target: &mut MutableImageSlice<u8>) {
+        for y in 0..self.height {
+            let row = &self.cells[y * self.stride..(y + 1) * self.stride];
+            let mut winding = 0.0;
+            for (x, cell) in row.iter().take(self.width).enumerate() {
+                winding += cell;
+                let coverage = match fill_rule {
+                    FillRule::NonZero => winding.abs().min(1.0),
+                    FillRule::EvenOdd => {
+                        let w = winding.abs() % 2.0;
+                        w.min(2.0 - w)
+                    }
+                };
+                let offset = target.pixel_offset(x, y);
+                target.pixels[offset] = (coverage * 255.0 + 0.5) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+use path::Path;
+#[cfg(test)]
+use path::builder::*;
+
+#[cfg(test)]
+fn rasterize(path: &Path, fill_rule: FillRule, width: usize, height: usize) -> Vec<u8> {
+    let mut pixels = vec![0; width * height];
+    rasterize_path(path.iter(), fill_rule, &mut MutableImageSlice::new(width, height, &mut pixels));
+    pixels
+}
+
+#[cfg(test)]
+fn add_rect<Builder: FlatPathBuilder>(builder: &mut Builder, r: &Rect) {
+    builder.move_to(point(r.min_x(), r.min_y()));
+    builder.line_to(point(r.max_x(), r.min_y()));
+    builder.line_to(point(r.max_x(), r.max_y()));
+    builder.line_to(point(r.min_x(), r.max_y()));
+    builder.close();
+}
+
+#[test]
+fn rasterize_rectangles() {
+    let mut builder = Path::builder();
+    add_rect(&mut builder, &rect(2.0, 2.0, 4.0, 4.0));
+    let pixels = rasterize(&builder.build(), FillRule::NonZero, 8, 8);
+    for y in 0..8 {
+        for x in 0..8 {
+            let inside = (2..6).contains(&x) && (2..6).contains(&y);
+            assert_eq!(pixels[y * 8 + x], if inside { 255 } else { 0 }, "pixel {} {}", x, y);
+        }
+    }
+
+    // Edges in the middle of pixels give partial coverage.
+    let mut builder = Path::builder();
+    add_rect(&mut builder, &rect(1.5, 1.5, 3.0, 3.0));
+    let pixels = rasterize(&builder.build(), FillRule::NonZero, 6, 6);
+    assert_eq!(&pixels[6..12], &[0, 64, 128, 128, 64, 0]);
+    assert_eq!(&pixels[12..18], &[0, 128, 255, 255, 128, 0]);
+    assert_eq!(&pixels[30..36], &[0, 0, 0, 0, 0, 0]);
+
+    // A diagonal edge splitting pixels in half.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(4.0, 0.0));
+    builder.line_to(point(4.0, 4.0));
+    builder.close();
+    let pixels = rasterize(&builder.build(), FillRule::NonZero, 4, 4);
+    assert_eq!(&pixels[4..8], &[0, 128, 255, 255]);
+}
+
+#[test]
+fn rasterize_clipped() {
+    // Most of the rectangle is outside of the image, on all sides.
+    let mut builder = Path::builder();
+    add_rect(&mut builder, &rect(-10.5, -3.0, 30.0, 6.5));
+    builder.move_to(point(-5.0, 5.0));
+    builder.line_to(point(20.0, 5.0));
+    builder.line_to(point(-5.0, 8.0));
+    let pixels = rasterize(&builder.build(), FillRule::NonZero, 4, 5);
+    assert!(pixels[..12].iter().all(|&p| p == 255));
+    assert!(pixels[12..16].iter().all(|&p| p == 128));
+    assert!(pixels[16..].iter().all(|&p| p == 0));
+
+    // Reversed, entirely outside on the right or the bottom.
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, -5.0));
+    builder.line_to(point(-3.0, 2.5));
+    builder.line_to(point(-3.0, 10.0));
+    builder.line_to(point(1.0, 10.0));
+    add_rect(&mut builder, &rect(5.0, 0.0, 4.0, 4.0));
+    add_rect(&mut builder, &rect(0.0, 6.0, 4.0, 4.0));
+    let pixels = rasterize(&builder.build(), FillRule::NonZero, 4, 5);
+    for y in 0..5 {
+        assert_eq!(pixels[y * 4], 255);
+        assert!(pixels[y * 4 + 1..y * 4 + 4].iter().all(|&p| p == 0));
+    }
+}
+
+#[test]
+fn rasterize_fill_rules() {
+    let mut builder = Path::builder();
+    add_rect(&mut builder, &rect(0.0, 0.0, 3.0, 2.0));
+    add_rect(&mut builder, &rect(1.0, 0.0, 3.0, 2.0));
+    let path = builder.build();
+
+    let pixels = rasterize(&path, FillRule::NonZero, 4, 2);
+    assert_eq!(&pixels[..], &[255; 8][..]);
+
+    let pixels = rasterize(&path, FillRule::EvenOdd, 4, 2);
+    assert_eq!(&pixels[..], &[255, 0, 0, 255, 255, 0, 0, 255][..]);
+}
+
+#[test]
+fn rasterize_circle_area() {
+    let mut builder = Path::builder();
+    builder.move_to(point(18.0, 10.0));
+    for _ in 0..4 {
+        builder.arc(point(10.0, 10.0), vector(8.0, 8.0), Angle::frac_pi_2(), Angle::zero());
+    }
+    builder.close();
+    let pixels = rasterize(&builder.build(), FillRule::NonZero, 20, 20);
+
+    let area = pixels.iter().map(|&p| p as f32 / 255.0).sum::<f32>();
+    assert!((area - 64.0 * ::std::f32::consts::PI).abs() < 1.0, "{}", area);
+}
+
+#[test]
+fn rasterize_with_stride() {
+    let mut builder = Path::builder();
+    add_rect(&mut builder, &rect(0.0, 0.0, 2.0, 2.0));
+    let mut pixels = vec![7u8; 3 * 2];
+    rasterize_path(
+        builder.build().iter(),
+        FillRule::NonZero,
+        &mut MutableImageSlice::with_stride(2, 2, 3, &mut pixels),
+    );
+    assert_eq!(&pixels[..], &[255, 255, 7, 255, 255, 7]);
+}
@@ -1,4 +1,3 @@
-use std::cmp::{min, max};
 use std::ops::Add;
 
 use math::*;
@@ -9,10 +8,14 @@ type IntVector = euclid::default::Vector2D<i32>;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct IntVec4 {
-    pub x: i32,
-    pub y: i32,
-    pub z: i32,
-    pub w: i32,
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+    pub w: i64,
+}
+
+impl IntVec4 {
+    fn splat(v: i64) -> Self { IntVec4 { x: v, y: v, z: v, w: v } }
 }
 
 impl Add for IntVec4 {
@@ -27,6 +30,10 @@ impl Add for IntVec4 {
     }
 }
 
+/// Number of bits of sub-pixel precision of the vertex positions.
+pub const SUBPIXEL_BITS: u32 = 8;
+const SUBPIXEL_ONE: i64 = 1 << SUBPIXEL_BITS;
+
 /// A software triangle rasterizer intended for ref testing and to help debugging
 /// the output of the various tessellation routines.
 ///
@@ -37,116 +44,289 @@ impl Add for IntVec4 {
 /// { vertices[indices[3]], vertices[indices[4]], vertices[indices[5]] }
 /// etc.
 ///
+/// Vertex positions are snapped to a fixed point grid with `SUBPIXEL_BITS` bits of
+/// precision and pixels are sampled at their center. Pixel centers that lie exactly on
+/// an edge follow the top-left rule, so that triangles sharing an edge never touch the
+/// same pixel twice and don't leave gaps between them. Triangles can have either winding
+/// order and degenerate ones are ignored.
+///
 /// The rasterizer processes pixels by block of 4 and hands blocks containing at
-/// least one affected pixel to the ShadingStage.
-pub fn rasterize_triangles<Constants, Vertex: VertexData, Target>(
+/// least one affected pixel to the ShadingStage, along with the vertex data
+/// interpolated at the center of each pixel of the block.
+pub fn rasterize_triangles<Constants, Vertex: VertexData, Index, Target>(
     vertices: &[Vertex],
-    indices: &[u16],
+    indices: &[Index],
     constants: &Constants,
     target: &mut Target,
 ) where
+    Index: Copy + Into<u32>,
     Target: ShadingStage<Vertex, Constants>,
 {
-    // This is a naive implementation of the algorithm described in this blog post:
-    // https://fgiesen.wordpress.com/2013/02/08/triangle-rasterization-in-practice/
     let target_size = target.get_size();
-    let viewport_min_x = 0;
-    let viewport_min_y = 0;
-    let viewport_max_x = target_size.0 as i32;
-    let viewport_max_y = target_size.1 as i32;
-
-    let mut i = 0;
-    while i < indices.len() {
-        let v0 = vertices[indices[i] as usize].position().round().to_i32();
-        let v1 = vertices[indices[i + 1] as usize].position().round().to_i32();
-        let v2 = vertices[indices[i + 2] as usize].position().round().to_i32();
-
-        let min_x = max(viewport_min_x, min(v0.x, min(v1.x, v2.x)));
-        let max_x = min(viewport_max_x, max(v0.x, max(v1.x, v2.x)));
-        let min_y = max(viewport_min_y, min(v0.y, min(v1.y, v2.y)));
-        let max_y = min(viewport_max_y, max(v0.y, max(v1.y, v2.y)));
-
-        let p = int_vector(min_x, min_y);
-        let (e12_step_x, e12_step_y, mut w0_row) = init_edge(&v1, &v2, &p);
-        let (e20_step_x, e20_step_y, mut w1_row) = init_edge(&v2, &v0, &p);
-        let (e01_step_x, e01_step_y, mut w2_row) = init_edge(&v0, &v1, &p);
-
-        for y in min_y..max_y {
-            // Barycentric coordinates at the start of each row.
-            let mut w0 = w0_row;
-            let mut w1 = w1_row;
-            let mut w2 = w2_row;
-
-            let mut x = min_x;
-            while x < max_x {
-                // TODO: should implement | operator for IntVec4.
-                let mask = bvec4(
-                    (w0.x | w1.x | w2.x) >= 0,
-                    (w0.y | w1.y | w2.y) >= 0,
-                    (w0.z | w1.z | w2.z) >= 0,
-                    (w0.w | w1.w | w2.w) >= 0,
-                );
-
-                if mask.any() {
-                    target.process_block(
-                        x,
-                        y,
-                        mask,
-                        // TODO: interpolate the vertices
-                        &vertices[indices[i] as usize],
-                        constants,
-                    );
-                }
-
-                w0 = w0 + e12_step_x;
-                w1 = w1 + e20_step_x;
-                w2 = w2 + e01_step_x;
-
-                x += 4; // we process pixels by groups of 4.
+    let viewport = (target_size.0 as i64, target_size.1 as i64);
+    let guard_band_min = (-GUARD_BAND, -GUARD_BAND);
+    let guard_band_max = (
+        target_size.0 as f64 + GUARD_BAND,
+        target_size.1 as f64 + GUARD_BAND,
+    );
+
+    for triangle in indices.chunks(3) {
+        if triangle.len() < 3 {
+            break;
+        }
+
+        let a = &vertices[triangle[0].into() as usize];
+        let b = &vertices[triangle[1].into() as usize];
+        let c = &vertices[triangle[2].into() as usize];
+
+        let positions = [a.position(), b.position(), c.position()];
+        let in_guard_band = positions.iter().all(|p| {
+            p.x as f64 >= guard_band_min.0 && p.x as f64 <= guard_band_max.0
+                && p.y as f64 >= guard_band_min.1 && p.y as f64 <= guard_band_max.1
+        });
+        if in_guard_band {
+            rasterize_triangle(a, b, c, viewport, constants, target);
+            continue;
+        }
+
+        if positions.iter().any(|p| !p.x.is_finite() || !p.y.is_finite()) {
+            continue;
+        }
+
+        // Clip the triangle against the guard band and rasterize the resulting polygon
+        // as a triangle fan. The vertices of the polygon are interpolated from the
+        // vertices of the triangle.
+        let polygon = clip_to_guard_band(&positions, guard_band_min, guard_band_max);
+        let clipped: Vec<Vertex> = polygon.iter().map(|w| {
+            Vertex::interpolate(a, b, c, w[0] as f32, w[1] as f32, w[2] as f32)
+        }).collect();
+        for i in 2..clipped.len() {
+            rasterize_triangle(&clipped[0], &clipped[i - 1], &clipped[i], viewport, constants, target);
+        }
+    }
+}
+
+fn rasterize_triangle<'l, Constants, Vertex: VertexData, Target>(
+    mut a: &'l Vertex,
+    mut b: &'l Vertex,
+    c: &'l Vertex,
+    viewport: (i64, i64),
+    constants: &Constants,
+    target: &mut Target,
+) where
+    Target: ShadingStage<Vertex, Constants>,
+{
+    // This is an implementation of the algorithm described in this blog post:
+    // https://fgiesen.wordpress.com/2013/02/08/triangle-rasterization-in-practice/
+    let mut v0 = to_fixed(a.position());
+    let mut v1 = to_fixed(b.position());
+    let v2 = to_fixed(c.position());
+
+    // Make the edge functions positive inside of the triangle.
+    let mut area = edge_function(v0, v1, v2);
+    if area == 0 {
+        return;
+    }
+    if area < 0 {
+        ::std::mem::swap(&mut a, &mut b);
+        ::std::mem::swap(&mut v0, &mut v1);
+        area = -area;
+    }
+
+    // Bounding box of the pixels whose center may be covered.
+    let min_x = pixel_start(v0.0.min(v1.0).min(v2.0)).max(0);
+    let max_x = pixel_end(v0.0.max(v1.0).max(v2.0)).min(viewport.0);
+    let min_y = pixel_start(v0.1.min(v1.1).min(v2.1)).max(0);
+    let max_y = pixel_end(v0.1.max(v1.1).max(v2.1)).min(viewport.1);
+    if min_x >= max_x || min_y >= max_y {
+        return;
+    }
+
+    let origin = (min_x, min_y);
+    let e12 = Edge::new(v1, v2, origin);
+    let e20 = Edge::new(v2, v0, origin);
+    let e01 = Edge::new(v0, v1, origin);
+    let mut w0_row = e12.row;
+    let mut w1_row = e20.row;
+    let mut w2_row = e01.row;
+
+    let inv_area = 1.0 / area as f32;
+
+    for y in min_y..max_y {
+        // Barycentric coordinates at the start of each row.
+        let mut w0 = w0_row;
+        let mut w1 = w1_row;
+        let mut w2 = w2_row;
+
+        let mut x = min_x;
+        while x < max_x {
+            let inside = |lane: usize, w0: i64, w1: i64, w2: i64| {
+                x + (lane as i64) < max_x
+                    && w0 + e12.bias >= 0
+                    && w1 + e20.bias >= 0
+                    && w2 + e01.bias >= 0
+            };
+            let mask = bvec4(
+                inside(0, w0.x, w1.x, w2.x),
+                inside(1, w0.y, w1.y, w2.y),
+                inside(2, w0.z, w1.z, w2.z),
+                inside(3, w0.w, w1.w, w2.w),
+            );
+
+            if mask.any() {
+                let interpolate = |w0: i64, w1: i64, w2: i64| {
+                    Vertex::interpolate(
+                        a, b, c,
+                        w0 as f32 * inv_area,
+                        w1 as f32 * inv_area,
+                        w2 as f32 * inv_area,
+                    )
+                };
+                let block = [
+                    interpolate(w0.x, w1.x, w2.x),
+                    interpolate(w0.y, w1.y, w2.y),
+                    interpolate(w0.z, w1.z, w2.z),
+                    interpolate(w0.w, w1.w, w2.w),
+                ];
+
+                target.process_block(x as i32, y as i32, mask, &block, constants);
             }
 
-            w0_row = w0_row + e12_step_y;
-            w1_row = w1_row + e20_step_y;
-            w2_row = w2_row + e01_step_y;
+            w0 = w0 + e12.step_x;
+            w1 = w1 + e20.step_x;
+            w2 = w2 + e01.step_x;
+
+            x += PX_GROUP_X; // we process pixels by groups of 4.
         }
 
-        i += 3;
+        w0_row = w0_row + e12.step_y;
+        w1_row = w1_row + e20.step_y;
+        w2_row = w2_row + e01.step_y;
     }
 }
 
+/// Distance in pixels beyond the edges of the target at which triangles are clipped.
+///
+/// Snapping vertices that are further away to the fixed point grid could overflow the
+/// edge functions.
+const GUARD_BAND: f64 = (1 << 20) as f64;
+
+const PX_GROUP_X: i64 = 4;
+const PX_GROUP_Y: i64 = 1;
+
+type FixedPoint = (i64, i64);
+
+fn to_fixed(v: Vector) -> FixedPoint {
+    (
+        (v.x * SUBPIXEL_ONE as f32).round() as i64,
+        (v.y * SUBPIXEL_ONE as f32).round() as i64,
+    )
+}
 
-const PX_GROUP_X: i32 = 4;
-const PX_GROUP_Y: i32 = 1;
+// Clips a triangle against the guard band using the Sutherland-Hodgman algorithm and
+// returns the barycentric coordinates of the vertices of the resulting polygon.
+fn clip_to_guard_band(positions: &[Vector; 3], min: (f64, f64), max: (f64, f64)) -> Vec<[f64; 3]> {
+    let mut polygon = vec![
+        (positions[0].x as f64, positions[0].y as f64, [1.0, 0.0, 0.0]),
+        (positions[1].x as f64, positions[1].y as f64, [0.0, 1.0, 0.0]),
+        (positions[2].x as f64, positions[2].y as f64, [0.0, 0.0, 1.0]),
+    ];
+
+    // Signed distances to the sides of the guard band, positive inside.
+    let sides: [&dyn Fn(f64, f64) -> f64; 4] = [
+        &|x, _| x - min.0,
+        &|x, _| max.0 - x,
+        &|_, y| y - min.1,
+        &|_, y| max.1 - y,
+    ];
+
+    for distance in &sides {
+        let input = ::std::mem::take(&mut polygon);
+        for (i, &(x0, y0, w0)) in input.iter().enumerate() {
+            let (x1, y1, w1) = input[(i + 1) % input.len()];
+            let d0 = distance(x0, y0);
+            let d1 = distance(x1, y1);
+            if d0 >= 0.0 {
+                polygon.push((x0, y0, w0));
+            }
+            if (d0 >= 0.0) != (d1 >= 0.0) {
+                let t = d0 / (d0 - d1);
+                let lerp = |a: f64, b: f64| a + (b - a) * t;
+                polygon.push((
+                    lerp(x0, x1),
+                    lerp(y0, y1),
+                    [lerp(w0[0], w1[0]), lerp(w0[1], w1[1]), lerp(w0[2], w1[2])],
+                ));
+            }
+        }
+    }
 
-fn init_edge(v0: &IntVector, v1: &IntVector, origin: &IntVector) -> (IntVec4, IntVec4, IntVec4) {
-    let a = v0.y - v1.y;
-    let b = v1.x - v0.x;
-    let c = v0.x * v1.y - v0.y * v1.x;
+    polygon.iter().map(|&(_, _, w)| w).collect()
+}
 
-    let sx = a * PX_GROUP_X;
-    let sy = b * PX_GROUP_Y;
-    let step_x = IntVec4 { x: sx, y: sx, z: sx, w: sx };
-    let step_y = IntVec4 { x: sy, y: sy, z: sy, w: sy };
+// Index of the first pixel with a center at or after the fixed point coordinate.
+fn pixel_start(v: i64) -> i64 {
+    (v - SUBPIXEL_ONE / 2 + SUBPIXEL_ONE - 1).div_euclid(SUBPIXEL_ONE)
+}
 
-    let swizzling_x = IntVec4 { x: 0, y: 1, z: 2, w: 3 };
-    let swizzling_y = IntVec4 { x: 0, y: 0, z: 0, w: 0 };
-    let dx = IntVec4 { x: origin.x, y: origin.x, z: origin.x, w: origin.x } + swizzling_x;
-    let dy = IntVec4 { x: origin.y, y: origin.y, z: origin.y, w: origin.y } + swizzling_y;
-    let row = IntVec4 { x: a * dx.x, y: a * dx.y, z: a * dx.z, w: a * dx.w }
-        + IntVec4 { x: b * dy.x, y: b * dy.y, z: b * dy.w, w: b * dy.w }
-        + IntVec4 { x: c, y: c, z: c, w: c };
+// Index after the last pixel with a center at or before the fixed point coordinate.
+fn pixel_end(v: i64) -> i64 {
+    (v - SUBPIXEL_ONE / 2).div_euclid(SUBPIXEL_ONE) + 1
+}
 
-    return (step_x, step_y, row);
+// Twice the signed area of the triangle (v0, v1, p), positive when p is on the right
+// of v0 -> v1 (with the y axis pointing down).
+fn edge_function(v0: FixedPoint, v1: FixedPoint, p: FixedPoint) -> i64 {
+    (v1.0 - v0.0) * (p.1 - v0.1) - (v1.1 - v0.1) * (p.0 - v0.0)
 }
 
+struct Edge {
+    step_x: IntVec4,
+    step_y: IntVec4,
+    // The edge function at the center of the 4 first pixels of the block at the origin.
+    row: IntVec4,
+    // Makes the pixel centers exactly on the edge fail the inside test unless the
+    // edge is a top or left edge.
+    bias: i64,
+}
 
+impl Edge {
+    fn new(v0: FixedPoint, v1: FixedPoint, origin: (i64, i64)) -> Self {
+        let a = v0.1 - v1.1;
+        let b = v1.0 - v0.0;
+
+        let sample = |x: i64| {
+            let p = (
+                (origin.0 + x) * SUBPIXEL_ONE + SUBPIXEL_ONE / 2,
+                origin.1 * SUBPIXEL_ONE + SUBPIXEL_ONE / 2,
+            );
+            edge_function(v0, v1, p)
+        };
+
+        let is_top_left = (a == 0 && b > 0) || a > 0;
+
+        Edge {
+            step_x: IntVec4::splat(a * SUBPIXEL_ONE * PX_GROUP_X),
+            step_y: IntVec4::splat(b * SUBPIXEL_ONE * PX_GROUP_Y),
+            row: IntVec4 { x: sample(0), y: sample(1), z: sample(2), w: sample(3) },
+            bias: if is_top_left { 0 } else { -1 },
+        }
+    }
+}
+
+
+/// Receives the blocks of pixels produced by `rasterize_triangles`.
 pub trait ShadingStage<Vertex, Constants> {
+    /// Processes the pixels `(x, y)` to `(x + 3, y)` for which the mask is set.
+    ///
+    /// `vertices` contains the vertex data interpolated at the center of each of the
+    /// four pixels.
     fn process_block(
         &mut self,
         x: i32,
         y: i32,
         mask: BoolVec4,
-        vertex: &Vertex,
+        vertices: &[Vertex; 4],
         constants: &Constants,
     );
     fn get_size(&self) -> (usize, usize);
@@ -159,15 +339,16 @@ pub trait PixelShader<Pixel, Vertex, Constants> {
 
 // Vertices must implement this trait
 pub trait VertexData {
+    /// Blends three vertices using barycentric coordinates that sum up to one.
     fn interpolate(v1: &Self, v2: &Self, v3: &Self, w1: f32, w2: f32, w3: f32) -> Self;
     fn position(&self) -> Vector;
 }
 
 /// A simple shader that returns the interpolated vertex color.
-struct FillVertexColor;
+pub struct FillVertexColor;
 
 /// A simple shader that returns the constant color.
-struct FillConstantColor;
+pub struct FillConstantColor;
 
 /// Implemented vertices and constants that can return a color.
 pub trait GetColor<Pixel> {
@@ -194,11 +375,26 @@ impl VertexData for Vector {
     fn position(&self) -> Vector { *self }
 }
 
+impl VertexData for Point {
+    fn interpolate(a: &Point, b: &Point, c: &Point, wa: f32, wb: f32, wc: f32) -> Point {
+        Vector::interpolate(&a.to_vector(), &b.to_vector(), &c.to_vector(), wa, wb, wc).to_point()
+    }
+
+    fn position(&self) -> Vector { self.to_vector() }
+}
+
+/// A shading stage that writes the output of a pixel shader into an image.
 pub struct ColorTarget<'a, 'b: 'a, Pixel: Copy + 'static, Shader> {
     target: &'a mut MutableImageSlice<'b, Pixel>,
     shader: Shader,
 }
 
+impl<'a, 'b: 'a, Pixel: Copy + 'static, Shader> ColorTarget<'a, 'b, Pixel, Shader> {
+    pub fn new(target: &'a mut MutableImageSlice<'b, Pixel>, shader: Shader) -> Self {
+        ColorTarget { target, shader }
+    }
+}
+
 impl<'l, 'm, Pixel, Vertex, Constants, Shader> ShadingStage<Vertex, Constants>
 for ColorTarget<'l, 'm, Pixel, Shader>
 where
@@ -210,29 +406,16 @@ where
         x: i32,
         y: i32,
         mask: BoolVec4,
-        vertex: &Vertex,
+        vertices: &[Vertex; 4],
         constants: &Constants,
     ) {
         // This is pretty slow, the shader should process blocks instead of pixels, etc.
-        if mask.x {
-            let i0 = self.target.pixel_offset(x as usize, y as usize);
-            let p = self.target.pixels[i0];
-            self.target.pixels[i0] = Shader::shade(p, vertex, constants);
-        }
-        if mask.y {
-            let i1 = self.target.pixel_offset(x as usize + 1, y as usize);
-            let p = self.target.pixels[i1];
-            self.target.pixels[i1] = Shader::shade(p, vertex, constants);
-        }
-        if mask.z {
-            let i2 = self.target.pixel_offset(x as usize + 2, y as usize);
-            let p = self.target.pixels[i2];
-            self.target.pixels[i2] = Shader::shade(p, vertex, constants);
-        }
-        if mask.w {
-            let i3 = self.target.pixel_offset(x as usize + 3, y as usize);
-            let p = self.target.pixels[i3];
-            self.target.pixels[i3] = Shader::shade(p, vertex, constants);
+        for (i, &covered) in mask.array().iter().enumerate() {
+            if covered {
+                let offset = self.target.pixel_offset(x as usize + i, y as usize);
+                let p = self.target.pixels[offset];
+                self.target.pixels[offset] = Shader::shade(p, &vertices[i], constants);
+            }
         }
     }
 
@@ -240,60 +423,215 @@ where
 }
 
 
+#[cfg(test)]
+struct Constants {
+    color: u8,
+}
+
+#[cfg(test)]
+impl GetColor<u8> for Constants {
+    fn get_color(&self) -> u8 { self.color }
+}
+
+/// Adds the constant color to the pixel, to count how many times each pixel is touched.
+#[cfg(test)]
+struct AddConstantColor;
+
+#[cfg(test)]
+impl<Vertex> PixelShader<u8, Vertex, Constants> for AddConstantColor {
+    fn shade(pixel: u8, _: &Vertex, constants: &Constants) -> u8 { pixel + constants.color }
+}
+
+#[cfg(test)]
+fn count_pixels<Index: Copy + Into<u32>>(
+    vertices: &[Vector],
+    indices: &[Index],
+    width: usize,
+    height: usize,
+) -> Vec<u8> {
+    let mut pixels = vec![0; width * height];
+    rasterize_triangles(
+        vertices,
+        indices,
+        &Constants { color: 1 },
+        &mut ColorTarget::new(
+            &mut MutableImageSlice::new(width, height, &mut pixels),
+            AddConstantColor,
+        ),
+    );
+    pixels
+}
 
 #[test]
 fn test_rasterizer_simple() {
     // This test rasterizes two triangles which should produce a square of origin
-    // (10, 10) and size (80, 80).
+    // (2, 2) and size (8, 8), the diagonal going through the center of the pixels.
+    let vertices = &[
+        vector(2.0, 2.0),
+        vector(10.0, 2.0),
+        vector(10.0, 10.0),
+        vector(2.0, 10.0),
+    ];
+
+    let indices: &[u16] = &[
+        0, 1, 2,
+        0, 2, 3,
+    ];
+
+    let pixels = count_pixels(vertices, indices, 16, 16);
+    for y in 0..16 {
+        for x in 0..16 {
+            let inside = x >= 2 && x < 10 && y >= 2 && y < 10;
+            assert_eq!(pixels[y * 16 + x], inside as u8, "pixel {} {}", x, y);
+        }
+    }
+}
 
-    struct Constants {
-        color: u8,
+#[test]
+fn test_rasterizer_shared_edges() {
+    // A fan of triangles with both winding orders around a point in the middle of a
+    // pixel. Every covered pixel must be touched exactly once.
+    let vertices = &[
+        vector(4.5, 4.5),
+        vector(0.0, 0.0),
+        vector(4.5, 0.0),
+        vector(9.0, 0.0),
+        vector(9.0, 4.5),
+        vector(9.0, 9.0),
+        vector(4.5, 9.0),
+        vector(0.0, 9.0),
+        vector(0.0, 4.5),
+    ];
+    let indices: &[u32] = &[
+        0, 1, 2,
+        0, 3, 2,
+        0, 3, 4,
+        0, 5, 4,
+        0, 5, 6,
+        0, 7, 6,
+        0, 7, 8,
+        0, 1, 8,
+    ];
+
+    let pixels = count_pixels(vertices, indices, 12, 12);
+    for y in 0..12 {
+        for x in 0..12 {
+            let inside = x < 9 && y < 9;
+            assert_eq!(pixels[y * 12 + x], inside as u8, "pixel {} {}", x, y);
+        }
     }
-    impl GetColor<u8> for Constants {
-        fn get_color(&self) -> u8 { self.color }
+}
+
+#[test]
+fn test_rasterizer_subpixel() {
+    // Rounding the vertices would move the left edge across the center of the pixels
+    // in the third column.
+    let vertices = &[
+        vector(2.4, 0.0),
+        vector(6.0, 0.0),
+        vector(6.0, 2.0),
+        vector(2.6, 2.0),
+        vector(6.0, 4.0),
+        vector(2.6, 4.0),
+    ];
+    let indices: &[u16] = &[
+        0, 1, 2,
+        0, 2, 3,
+        3, 2, 4,
+        3, 4, 5,
+    ];
+
+    let pixels = count_pixels(vertices, indices, 8, 4);
+    // The left edge goes from x = 2.4 to x = 2.6 in the first two rows.
+    assert_eq!(&pixels[0..8], &[0, 0, 1, 1, 1, 1, 0, 0]);
+    assert_eq!(&pixels[8..16], &[0, 0, 0, 1, 1, 1, 0, 0]);
+    assert_eq!(&pixels[16..24], &[0, 0, 0, 1, 1, 1, 0, 0]);
+
+    // Triangles outside of the target or too thin to cover a pixel center.
+    let vertices = &[
+        vector(-5.0, -5.0),
+        vector(-1.0, -5.0),
+        vector(-1.0, 20.0),
+        vector(1.6, 1.0),
+        vector(1.6, 3.0),
+        vector(1.55, 3.0),
+    ];
+    let pixels = count_pixels(vertices, &[0u16, 1, 2, 3, 4, 5], 8, 4);
+    assert!(pixels.iter().all(|&p| p == 0));
+}
+
+#[test]
+fn test_rasterizer_far_vertices() {
+    // Vertices this far away would overflow the edge functions if they weren't
+    // clipped against the guard band first. The clipped triangle is rasterized as
+    // a fan, which must not touch any pixel twice.
+    let vertices = &[
+        vector(2.0, 2.0),
+        vector(1.0e9, 2.0),
+        vector(2.0, 1.0e9),
+        vector(-3.0e9, 0.0),
+        vector(-2.0e9, 0.0),
+        vector(-2.0e9, 1.0e9),
+    ];
+    let indices: &[u16] = &[
+        0, 1, 2,
+        3, 4, 5,
+    ];
+
+    let pixels = count_pixels(vertices, indices, 16, 16);
+    for y in 0..16 {
+        for x in 0..16 {
+            let inside = x >= 2 && y >= 2;
+            assert_eq!(pixels[y * 16 + x], inside as u8, "pixel {} {}", x, y);
+        }
     }
+}
 
-    let mut buffer = Box::new([0; 256]);
-    let mut surface = MutableImageSlice::new(16, 16, &mut *buffer);
-
-    {
-        let vertices = &[
-            vector(0.0, 0.0),
-            vector(8.0, 0.0),
-            vector(0.0, 8.0),
-
-            vector(15.0, 15.0),
-            vector(1.0, 15.0),
-        ];
-
-        let indices = &[
-            0, 1, 2,
-            //0, 2, 3,
-            1, 3, 4,
-        ];
-
-        // Fill the two triangles with the constant 1.
-        rasterize_triangles(
-            vertices,
-            indices,
-            &Constants { color: 1 },
-            &mut ColorTarget {
-                     target: &mut surface,
-                     shader: FillConstantColor,
-                 },
-        );
+#[test]
+fn test_rasterizer_interpolation() {
+    #[derive(Copy, Clone, Debug)]
+    struct Vertex {
+        position: Vector,
+        value: f32,
     }
 
-    for i in 0..(surface.width * surface.height) {
-        if i % surface.width == 0 {
-            println!(" |");
-            print!("          |");
+    impl VertexData for Vertex {
+        fn interpolate(a: &Self, b: &Self, c: &Self, wa: f32, wb: f32, wc: f32) -> Self {
+            Vertex {
+                position: Vector::interpolate(&a.position, &b.position, &c.position, wa, wb, wc),
+                value: a.value * wa + b.value * wb + c.value * wc,
+            }
         }
-        let pix = surface.pixels[i];
-        if pix == 0 {
-            print!(" .");
-        } else {
-            print!(" {}", pix);
+
+        fn position(&self) -> Vector { self.position }
+    }
+
+    impl GetColor<u8> for Vertex {
+        fn get_color(&self) -> u8 { self.value.round() as u8 }
+    }
+
+    // The value is 8 times the x coordinate.
+    let vertices = &[
+        Vertex { position: vector(0.0, 0.0), value: 0.0 },
+        Vertex { position: vector(0.0, 16.0), value: 0.0 },
+        Vertex { position: vector(16.0, 0.0), value: 128.0 },
+    ];
+
+    let mut pixels = vec![0; 16 * 16];
+    rasterize_triangles(
+        vertices,
+        &[0u32, 1, 2],
+        &(),
+        &mut ColorTarget::new(
+            &mut MutableImageSlice::new(16, 16, &mut pixels),
+            FillVertexColor,
+        ),
+    );
+
+    for y in 0..16 {
+        for x in 0..16 {
+            let expected = if x + y < 15 { x as u8 * 8 + 4 } else { 0 };
+            assert_eq!(pixels[y * 16 + x], expected, "pixel {} {}", x, y);
         }
     }
 }
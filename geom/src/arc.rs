@@ -3,13 +3,14 @@
 use std::ops::Range;
 use std::mem::swap;
 
-use crate::Line;
+use crate::{Line, LineSegment};
 use crate::scalar::{Scalar, Float, cast};
 use crate::generic_math::{Point, point, Vector, vector, Rotation2D, Transform2D, Angle, Rect};
 use crate::segment::{Segment, FlattenedForEach, FlatteningStep, BoundingRect};
 use crate::segment;
 use crate::QuadraticBezierSegment;
 use crate::CubicBezierSegment;
+use arrayvec::ArrayVec;
 
 /// A flattening iterator for arc segments.
 pub type Flattened<S> = segment::Flattened<S, Arc<S>>;
@@ -195,6 +196,70 @@ impl<S: Scalar> Arc<S> {
         self.tangent_at_angle(self.get_angle(t))
     }
 
+    /// Sample the curve's second derivative at t (expecting t between 0 and 1).
+    ///
+    /// Note that unlike `sample_tangent`, this takes the sweep angle into account.
+    #[inline]
+    pub fn second_derivative(&self, t: S) -> Vector<S> {
+        let a = self.get_angle(t).get();
+        let sweep = self.sweep_angle.get();
+        Rotation2D::new(self.x_rotation).transform_vector(
+            vector(-self.radii.x * Float::cos(a), -self.radii.y * Float::sin(a))
+        ) * (sweep * sweep)
+    }
+
+    /// Sample the signed curvature at t (expecting t between 0 and 1).
+    ///
+    /// See `Segment::curvature`.
+    pub fn curvature(&self, t: S) -> S {
+        let derivative = self.sample_tangent(t) * self.sweep_angle.get();
+        segment::curvature(derivative, self.second_derivative(t))
+    }
+
+    /// Find the parameter t of the point of the arc that is closest to a given position.
+    pub fn closest_point_t(&self, p: Point<S>) -> S {
+        let sweep = self.sweep_angle.get();
+        if sweep == S::ZERO {
+            return S::ZERO;
+        }
+
+        if self.radii.x != self.radii.y {
+            let arc = *self;
+            return segment::closest_point_t_by_sampling(
+                p,
+                |t| arc.sample(t),
+                |t| arc.sample_tangent(t) * sweep,
+                |t| arc.second_derivative(t),
+                cast::<S, u32>(S::max(S::ceil(S::abs(sweep) * S::FOUR), S::FOUR)).unwrap(),
+            );
+        }
+
+        // For circles, the closest point is in the direction of the position.
+        let v = p - self.center;
+        if v.square_length() == S::ZERO {
+            return S::ZERO;
+        }
+
+        let two_pi = S::TWO * S::PI();
+        let angle = Float::atan2(v.y, v.x) - self.x_rotation.get() - self.start_angle.get();
+        // Angle between the start of the arc and the position in the direction of the sweep.
+        let mut delta = angle % two_pi;
+        if sweep > S::ZERO && delta < S::ZERO {
+            delta += two_pi;
+        } else if sweep < S::ZERO && delta > S::ZERO {
+            delta -= two_pi;
+        }
+
+        let t = delta / sweep;
+        if t <= S::ONE {
+            return t;
+        }
+
+        let d0 = (self.from() - p).square_length();
+        let d1 = (self.to() - p).square_length();
+        if d0 < d1 { S::ZERO } else { S::ONE }
+    }
+
     /// Sample the curve's angle at t (expecting t between 0 and 1).
     #[inline]
     pub fn get_angle(&self, t: S) -> Angle<S> {
@@ -424,6 +489,242 @@ impl<S: Scalar> Arc<S> {
         segment::approximate_length_from_flattening(self, tolerance)
     }
 
+    /// Computes the intersections (if any) between this arc and a line.
+    ///
+    /// The result is provided in the form of the `t` parameters of each point along the
+    /// arc, including its endpoints. To get the intersection points, sample the arc at the
+    /// corresponding values.
+    pub fn line_intersections_t(&self, line: &Line<S>) -> ArrayVec<[S; 2]> {
+        let mut result = ArrayVec::new();
+        if line.vector.square_length() < S::EPSILON || !self.has_area() {
+            return result;
+        }
+
+        // Intersect the line with the unit circle in the ellipse's local space.
+        let p = self.unit_space_point(line.point).to_vector();
+        let v = self.unit_space_vector(line.vector);
+
+        let a = v.square_length();
+        let b = S::TWO * p.dot(v);
+        let c = p.square_length() - S::ONE;
+        let delta = b * b - S::FOUR * a * c;
+        if delta < S::ZERO {
+            return result;
+        }
+
+        let sqrt_delta = S::sqrt(delta);
+        let mut push = |u: S| {
+            let q = p + v * u;
+            if let Some(t) = self.t_for_angle(Float::atan2(q.y, q.x)) {
+                result.push(t);
+            }
+        };
+
+        push((-b - sqrt_delta) / (S::TWO * a));
+        if sqrt_delta > S::ZERO {
+            push((-b + sqrt_delta) / (S::TWO * a));
+        }
+
+        result
+    }
+
+    /// Computes the intersections (if any) between this arc and a line segment.
+    ///
+    /// The result is provided in the form of the `t` parameters of each point along the
+    /// arc and the segment, including their endpoints.
+    pub fn line_segment_intersections_t(&self, segment: &LineSegment<S>) -> ArrayVec<[(S, S); 2]> {
+        let mut result = ArrayVec::new();
+        if !self.has_area() || !self.fast_bounding_rect().inflate(S::EPSILON, S::EPSILON).intersects(
+            &segment.bounding_rect().inflate(S::EPSILON, S::EPSILON)
+        ) {
+            return result;
+        }
+
+        let v = segment.to_vector();
+        let inv_square_length = S::ONE / v.square_length();
+        for t in self.line_intersections_t(&segment.to_line()) {
+            let t2 = (self.sample(t) - segment.from).dot(v) * inv_square_length;
+            if t2 >= -S::EPSILON && t2 <= S::ONE + S::EPSILON {
+                result.push((t, S::min(S::max(t2, S::ZERO), S::ONE)));
+            }
+        }
+
+        result
+    }
+
+    /// Computes the intersections (if any) between two arcs.
+    ///
+    /// The result is provided in the form of the `t` parameters of each point along
+    /// both arcs, including their endpoints.
+    ///
+    /// Returns no intersections if the arcs are on the same ellipse. If the other arc
+    /// sweeps more than a full revolution, only the first four intersections are returned.
+    pub fn arc_intersections_t(&self, other: &Arc<S>) -> ArrayVec<[(S, S); 4]> {
+        let mut result = ArrayVec::new();
+        if !self.has_area() || !other.has_area() || self.same_ellipse(other) {
+            return result;
+        }
+
+        let num_samples = S::max(S::abs(other.sweep_angle.get()) * S::EIGHT, S::EIGHT);
+        self.unit_circle_intersections(
+            |t| other.sample(t),
+            cast::<S, u32>(Float::ceil(num_samples)).unwrap(),
+            &mut |t1, t2| {
+                if !result.is_full() {
+                    result.push((t1, t2));
+                }
+            },
+        );
+
+        result
+    }
+
+    /// Computes the intersections (if any) between this arc and a quadratic bézier segment.
+    ///
+    /// The result is provided in the form of the `t` parameters of each point along the
+    /// arc and the curve, including their endpoints.
+    pub fn quadratic_intersections_t(&self, curve: &QuadraticBezierSegment<S>) -> ArrayVec<[(S, S); 4]> {
+        let mut result = ArrayVec::new();
+        if !self.has_area() || !self.fast_bounding_rect().intersects(&curve.fast_bounding_rect()) {
+            return result;
+        }
+
+        self.unit_circle_intersections(
+            |t| curve.sample(t),
+            32,
+            &mut |t1, t2| {
+                if !result.is_full() {
+                    result.push((t1, t2));
+                }
+            },
+        );
+
+        result
+    }
+
+    /// Computes the intersections (if any) between this arc and a cubic bézier segment.
+    ///
+    /// The result is provided in the form of the `t` parameters of each point along the
+    /// arc and the curve, including their endpoints.
+    pub fn cubic_intersections_t(&self, curve: &CubicBezierSegment<S>) -> ArrayVec<[(S, S); 6]> {
+        let mut result = ArrayVec::new();
+        if !self.has_area() || !self.fast_bounding_rect().intersects(&curve.fast_bounding_rect()) {
+            return result;
+        }
+
+        self.unit_circle_intersections(
+            |t| curve.sample(t),
+            48,
+            &mut |t1, t2| {
+                if !result.is_full() {
+                    result.push((t1, t2));
+                }
+            },
+        );
+
+        result
+    }
+
+    // Finds the parameters where a curve crosses this arc.
+    //
+    // In the local space of the ellipse where it is the unit circle, the intersections
+    // are the roots of `|curve(t)|² - 1`. They are bracketed by sampling the curve and
+    // refined by bisection. Curves that touch the ellipse without crossing it may be missed.
+    fn unit_circle_intersections<F>(&self, curve: F, num_samples: u32, cb: &mut dyn FnMut(S, S))
+    where
+        F: Fn(S) -> Point<S>,
+    {
+        let f = |t: S| self.unit_space_point(curve(t)).to_vector().square_length() - S::ONE;
+
+        let mut report = |t2: S| {
+            let p = self.unit_space_point(curve(t2));
+            if let Some(t1) = self.t_for_angle(Float::atan2(p.y, p.x)) {
+                cb(t1, t2);
+            }
+        };
+
+        let mut t0 = S::ZERO;
+        let mut f0 = f(t0);
+        if f0 == S::ZERO {
+            report(t0);
+        }
+        for i in 1..=num_samples {
+            let t1 = S::value(i as f32) / S::value(num_samples as f32);
+            let f1 = f(t1);
+            if f1 == S::ZERO {
+                report(t1);
+            } else if (f0 < S::ZERO && f1 > S::ZERO) || (f0 > S::ZERO && f1 < S::ZERO) {
+                let (mut a, mut b) = (t0, t1);
+                for _ in 0..64 {
+                    let mid = (a + b) * S::HALF;
+                    if mid <= a || mid >= b {
+                        break;
+                    }
+                    let f_mid = f(mid);
+                    if (f_mid < S::ZERO) == (f0 < S::ZERO) {
+                        a = mid;
+                    } else {
+                        b = mid;
+                    }
+                }
+                report((a + b) * S::HALF);
+            }
+            t0 = t1;
+            f0 = f1;
+        }
+    }
+
+    // Maps a point to the local space of the ellipse where it is the unit circle.
+    fn unit_space_point(&self, p: Point<S>) -> Point<S> {
+        self.unit_space_vector(p - self.center).to_point()
+    }
+
+    fn unit_space_vector(&self, v: Vector<S>) -> Vector<S> {
+        let v = Rotation2D::new(-self.x_rotation).transform_vector(v);
+        vector(v.x / self.radii.x, v.y / self.radii.y)
+    }
+
+    // Returns the parameter of the point of the arc at a given angle of the ellipse, if
+    // the arc contains it.
+    fn t_for_angle(&self, angle: S) -> Option<S> {
+        let sweep = self.sweep_angle.get();
+        if sweep == S::ZERO {
+            return None;
+        }
+
+        let two_pi = S::TWO * S::PI();
+        let mut delta = (angle - self.start_angle.get()) % two_pi;
+        if sweep > S::ZERO && delta < S::ZERO {
+            delta += two_pi;
+        } else if sweep < S::ZERO && delta > S::ZERO {
+            delta -= two_pi;
+        }
+
+        // Angles close to the start of the arc can end up on the wrong side of the
+        // discontinuity.
+        let epsilon = S::EPSILON;
+        let t = delta / sweep;
+        if t <= S::ONE + epsilon {
+            return Some(S::min(t, S::ONE));
+        }
+        let t = (delta - two_pi * sweep.signum()) / sweep;
+        if t >= -epsilon {
+            return Some(S::max(t, S::ZERO));
+        }
+
+        None
+    }
+
+    fn has_area(&self) -> bool {
+        self.radii.x > S::ZERO && self.radii.y > S::ZERO
+    }
+
+    fn same_ellipse(&self, other: &Self) -> bool {
+        self.center == other.center
+            && ((self.radii == other.radii && (self.x_rotation.get() - other.x_rotation.get()) % S::PI() == S::ZERO)
+                || (self.radii.x == self.radii.y && other.radii.x == other.radii.y && self.radii.x == other.radii.x))
+    }
+
     #[inline]
     fn tangent_at_angle(&self, angle: Angle<S>) -> Vector<S> {
         let a = angle.get();
@@ -594,6 +895,9 @@ impl<S: Scalar> Segment for Arc<S> {
     fn x(&self, t: S) -> S { self.x(t) }
     fn y(&self, t: S) -> S { self.y(t) }
     fn derivative(&self, t: S) -> Vector<S> { self.sample_tangent(t) }
+    fn second_derivative(&self, t: S) -> Vector<S> { self.second_derivative(t) }
+    fn curvature(&self, t: S) -> S { self.curvature(t) }
+    fn closest_point_t(&self, p: Point<S>) -> S { self.closest_point_t(p) }
     fn split_range(&self, t_range: Range<S>) -> Self { self.split_range(t_range) }
     fn split(&self, t: S) -> (Self, Self) { self.split(t) }
     fn before_split(&self, t: S) -> Self { self.before_split(t) }
@@ -893,3 +1197,157 @@ fn negative_flattening_step() {
 
     arc.for_each_flattened(0.100000001, &mut|_|{});
 }
+
+#[test]
+fn closest_point_and_curvature() {
+    let arc = Arc {
+        center: point(0.0f32, 0.0),
+        radii: vector(1.0, 1.0),
+        start_angle: Angle::zero(),
+        sweep_angle: Angle::frac_pi_2(),
+        x_rotation: Angle::zero(),
+    };
+
+    assert!((arc.closest_point_t(point(2.0, 2.0)) - 0.5).abs() < 1e-5);
+    assert!((arc.closest_point_t(point(0.5, 0.0))).abs() < 1e-5);
+    assert_eq!(arc.closest_point_t(point(1.0, -1.0)), 0.0);
+    assert_eq!(arc.closest_point_t(point(-1.0, 0.5)), 1.0);
+    assert!((arc.flip().closest_point_t(point(2.0, 2.0)) - 0.5).abs() < 1e-5);
+    assert!((arc.flip().closest_point_t(point(1.0, 0.1)) - 1.0).abs() < 0.1);
+
+    assert!((arc.curvature(0.3) - 1.0).abs() < 1e-5);
+    assert!((arc.flip().curvature(0.3) + 1.0).abs() < 1e-5);
+
+    let ellipse = Arc {
+        center: point(1.0, 2.0),
+        radii: vector(4.0, 1.0),
+        start_angle: Angle::radians(0.5),
+        sweep_angle: Angle::radians(4.0),
+        x_rotation: Angle::radians(0.3),
+    };
+
+    for p in &[point(0.0, 0.0), point(6.0, 3.0), point(-4.0, 1.0), point(1.0, 2.5)] {
+        let t = ellipse.closest_point_t(*p);
+        let d = (ellipse.sample(t) - *p).length();
+        for i in 0..=1000 {
+            let other = i as f32 / 1000.0;
+            assert!(d <= (ellipse.sample(other) - *p).length() + 1e-3, "{:?} {:?}", p, t);
+        }
+    }
+
+    // The curvature of an ellipse is a / b² at the ends of its major axis and b / a²
+    // at the ends of its minor axis.
+    let ellipse = Arc {
+        center: point(0.0, 0.0),
+        radii: vector(4.0, 1.0),
+        start_angle: Angle::zero(),
+        sweep_angle: Angle::pi(),
+        x_rotation: Angle::zero(),
+    };
+    assert!((ellipse.curvature(0.0) - 4.0).abs() < 1e-4);
+    assert!((ellipse.curvature(0.5) - 0.0625).abs() < 1e-4);
+}
+
+#[test]
+fn arc_line_intersections() {
+    let arc = Arc {
+        center: point(0.0f32, 0.0),
+        radii: vector(1.0, 1.0),
+        start_angle: Angle::zero(),
+        sweep_angle: Angle::frac_pi_2(),
+        x_rotation: Angle::zero(),
+    };
+
+    let line = Line { point: point(0.0, 0.0), vector: vector(1.0, 1.0) };
+    let result = arc.line_intersections_t(&line);
+    assert_eq!(result.len(), 1);
+    assert!((result[0] - 0.5).abs() < 1e-5);
+
+    let segment = LineSegment { from: point(0.0, 0.0), to: point(2.0, 2.0) };
+    let result = arc.line_segment_intersections_t(&segment);
+    assert_eq!(result.len(), 1);
+    assert!((result[0].0 - 0.5).abs() < 1e-5);
+    assert!((result[0].1 - f32::sqrt(0.5) * 0.5).abs() < 1e-5);
+
+    let segment = LineSegment { from: point(2.0, 2.0), to: point(1.0, 1.0) };
+    assert!(arc.line_segment_intersections_t(&segment).is_empty());
+
+    // The line crosses the ellipse twice, but only one intersection is on the arc.
+    let ellipse = Arc {
+        center: point(1.0, 2.0),
+        radii: vector(3.0, 1.0),
+        start_angle: Angle::radians(0.2),
+        sweep_angle: Angle::radians(-3.0),
+        x_rotation: Angle::radians(0.5),
+    };
+    let line = Line { point: point(1.0, 2.0), vector: vector(0.0, 1.0) };
+    let result = ellipse.line_intersections_t(&line);
+    assert_eq!(result.len(), 1);
+    assert!((ellipse.sample(result[0]).x - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn arc_curve_intersections() {
+    let circle = Arc::circle(point(0.0f32, 0.0), 1.0);
+    let other = Arc::circle(point(1.0, 0.0), 1.0);
+
+    let mut result = circle.arc_intersections_t(&other);
+    result.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    assert_eq!(result.len(), 2);
+    assert!((result[0].0 - 1.0 / 6.0).abs() < 1e-4);
+    assert!((result[0].1 - 1.0 / 3.0).abs() < 1e-4);
+    assert!((result[1].0 - 5.0 / 6.0).abs() < 1e-4);
+    assert!((result[1].1 - 2.0 / 3.0).abs() < 1e-4);
+
+    assert!(circle.arc_intersections_t(&circle).is_empty());
+
+    let ellipse = Arc {
+        center: point(0.5, 0.2),
+        radii: vector(2.0, 0.5),
+        start_angle: Angle::zero(),
+        sweep_angle: Angle::two_pi(),
+        x_rotation: Angle::radians(0.3),
+    };
+    let result = circle.arc_intersections_t(&ellipse);
+    assert_eq!(result.len(), 4);
+    for &(t1, t2) in &result {
+        assert!((circle.sample(t1) - ellipse.sample(t2)).length() < 1e-4);
+    }
+
+    // The other arc goes three times around a circle that crosses this one twice.
+    let loops = Arc {
+        center: point(1.0, 0.0),
+        radii: vector(1.0, 1.0),
+        start_angle: Angle::zero(),
+        sweep_angle: Angle::radians(6.0 * std::f32::consts::PI),
+        x_rotation: Angle::zero(),
+    };
+    let result = circle.arc_intersections_t(&loops);
+    assert_eq!(result.len(), 4);
+    for &(t1, t2) in &result {
+        assert!((circle.sample(t1) - loops.sample(t2)).length() < 1e-4);
+    }
+
+    let quadratic = QuadraticBezierSegment {
+        from: point(-2.0, 0.5),
+        ctrl: point(0.0, -1.5),
+        to: point(2.0, 0.5),
+    };
+    let result = circle.quadratic_intersections_t(&quadratic);
+    assert_eq!(result.len(), 2);
+    for &(t1, t2) in &result {
+        assert!((circle.sample(t1) - quadratic.sample(t2)).length() < 1e-4);
+    }
+
+    let cubic = CubicBezierSegment {
+        from: point(-2.0, 0.0),
+        ctrl1: point(-1.0, 3.0),
+        ctrl2: point(1.0, -3.0),
+        to: point(2.0, 0.0),
+    };
+    let result = circle.cubic_intersections_t(&cubic);
+    assert_eq!(result.len(), 2);
+    for &(t1, t2) in &result {
+        assert!((circle.sample(t1) - cubic.sample(t2)).length() < 1e-4);
+    }
+}
@@ -0,0 +1,205 @@
+//! Arc-length parameterization of curve segments.
+//!
+//! The parameter `t` of a curve does not progress at a constant speed along the curve.
+//! `ArcLengthTable` approximates the mapping between `t` and the distance along the curve,
+//! which makes it possible to sample a curve at regular intervals of length, for example
+//! to animate an object along it or to apply a dash pattern.
+//!
+//! # Examples
+//!
+//! ```
+//! use lyon_geom::{ArcLengthTable, CubicBezierSegment};
+//! use lyon_geom::math::point;
+//!
+//! let curve = CubicBezierSegment {
+//!     from: point(0.0, 0.0),
+//!     ctrl1: point(10.0, 10.0),
+//!     ctrl2: point(20.0, -10.0),
+//!     to: point(30.0, 0.0),
+//! };
+//!
+//! let table = ArcLengthTable::new(&curve, 0.01);
+//! let length = table.length();
+//!
+//! // Place ten points at regular intervals along the curve.
+//! for i in 0..10 {
+//!     let t = table.t_at_length(length * i as f32 / 9.0);
+//!     let _position = curve.sample(t);
+//! }
+//! ```
+
+use crate::scalar::Scalar;
+use crate::segment::Segment;
+
+use std::cmp::Ordering;
+
+/// A lookup table that maps the parameter `t` of a curve segment to the distance along the
+/// curve and vice versa.
+///
+/// The curve is approximated with a sequence of line segments, such that the approximation is
+/// within the tolerance threshold of the curve. Building the table is the expensive part, after
+/// which each query is a binary search in the table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArcLengthTable<S> {
+    // Pairs of (t, length) sorted by increasing t (and length).
+    samples: Vec<(S, S)>,
+}
+
+// Number of sub-segments the curve is always split into before adaptive subdivision,
+// so that symmetric features such as inflections are not missed by the flatness test.
+const MIN_SUBDIVISIONS: u32 = 8;
+const MAX_DEPTH: u32 = 16;
+
+impl<S: Scalar> ArcLengthTable<S> {
+    /// Build the lookup table for a segment.
+    pub fn new<T>(segment: &T, tolerance: S) -> Self
+    where
+        T: Segment<Scalar = S>,
+    {
+        let mut samples = Vec::with_capacity(MIN_SUBDIVISIONS as usize * 2 + 1);
+        samples.push((S::ZERO, S::ZERO));
+
+        let step = S::ONE / S::value(MIN_SUBDIVISIONS as f32);
+        let mut length = S::ZERO;
+        let mut t0 = S::ZERO;
+        for i in 1..=MIN_SUBDIVISIONS {
+            let t1 = if i == MIN_SUBDIVISIONS { S::ONE } else { S::value(i as f32) * step };
+            subdivide(segment, t0, t1, tolerance, MAX_DEPTH, &mut length, &mut samples);
+            t0 = t1;
+        }
+
+        ArcLengthTable { samples }
+    }
+
+    /// The approximate length of the segment.
+    #[inline]
+    pub fn length(&self) -> S {
+        self.samples[self.samples.len() - 1].1
+    }
+
+    /// The approximate distance along the curve between its start and the point at `t`.
+    pub fn length_at_t(&self, t: S) -> S {
+        if t <= S::ZERO {
+            return S::ZERO;
+        }
+        if t >= S::ONE {
+            return self.length();
+        }
+
+        let idx = self.upper_bound(|sample| sample.0, t);
+        let (t0, l0) = self.samples[idx - 1];
+        let (t1, l1) = self.samples[idx];
+
+        l0 + (l1 - l0) * (t - t0) / (t1 - t0)
+    }
+
+    /// The approximate parameter `t` of the point at a given distance along the curve.
+    ///
+    /// The distance is clamped between zero and the length of the segment.
+    pub fn t_at_length(&self, length: S) -> S {
+        if length <= S::ZERO {
+            return S::ZERO;
+        }
+        if length >= self.length() {
+            return S::ONE;
+        }
+
+        let idx = self.upper_bound(|sample| sample.1, length);
+        let (t0, l0) = self.samples[idx - 1];
+        let (t1, l1) = self.samples[idx];
+
+        if l1 == l0 {
+            return t0;
+        }
+
+        t0 + (t1 - t0) * (length - l0) / (l1 - l0)
+    }
+
+    // Index of the first sample greater than the value, between 1 and the last sample.
+    fn upper_bound<F>(&self, key: F, value: S) -> usize
+    where
+        F: Fn(&(S, S)) -> S,
+    {
+        let idx = match self.samples.binary_search_by(|sample| {
+            if key(sample) <= value { Ordering::Less } else { Ordering::Greater }
+        }) {
+            Ok(idx) | Err(idx) => idx,
+        };
+
+        idx.max(1).min(self.samples.len() - 1)
+    }
+}
+
+fn subdivide<S: Scalar, T: Segment<Scalar = S>>(
+    segment: &T,
+    t0: S,
+    t1: S,
+    tolerance: S,
+    depth: u32,
+    length: &mut S,
+    samples: &mut Vec<(S, S)>,
+) {
+    let from = segment.sample(t0);
+    let to = segment.sample(t1);
+    let t_mid = (t0 + t1) * S::HALF;
+    let mid = segment.sample(t_mid);
+
+    if depth > 0 && (mid - from.lerp(to, S::HALF)).square_length() > tolerance * tolerance {
+        subdivide(segment, t0, t_mid, tolerance, depth - 1, length, samples);
+        subdivide(segment, t_mid, t1, tolerance, depth - 1, length, samples);
+        return;
+    }
+
+    *length += (to - from).length();
+    samples.push((t1, *length));
+}
+
+#[test]
+fn arc_length_table_line() {
+    use crate::LineSegment;
+    use crate::math::point;
+
+    let segment = LineSegment { from: point(0.0f32, 0.0), to: point(10.0, 0.0) };
+    let table = ArcLengthTable::new(&segment, 0.01);
+
+    assert_eq!(table.length(), 10.0);
+    assert_eq!(table.length_at_t(0.25), 2.5);
+    assert_eq!(table.t_at_length(7.5), 0.75);
+    assert_eq!(table.t_at_length(-1.0), 0.0);
+    assert_eq!(table.t_at_length(11.0), 1.0);
+    assert_eq!(table.length_at_t(2.0), 10.0);
+}
+
+#[test]
+fn arc_length_table_cubic() {
+    use crate::CubicBezierSegment;
+    use crate::math::point;
+
+    let curve = CubicBezierSegment {
+        from: point(0.0f32, 0.0),
+        ctrl1: point(0.0, 10.0),
+        ctrl2: point(30.0, 10.0),
+        to: point(30.0, 0.0),
+    };
+
+    let table = ArcLengthTable::new(&curve, 0.001);
+    let length = curve.approximate_length(0.0001);
+    assert!((table.length() - length).abs() < 0.01);
+
+    for i in 0..=10 {
+        let t = i as f32 / 10.0;
+        let expected = curve.before_split(t).approximate_length(0.0001);
+        assert!((table.length_at_t(t) - expected).abs() < 0.01);
+
+        // Round trip.
+        let l = table.length() * t;
+        assert!((table.length_at_t(table.t_at_length(l)) - l).abs() < 0.001);
+    }
+
+    // Points sampled at regular distances are equally spaced along the curve.
+    for i in 1..=20 {
+        let l = length * i as f32 / 20.0;
+        let t = table.t_at_length(l);
+        assert!((curve.approximate_length_to_t(t, 0.0001) - l).abs() < 0.01);
+    }
+}
@@ -0,0 +1,578 @@
+//! Rational quadratic bézier curves, also known as conic sections.
+//!
+//! Unlike regular bézier curves, conics can represent circular and elliptic arcs exactly,
+//! which is why some vector graphics libraries (Skia for example) use them for rounded
+//! shapes.
+
+use crate::{QuadraticBezierSegment, LineSegment};
+use crate::scalar::Scalar;
+use crate::generic_math::{Point, Vector, Rect, rect, Transform2D};
+use crate::segment::{Segment, FlattenedForEach, BoundingRect};
+use crate::segment;
+use crate::quadratic_bezier;
+use crate::utils::cubic_polynomial_roots;
+use arrayvec::ArrayVec;
+
+use std::ops::Range;
+
+// Maximum number of times a conic is subdivided in halves when approximating it
+// with quadratic bézier curves.
+const MAX_SUBDIVISIONS: usize = 16;
+
+/// A rational quadratic bézier curve segment.
+///
+/// The curve is defined by equation:
+/// ```∀ t ∈ [0..1],  P(t) = ((1 - t)² * from + 2 * (1 - t) * t * w * ctrl + t² * to) / ((1 - t)² + 2 * (1 - t) * t * w + t²)```
+///
+/// Where `w` is the weight of the control point. A weight smaller than one gives an
+/// ellipse, a weight equal to one a parabola (regular quadratic bézier curve) and a weight
+/// greater than one a hyperbola.
+/// The weight is expected to be positive.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct ConicSegment<S> {
+    pub from: Point<S>,
+    pub ctrl: Point<S>,
+    pub to: Point<S>,
+    pub weight: S,
+}
+
+// A control point in homogeneous coordinates.
+#[derive(Copy, Clone, Debug)]
+struct WeightedPoint<S> {
+    x: S,
+    y: S,
+    w: S,
+}
+
+impl<S: Scalar> WeightedPoint<S> {
+    fn new(p: Point<S>, w: S) -> Self {
+        WeightedPoint { x: p.x * w, y: p.y * w, w }
+    }
+
+    fn lerp(&self, other: &Self, t: S) -> Self {
+        WeightedPoint {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+            w: self.w + (other.w - self.w) * t,
+        }
+    }
+
+    fn project(&self) -> Point<S> {
+        Point::new(self.x / self.w, self.y / self.w)
+    }
+}
+
+impl<S: Scalar> ConicSegment<S> {
+    /// Sample the curve at t (expecting t between 0 and 1).
+    pub fn sample(&self, t: S) -> Point<S> {
+        let (c0, c1, c2) = self.weighted_coefficients(t);
+        let p = self.from.to_vector() * c0 + self.ctrl.to_vector() * c1 + self.to.to_vector() * c2;
+        (p / (c0 + c1 + c2)).to_point()
+    }
+
+    /// Sample the x coordinate of the curve at t (expecting t between 0 and 1).
+    pub fn x(&self, t: S) -> S {
+        let (c0, c1, c2) = self.weighted_coefficients(t);
+        (self.from.x * c0 + self.ctrl.x * c1 + self.to.x * c2) / (c0 + c1 + c2)
+    }
+
+    /// Sample the y coordinate of the curve at t (expecting t between 0 and 1).
+    pub fn y(&self, t: S) -> S {
+        let (c0, c1, c2) = self.weighted_coefficients(t);
+        (self.from.y * c0 + self.ctrl.y * c1 + self.to.y * c2) / (c0 + c1 + c2)
+    }
+
+    #[inline]
+    fn weighted_coefficients(&self, t: S) -> (S, S, S) {
+        let one_t = S::ONE - t;
+        (one_t * one_t, S::TWO * one_t * t * self.weight, t * t)
+    }
+
+    /// The denominator of the curve's equation and its derivative at t.
+    #[inline]
+    fn denominator(&self, t: S) -> (S, S) {
+        let w1 = self.weight - S::ONE;
+        (S::ONE + S::TWO * t * (S::ONE - t) * w1, S::TWO * w1 * (S::ONE - S::TWO * t))
+    }
+
+    /// The numerator of the curve's equation and its first two derivatives at t.
+    #[inline]
+    fn numerator(&self, t: S) -> (Vector<S>, Vector<S>, Vector<S>) {
+        let (c0, c1, c2) = self.weighted_coefficients(t);
+        let p0 = self.from.to_vector();
+        let p1 = self.ctrl.to_vector() * self.weight;
+        let p2 = self.to.to_vector();
+        let n = p0 * c0 + self.ctrl.to_vector() * c1 + p2 * c2;
+        let dn = ((p1 - p0) * (S::ONE - t) + (p2 - p1) * t) * S::TWO;
+        let ddn = (p0 - p1 * S::TWO + p2) * S::TWO;
+
+        (n, dn, ddn)
+    }
+
+    /// Sample the curve's derivative at t (expecting t between 0 and 1).
+    pub fn derivative(&self, t: S) -> Vector<S> {
+        let (n, dn, _) = self.numerator(t);
+        let (d, dd) = self.denominator(t);
+
+        (dn * d - n * dd) / (d * d)
+    }
+
+    /// Sample the x coordinate of the curve's derivative at t (expecting t between 0 and 1).
+    pub fn dx(&self, t: S) -> S {
+        self.derivative(t).x
+    }
+
+    /// Sample the y coordinate of the curve's derivative at t (expecting t between 0 and 1).
+    pub fn dy(&self, t: S) -> S {
+        self.derivative(t).y
+    }
+
+    /// Sample the curve's second derivative at t (expecting t between 0 and 1).
+    pub fn second_derivative(&self, t: S) -> Vector<S> {
+        let (n, dn, ddn) = self.numerator(t);
+        let (d, dd) = self.denominator(t);
+        let ddd = S::FOUR * (S::ONE - self.weight);
+
+        let p = n / d;
+        let dp = (dn * d - n * dd) / (d * d);
+
+        (ddn - p * ddd - dp * (S::TWO * dd)) / d
+    }
+
+    /// Sample the signed curvature at t (expecting t between 0 and 1).
+    ///
+    /// See `Segment::curvature`.
+    pub fn curvature(&self, t: S) -> S {
+        segment::curvature(self.derivative(t), self.second_derivative(t))
+    }
+
+    /// Find the parameter t of the point of the curve that is closest to a given position.
+    pub fn closest_point_t(&self, p: Point<S>) -> S {
+        segment::closest_point_t_by_sampling(
+            p,
+            |t| self.sample(t),
+            |t| self.derivative(t),
+            |t| self.second_derivative(t),
+            16,
+        )
+    }
+
+    /// Swap the beginning and the end of the segment.
+    pub fn flip(&self) -> Self {
+        ConicSegment {
+            from: self.to,
+            ctrl: self.ctrl,
+            to: self.from,
+            weight: self.weight,
+        }
+    }
+
+    // The control points of the sub-curve between a and b, using the blossom of the
+    // curve in homogeneous coordinates. The resulting curve is normalized so that the
+    // weights of the endpoints are one.
+    fn weighted_range(&self, a: S, b: S) -> Self {
+        let p0 = WeightedPoint::new(self.from, S::ONE);
+        let p1 = WeightedPoint::new(self.ctrl, self.weight);
+        let p2 = WeightedPoint::new(self.to, S::ONE);
+
+        let blossom = |u: S, v: S| {
+            p0.lerp(&p1, u).lerp(&p1.lerp(&p2, u), v)
+        };
+
+        let from = blossom(a, a);
+        let ctrl = blossom(a, b);
+        let to = blossom(b, b);
+
+        ConicSegment {
+            from: from.project(),
+            ctrl: ctrl.project(),
+            to: to.project(),
+            weight: ctrl.w / (from.w * to.w).sqrt(),
+        }
+    }
+
+    /// Return the curve inside a given range of t.
+    ///
+    /// This is equivalent splitting at the range's end points.
+    pub fn split_range(&self, t_range: Range<S>) -> Self {
+        self.weighted_range(t_range.start, t_range.end)
+    }
+
+    /// Split this curve into two sub-curves.
+    pub fn split(&self, t: S) -> (ConicSegment<S>, ConicSegment<S>) {
+        (self.before_split(t), self.after_split(t))
+    }
+
+    /// Return the curve before the split point.
+    pub fn before_split(&self, t: S) -> ConicSegment<S> {
+        self.weighted_range(S::ZERO, t)
+    }
+
+    /// Return the curve after the split point.
+    pub fn after_split(&self, t: S) -> ConicSegment<S> {
+        self.weighted_range(t, S::ONE)
+    }
+
+    #[inline]
+    pub fn from(&self) -> Point<S> { self.from }
+
+    #[inline]
+    pub fn to(&self) -> Point<S> { self.to }
+
+    #[inline]
+    pub fn baseline(&self) -> LineSegment<S> {
+        LineSegment { from: self.from, to: self.to }
+    }
+
+    pub fn is_linear(&self, tolerance: S) -> bool {
+        self.to_quadratic().is_linear(tolerance)
+    }
+
+    /// Applies the transform to this curve and returns the results.
+    ///
+    /// Affine transformations don't affect the weight of the curve.
+    #[inline]
+    pub fn transform(&self, transform: &Transform2D<S>) -> Self {
+        ConicSegment {
+            from: transform.transform_point(self.from),
+            ctrl: transform.transform_point(self.ctrl),
+            to: transform.transform_point(self.to),
+            weight: self.weight,
+        }
+    }
+
+    /// Returns the quadratic bézier curve with the same control points.
+    ///
+    /// This is only exactly the same curve if the weight is equal to one.
+    pub fn to_quadratic(&self) -> QuadraticBezierSegment<S> {
+        QuadraticBezierSegment {
+            from: self.from,
+            ctrl: self.ctrl,
+            to: self.to,
+        }
+    }
+
+    /// Returns an upper bound of the distance between the curve and the quadratic
+    /// bézier curve with the same control points.
+    pub fn quadratic_approximation_error(&self) -> S {
+        // See "Approximation of conic sections by quadratic Bézier curves" in the
+        // Skia source code (SkGeometry.cpp).
+        let a = self.weight - S::ONE;
+        let k = a / (S::FOUR * (S::TWO + a));
+        let v = self.from.to_vector() - self.ctrl.to_vector() * S::TWO + self.to.to_vector();
+
+        S::abs(k) * v.length()
+    }
+
+    /// Approximates the curve with a sequence of quadratic bézier curves.
+    ///
+    /// The curve is recursively split in halves until each part is within the tolerance
+    /// of the quadratic bézier curve that shares its control points.
+    pub fn for_each_quadratic_bezier<F>(&self, tolerance: S, cb: &mut F)
+    where
+        F: FnMut(&QuadraticBezierSegment<S>)
+    {
+        for curve in QuadraticApproximation::new(*self, tolerance) {
+            cb(&curve);
+        }
+    }
+
+    /// Iterates through the curve invoking a callback at each point.
+    pub fn for_each_flattened<F: FnMut(Point<S>)>(&self, tolerance: S, call_back: &mut F) {
+        let tolerance = tolerance * S::HALF;
+        self.for_each_quadratic_bezier(tolerance, &mut |curve| {
+            curve.for_each_flattened(tolerance, call_back);
+        });
+    }
+
+    /// Returns the flattened representation of the curve as an iterator, starting *after* the
+    /// current point.
+    pub fn flattened(&self, tolerance: S) -> Flattened<S> {
+        Flattened::new(*self, tolerance)
+    }
+
+    /// Compute the length of the segment using a flattened approximation.
+    pub fn approximate_length(&self, tolerance: S) -> S {
+        segment::approximate_length_from_flattening(self, tolerance)
+    }
+
+    /// Returns a conservative rectangle that contains the curve.
+    ///
+    /// For positive weights the curve is inside the triangle formed by its control points.
+    pub fn fast_bounding_rect(&self) -> Rect<S> {
+        self.to_quadratic().fast_bounding_rect()
+    }
+
+    /// Returns a conservative range of x this curve is contained in.
+    pub fn fast_bounding_range_x(&self) -> (S, S) {
+        self.to_quadratic().fast_bounding_range_x()
+    }
+
+    /// Returns a conservative range of y this curve is contained in.
+    pub fn fast_bounding_range_y(&self) -> (S, S) {
+        self.to_quadratic().fast_bounding_range_y()
+    }
+
+    /// Returns the smallest rectangle the curve is contained in.
+    pub fn bounding_rect(&self) -> Rect<S> {
+        let (min_x, max_x) = self.bounding_range_x();
+        let (min_y, max_y) = self.bounding_range_y();
+
+        rect(min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    /// Returns the smallest range of x this curve is contained in.
+    pub fn bounding_range_x(&self) -> (S, S) {
+        self.bounding_range(|p| p.x)
+    }
+
+    /// Returns the smallest range of y this curve is contained in.
+    pub fn bounding_range_y(&self) -> (S, S) {
+        self.bounding_range(|p| p.y)
+    }
+
+    fn bounding_range<F: Fn(Vector<S>) -> S>(&self, coord: F) -> (S, S) {
+        let from = coord(self.from.to_vector());
+        let to = coord(self.to.to_vector());
+        let mut min = S::min(from, to);
+        let mut max = S::max(from, to);
+
+        // The numerator of the derivative is a polynomial of degree two (the cubic
+        // terms cancel out). Find its coefficients from three samples.
+        let numerator = |t: S| {
+            let (n, dn, _) = self.numerator(t);
+            let (d, dd) = self.denominator(t);
+            coord(dn) * d - coord(n) * dd
+        };
+        let f0 = numerator(S::ZERO);
+        let f1 = numerator(S::HALF);
+        let f2 = numerator(S::ONE);
+        let a = S::TWO * (f0 + f2) - S::FOUR * f1;
+        let b = S::FOUR * f1 - S::THREE * f0 - f2;
+
+        for t in cubic_polynomial_roots(S::ZERO, a, b, f0) {
+            if t > S::ZERO && t < S::ONE {
+                let v = coord(self.sample(t).to_vector());
+                min = S::min(min, v);
+                max = S::max(max, v);
+            }
+        }
+
+        (min, max)
+    }
+}
+
+impl<S: Scalar> Segment for ConicSegment<S> { impl_segment!(S); }
+
+impl<S: Scalar> BoundingRect for ConicSegment<S> {
+    type Scalar = S;
+    fn bounding_rect(&self) -> Rect<S> { self.bounding_rect() }
+    fn fast_bounding_rect(&self) -> Rect<S> { self.fast_bounding_rect() }
+    fn bounding_range_x(&self) -> (S, S) { self.bounding_range_x() }
+    fn bounding_range_y(&self) -> (S, S) { self.bounding_range_y() }
+    fn fast_bounding_range_x(&self) -> (S, S) { self.fast_bounding_range_x() }
+    fn fast_bounding_range_y(&self) -> (S, S) { self.fast_bounding_range_y() }
+}
+
+impl<S: Scalar> FlattenedForEach for ConicSegment<S> {
+    fn for_each_flattened<F: FnMut(Point<S>)>(&self, tolerance: S, call_back: &mut F) {
+        self.for_each_flattened(tolerance, call_back);
+    }
+}
+
+/// An iterator over the quadratic bézier curves approximating a conic.
+struct QuadraticApproximation<S> {
+    // Parts of the curve that remain to be approximated, in reverse order.
+    stack: ArrayVec<[(ConicSegment<S>, usize); MAX_SUBDIVISIONS + 1]>,
+    tolerance: S,
+}
+
+impl<S: Scalar> QuadraticApproximation<S> {
+    fn new(curve: ConicSegment<S>, tolerance: S) -> Self {
+        let mut stack = ArrayVec::new();
+        stack.push((curve, 0));
+        QuadraticApproximation { stack, tolerance }
+    }
+}
+
+impl<S: Scalar> Iterator for QuadraticApproximation<S> {
+    type Item = QuadraticBezierSegment<S>;
+    fn next(&mut self) -> Option<QuadraticBezierSegment<S>> {
+        let (mut curve, mut depth) = self.stack.pop()?;
+        while depth < MAX_SUBDIVISIONS && curve.quadratic_approximation_error() > self.tolerance {
+            let (first, second) = curve.split(S::HALF);
+            depth += 1;
+            self.stack.push((second, depth));
+            curve = first;
+        }
+
+        Some(curve.to_quadratic())
+    }
+}
+
+/// A flattening iterator for conic segments.
+///
+/// The iterator starts at the first point *after* the origin of the curve and ends at the
+/// destination.
+pub struct Flattened<S> {
+    quadratics: QuadraticApproximation<S>,
+    current: Option<quadratic_bezier::Flattened<S>>,
+    tolerance: S,
+}
+
+impl<S: Scalar> Flattened<S> {
+    pub fn new(curve: ConicSegment<S>, tolerance: S) -> Self {
+        assert!(tolerance > S::ZERO);
+        // Half of the tolerance is used when approximating the conic with quadratic
+        // bézier curves, the other half when flattening them.
+        let tolerance = tolerance * S::HALF;
+        Flattened {
+            quadratics: QuadraticApproximation::new(curve, tolerance),
+            current: None,
+            tolerance,
+        }
+    }
+}
+
+impl<S: Scalar> Iterator for Flattened<S> {
+    type Item = Point<S>;
+    fn next(&mut self) -> Option<Point<S>> {
+        loop {
+            if let Some(ref mut current) = self.current {
+                if let Some(p) = current.next() {
+                    return Some(p);
+                }
+            }
+
+            let quadratic = self.quadratics.next()?;
+            self.current = Some(quadratic.flattened(self.tolerance));
+        }
+    }
+}
+
+#[cfg(test)]
+fn circle_quadrant(radius: f64) -> ConicSegment<f64> {
+    use crate::generic_math::point;
+
+    ConicSegment {
+        from: point(radius, 0.0),
+        ctrl: point(radius, radius),
+        to: point(0.0, radius),
+        weight: ::std::f64::consts::FRAC_1_SQRT_2,
+    }
+}
+
+#[test]
+fn conic_circle() {
+    let curve = circle_quadrant(10.0);
+
+    for i in 0..=10 {
+        let t = i as f64 / 10.0;
+        let p = curve.sample(t);
+        assert!((p.to_vector().length() - 10.0).abs() < 1e-9);
+        assert!((curve.x(t) - p.x).abs() < 1e-12);
+        assert!((curve.y(t) - p.y).abs() < 1e-12);
+
+        // The tangent is orthogonal to the radius.
+        assert!(curve.derivative(t).dot(p.to_vector()).abs() < 1e-9);
+
+        // The curvature of a circle is the inverse of its radius.
+        assert!((curve.curvature(t) - 0.1).abs() < 1e-9);
+    }
+
+    // Finite differences.
+    let h = 1e-5;
+    for i in 1..10 {
+        let t = i as f64 / 10.0;
+        let d = (curve.sample(t + h) - curve.sample(t - h)) / (2.0 * h);
+        let dd = (curve.derivative(t + h) - curve.derivative(t - h)) / (2.0 * h);
+        assert!((d - curve.derivative(t)).length() < 1e-4);
+        assert!((dd - curve.second_derivative(t)).length() < 1e-4);
+    }
+
+    let rect = curve.bounding_rect();
+    assert!((rect.min_x() - 0.0).abs() < 1e-9);
+    assert!((rect.max_x() - 10.0).abs() < 1e-9);
+    assert!((rect.max_y() - 10.0).abs() < 1e-9);
+
+    let length = curve.approximate_length(0.0001);
+    assert!((length - ::std::f64::consts::PI * 5.0).abs() < 0.001);
+
+    let t = curve.closest_point_t(Point::new(20.0, 20.0));
+    assert!((t - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn conic_split() {
+    let curve = circle_quadrant(10.0);
+    let (a, b) = curve.split(0.3);
+    assert!((a.to - curve.sample(0.3)).length() < 1e-9);
+    assert!((b.from - curve.sample(0.3)).length() < 1e-9);
+
+    // Sub-curves are still on the circle.
+    for i in 0..=10 {
+        let t = i as f64 / 10.0;
+        assert!((a.sample(t).to_vector().length() - 10.0).abs() < 1e-9);
+        assert!((b.sample(t).to_vector().length() - 10.0).abs() < 1e-9);
+    }
+
+    let range = curve.split_range(0.25..0.75);
+    assert!((range.from - curve.sample(0.25)).length() < 1e-9);
+    assert!((range.to - curve.sample(0.75)).length() < 1e-9);
+    assert!((range.sample(0.5).to_vector().length() - 10.0).abs() < 1e-9);
+
+    let flipped = curve.flip();
+    assert!((flipped.sample(0.3) - curve.sample(0.7)).length() < 1e-9);
+}
+
+#[test]
+fn conic_to_quadratics() {
+    let curve = circle_quadrant(100.0);
+
+    for &tolerance in &[1.0, 0.1, 0.01] {
+        let mut from = curve.from;
+        let mut count = 0;
+        curve.for_each_quadratic_bezier(tolerance, &mut |quadratic| {
+            assert_eq!(quadratic.from, from);
+            from = quadratic.to;
+            count += 1;
+
+            for i in 0..=10 {
+                let p = quadratic.sample(i as f64 / 10.0);
+                assert!((p.to_vector().length() - 100.0).abs() <= tolerance);
+            }
+        });
+        assert_eq!(from, curve.to);
+        assert!(count > 1);
+    }
+
+    // A conic with a weight of one is a quadratic bézier curve.
+    let mut parabola = curve;
+    parabola.weight = 1.0;
+    let mut count = 0;
+    parabola.for_each_quadratic_bezier(0.01, &mut |quadratic| {
+        assert_eq!(*quadratic, parabola.to_quadratic());
+        count += 1;
+    });
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn conic_flattening() {
+    let curve = circle_quadrant(100.0);
+    let tolerance = 0.1;
+
+    let mut points = Vec::new();
+    curve.for_each_flattened(tolerance, &mut |p| { points.push(p); });
+    let iterated: Vec<Point<f64>> = curve.flattened(tolerance).collect();
+    assert_eq!(points, iterated);
+    assert_eq!(*points.last().unwrap(), curve.to);
+
+    let mut from = curve.from;
+    for p in points {
+        let mid = from.lerp(p, 0.5);
+        assert!(100.0 - mid.to_vector().length() <= tolerance);
+        from = p;
+    }
+}
@@ -6,13 +6,35 @@ use crate::flatten_cubic::{flatten_cubic_bezier, find_cubic_bezier_inflection_po
 use crate::cubic_to_quadratic::*;
 use crate::cubic_bezier_intersections::cubic_bezier_intersections_t;
 use crate::monotonic::Monotonic;
+use crate::arc_length::ArcLengthTable;
 use crate::utils::{min_max, cubic_polynomial_roots};
 use crate::segment::{Segment, FlattenedForEach, approximate_length_from_flattening, BoundingRect};
+use crate::segment::{curvature, closest_point_t_by_sampling};
 use arrayvec::ArrayVec;
 
 use std::ops::Range;
 use std::cmp::Ordering::{Less, Equal, Greater};
 
+/// The shape of a cubic bézier curve.
+///
+/// Cubic bézier curves that aren't degenerate are either serpentines (which may have
+/// up to two inflection points), curves with a cusp or curves that intersect themselves
+/// (loops).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum CubicBezierType<S> {
+    /// All control points are aligned.
+    Line,
+    /// The curve can be represented exactly with a quadratic bézier curve.
+    Quadratic,
+    /// The curve doesn't have cusps nor self-intersections.
+    Serpentine,
+    /// The curve has a cusp at the provided parameter.
+    Cusp(S),
+    /// The curve intersects itself at the provided parameters t1 < t2.
+    Loop(S, S),
+}
+
 /// A 2d curve segment defined by four points: the beginning of the segment, two control
 /// points and the end of the segment.
 ///
@@ -148,6 +170,35 @@ impl<S: Scalar> CubicBezierSegment<S> {
         self.from.y * c0 + self.ctrl1.y * c1 + self.ctrl2.y * c2 + self.to.y * c3
     }
 
+    /// Sample the curve's second derivative at t (expecting t between 0 and 1).
+    pub fn second_derivative(&self, t: S) -> Vector<S> {
+        let one_t = S::ONE - t;
+        let a = self.from.to_vector() - self.ctrl1.to_vector() * S::TWO + self.ctrl2.to_vector();
+        let b = self.ctrl1.to_vector() - self.ctrl2.to_vector() * S::TWO + self.to.to_vector();
+        (a * one_t + b * t) * S::SIX
+    }
+
+    /// Sample the signed curvature at t (expecting t between 0 and 1).
+    ///
+    /// See `Segment::curvature`.
+    pub fn curvature(&self, t: S) -> S {
+        curvature(self.derivative(t), self.second_derivative(t))
+    }
+
+    /// Find the parameter t of the point of the curve that is closest to a given position.
+    ///
+    /// The extrema of the distance are the roots of a polynomial of degree 5, which
+    /// are found numerically.
+    pub fn closest_point_t(&self, p: Point<S>) -> S {
+        closest_point_t_by_sampling(
+            p,
+            |t| self.sample(t),
+            |t| self.derivative(t),
+            |t| self.second_derivative(t),
+            16,
+        )
+    }
+
     /// Return the sub-curve inside a given range of t.
     ///
     /// This is equivalent to splitting at the range's end points.
@@ -400,11 +451,138 @@ impl<S: Scalar> CubicBezierSegment<S> {
         approximate_length_from_flattening(self, tolerance)
     }
 
+    /// Compute the length of the curve between its start and the point at `t`, using a
+    /// flattened approximation.
+    pub fn approximate_length_to_t(&self, t: S, tolerance: S) -> S {
+        if t <= S::ZERO {
+            return S::ZERO;
+        }
+        self.before_split(S::min(t, S::ONE)).approximate_length(tolerance)
+    }
+
+    /// Find the parameter t of the point at a given distance along the curve, using a
+    /// flattened approximation.
+    ///
+    /// For repeated queries on the same curve, build an `ArcLengthTable` with
+    /// `arc_length_table` instead.
+    pub fn approximate_t_at_length(&self, length: S, tolerance: S) -> S {
+        self.arc_length_table(tolerance).t_at_length(length)
+    }
+
+    /// Build a lookup table to map the parameter `t` to the distance along the curve
+    /// and vice versa.
+    pub fn arc_length_table(&self, tolerance: S) -> ArcLengthTable<S> {
+        ArcLengthTable::new(self, tolerance)
+    }
+
     pub fn for_each_inflection_t<F>(&self, cb: &mut F)
     where F: FnMut(S) {
         find_cubic_bezier_inflection_points(self, cb);
     }
 
+    /// Determine the shape of the curve.
+    ///
+    /// The classification applies to the whole cubic curve the segment is part of, so the
+    /// parameters of a cusp or loop can be outside of the [0, 1] range. See
+    /// [CubicBezierType](enum.CubicBezierType.html).
+    pub fn classify(&self) -> CubicBezierType<S> {
+        // Power basis: P(t) = a * t³ + b * t² + c * t + from.
+        let a = (self.ctrl1 - self.ctrl2) * S::THREE + (self.to - self.from);
+        let b = (self.from.to_vector() - self.ctrl1.to_vector() * S::TWO + self.ctrl2.to_vector()) * S::THREE;
+        let c = (self.ctrl1 - self.from) * S::THREE;
+
+        let a_len = a.length();
+        let b_len = b.length();
+        let c_len = c.length();
+        let epsilon = S::EPSILON;
+
+        let ab = a.cross(b);
+        let ac = a.cross(c);
+        let bc = b.cross(c);
+
+        if S::abs(ab) <= epsilon * a_len * b_len
+            && S::abs(ac) <= epsilon * a_len * c_len
+            && S::abs(bc) <= epsilon * b_len * c_len {
+            return CubicBezierType::Line;
+        }
+
+        if a_len <= epsilon * (b_len + c_len) {
+            return CubicBezierType::Quadratic;
+        }
+
+        // A double point is reached at two parameters s and t such that P(s) = P(t).
+        // Dividing P(s) - P(t) by s - t gives:
+        //   a * (s² + s * t + t²) + b * (s + t) + c = 0
+        // Which is linear in terms of s + t and s * t once multiplied (cross product) by a
+        // or b.
+        if S::abs(ab) <= epsilon * a_len * b_len {
+            // The double point is at infinity.
+            return CubicBezierType::Serpentine;
+        }
+
+        let sum = -ac / ab;
+        let product = sum * sum - bc / ab;
+        let discriminant = sum * sum - S::FOUR * product;
+
+        if S::abs(discriminant) <= epsilon {
+            return CubicBezierType::Cusp(sum * S::HALF);
+        }
+
+        if discriminant < S::ZERO {
+            return CubicBezierType::Serpentine;
+        }
+
+        let sqrt_discriminant = discriminant.sqrt();
+        CubicBezierType::Loop((sum - sqrt_discriminant) * S::HALF, (sum + sqrt_discriminant) * S::HALF)
+    }
+
+    /// Returns the parameters of the self-intersection of the segment, if any.
+    pub fn self_intersection_t(&self) -> Option<(S, S)> {
+        match self.classify() {
+            CubicBezierType::Loop(t1, t2) if t1 >= S::ZERO && t2 <= S::ONE => Some((t1, t2)),
+            _ => None,
+        }
+    }
+
+    /// Invokes a callback for each part of the segment that doesn't contain a loop or
+    /// a cusp.
+    ///
+    /// Loops are split in the middle, so that each part contains one of the two branches
+    /// crossing at the double point.
+    pub fn for_each_loop_free_range<F>(&self, mut cb: F)
+    where
+        F: FnMut(Range<S>),
+    {
+        let split = match self.classify() {
+            CubicBezierType::Loop(t1, t2) if t1 >= S::ZERO && t2 <= S::ONE => (t1 + t2) * S::HALF,
+            CubicBezierType::Cusp(t) if t > S::ZERO && t < S::ONE => t,
+            _ => {
+                cb(S::ZERO..S::ONE);
+                return;
+            }
+        };
+
+        cb(S::ZERO..split);
+        cb(split..S::ONE);
+    }
+
+    /// Splits the segment into sub-segments that don't contain a loop or a cusp, invoking
+    /// a callback for each of them.
+    ///
+    /// See `for_each_loop_free_range`.
+    pub fn for_each_loop_free_segment<F>(&self, cb: &mut F)
+    where
+        F: FnMut(&CubicBezierSegment<S>),
+    {
+        self.for_each_loop_free_range(|range| {
+            if range.start == S::ZERO && range.end == S::ONE {
+                cb(self);
+            } else {
+                cb(&self.split_range(range));
+            }
+        });
+    }
+
     /// Return local x extrema or None if this curve is monotonic.
     ///
     /// This returns the advancements along the curve, not the actual x position.
@@ -1216,3 +1394,162 @@ fn test_cubic_intersection_deduping() {
     assert!(f64::abs(intersections[0].x) < epsilon);
     assert!(f64::abs(intersections[0].y) < epsilon);
 }
+
+#[test]
+fn closest_point_and_curvature() {
+    use crate::math::{point, Point};
+
+    let curves = [
+        CubicBezierSegment {
+            from: point(0.0f32, 0.0),
+            ctrl1: point(10.0, 10.0),
+            ctrl2: point(-5.0, 10.0),
+            to: point(5.0, 0.0),
+        },
+        // A loop.
+        CubicBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl1: point(20.0, 10.0),
+            ctrl2: point(-10.0, 10.0),
+            to: point(10.0, 0.0),
+        },
+        CubicBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl1: point(1.0, 0.0),
+            ctrl2: point(2.0, 0.0),
+            to: point(3.0, 0.0),
+        },
+    ];
+
+    let positions = [
+        point(0.0, 0.0), point(5.0, 5.0), point(5.0, 3.8), point(-3.0, 7.0),
+        point(12.0, -1.0), point(2.5, 10.0), point(1.0, 0.5),
+    ];
+
+    let distance = |c: &CubicBezierSegment<f32>, t: f32, p: Point| (c.sample(t) - p).length();
+
+    for c in &curves {
+        for p in &positions {
+            let t = c.closest_point_t(*p);
+            assert!((0.0..=1.0).contains(&t));
+            let d = distance(c, t, *p);
+            for i in 0..=1000 {
+                let other = i as f32 / 1000.0;
+                assert!(d <= distance(c, other, *p) + 1e-3, "{:?} {:?} t={:?} other={:?}", c, p, t, other);
+            }
+        }
+
+        // Compare the second derivative with finite differences.
+        for i in 1..10 {
+            let t = i as f32 / 10.0;
+            let h = 0.01;
+            let approx = (c.derivative(t + h) - c.derivative(t - h)) / (2.0 * h);
+            assert!((approx - c.second_derivative(t)).length() < 0.01, "{:?} {:?}", approx, c.second_derivative(t));
+        }
+    }
+
+    assert_eq!(curves[2].curvature(0.5), 0.0);
+    assert!(curves[1].curvature(0.5) > 0.0);
+}
+
+#[test]
+fn classify() {
+    use crate::math::point;
+
+    let serpentine = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(10.0, 10.0),
+        ctrl2: point(20.0, -10.0),
+        to: point(30.0, 0.0),
+    };
+    assert_eq!(serpentine.classify(), CubicBezierType::Serpentine);
+    assert_eq!(serpentine.self_intersection_t(), None);
+
+    let arch = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(0.0, 10.0),
+        ctrl2: point(10.0, 10.0),
+        to: point(10.0, 0.0),
+    };
+    match arch.classify() {
+        CubicBezierType::Loop(t1, t2) => {
+            // The double point is outside of the segment.
+            assert!(t1 < 0.0 || t2 > 1.0);
+        }
+        CubicBezierType::Serpentine => {}
+        t => panic!("{:?}", t),
+    }
+    assert_eq!(arch.self_intersection_t(), None);
+
+    let looping = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(30.0, 10.0),
+        ctrl2: point(-10.0, 10.0),
+        to: point(20.0, 0.0),
+    };
+    let (t1, t2) = looping.self_intersection_t().unwrap();
+    assert!(0.0 < t1 && t1 < t2 && t2 < 1.0);
+    assert!((looping.sample(t1) - looping.sample(t2)).length() < 1e-3);
+    assert_eq!(looping.classify(), CubicBezierType::Loop(t1, t2));
+
+    let cusp = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(10.0, 10.0),
+        ctrl2: point(0.0, 10.0),
+        to: point(10.0, 0.0),
+    };
+    match cusp.classify() {
+        CubicBezierType::Cusp(t) => {
+            assert!((t - 0.5f32).abs() < 1e-4);
+            assert!(cusp.derivative(t).length() < 1e-2);
+        }
+        t => panic!("{:?}", t),
+    }
+
+    let quadratic = QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(10.0, 10.0),
+        to: point(20.0, 0.0),
+    }.to_cubic();
+    assert_eq!(quadratic.classify(), CubicBezierType::Quadratic);
+
+    let line = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(1.0, 1.0),
+        ctrl2: point(5.0, 5.0),
+        to: point(3.0, 3.0),
+    };
+    assert_eq!(line.classify(), CubicBezierType::Line);
+}
+
+#[test]
+fn loop_free_segments() {
+    use crate::math::point;
+
+    let looping = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(30.0, 10.0),
+        ctrl2: point(-10.0, 10.0),
+        to: point(20.0, 0.0),
+    };
+
+    let mut segments = Vec::new();
+    looping.for_each_loop_free_segment(&mut |segment| { segments.push(*segment); });
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].from, looping.from);
+    assert_eq!(segments[1].to, looping.to);
+    assert!((segments[0].to - segments[1].from).length() < 1e-4);
+    for segment in &segments {
+        assert_eq!(segment.self_intersection_t(), None);
+    }
+
+    let serpentine = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(10.0, 10.0),
+        ctrl2: point(20.0, -10.0),
+        to: point(30.0, 0.0),
+    };
+    let mut ranges = Vec::new();
+    serpentine.for_each_loop_free_range(|range| { ranges.push(range); });
+    assert_eq!(ranges, vec![0.0..1.0]);
+}
@@ -86,8 +86,11 @@ pub extern crate serde;
 pub mod quadratic_bezier;
 pub mod cubic_bezier;
 pub mod arc;
+pub mod arc_length;
+pub mod conic;
 pub mod utils;
 pub mod cubic_to_quadratic;
+pub mod spline;
 mod cubic_bezier_intersections;
 mod flatten_cubic;
 mod triangle;
@@ -97,7 +100,7 @@ mod monotonic;
 #[doc(inline)]
 pub use crate::quadratic_bezier::QuadraticBezierSegment;
 #[doc(inline)]
-pub use crate::cubic_bezier::CubicBezierSegment;
+pub use crate::cubic_bezier::{CubicBezierSegment, CubicBezierType};
 #[doc(inline)]
 pub use crate::triangle::{Triangle};
 #[doc(inline)]
@@ -108,6 +111,10 @@ pub use crate::arc::{Arc, SvgArc, ArcFlags};
 pub use crate::segment::{Segment, BezierSegment};
 #[doc(inline)]
 pub use crate::monotonic::Monotonic;
+#[doc(inline)]
+pub use crate::arc_length::ArcLengthTable;
+#[doc(inline)]
+pub use crate::conic::ConicSegment;
 
 mod scalar {
     pub(crate) use num_traits::{Float, FloatConst, NumCast};
@@ -151,6 +151,18 @@ impl<S: Scalar> LineSegment<S> {
         self.to_vector().length()
     }
 
+    /// Find the parameter t of the point of the segment that is closest to a given position.
+    pub fn closest_point_t(&self, p: Point<S>) -> S {
+        let v = self.to_vector();
+        let square_length = v.square_length();
+        if square_length == S::ZERO {
+            return S::ZERO;
+        }
+
+        let t = (p - self.from).dot(v) / square_length;
+        S::max(S::min(t, S::ONE), S::ZERO)
+    }
+
     /// Changes the segment's length, moving destination point.
     pub fn set_length(&mut self, new_length: S) {
         let v = self.to_vector();
@@ -376,6 +388,9 @@ impl<S: Scalar> Segment for LineSegment<S> {
     fn derivative(&self, _t: S) -> Vector<S> { self.to_vector() }
     fn dx(&self, _t: S) -> S { self.to.x - self.from.x }
     fn dy(&self, _t: S) -> S { self.to.y - self.from.y }
+    fn second_derivative(&self, _t: S) -> Vector<S> { Vector::zero() }
+    fn curvature(&self, _t: S) -> S { S::ZERO }
+    fn closest_point_t(&self, p: Point<S>) -> S { self.closest_point_t(p) }
     fn split_range(&self, t_range: Range<S>) -> Self { self.split_range(t_range) }
     fn split(&self, t: S) -> (Self, Self) { self.split(t) }
     fn before_split(&self, t: S) -> Self { self.before_split(t) }
@@ -873,3 +888,16 @@ fn horizontal_line_intersection() {
     assert_eq!(segment.horizontal_line_intersection_t(1.5), None);
     assert_eq!(segment.horizontal_line_intersection_t(3.5), None);
 }
+
+#[test]
+fn closest_point_t() {
+    let segment = LineSegment {
+        from: point(1.0f32, 1.0),
+        to: point(5.0, 1.0),
+    };
+
+    assert_eq!(segment.closest_point_t(point(2.0, 5.0)), 0.25);
+    assert_eq!(segment.closest_point_t(point(-2.0, 0.0)), 0.0);
+    assert_eq!(segment.closest_point_t(point(7.0, -3.0)), 1.0);
+    assert_eq!(Segment::curvature(&segment, 0.5), 0.0);
+}
@@ -38,6 +38,12 @@ impl<T: Segment> Monotonic<T> {
     #[inline]
     pub fn dy(&self, t: T::Scalar) -> T::Scalar { self.segment.dy(t) }
     #[inline]
+    pub fn second_derivative(&self, t: T::Scalar) -> Vector<T::Scalar> { self.segment.second_derivative(t) }
+    #[inline]
+    pub fn curvature(&self, t: T::Scalar) -> T::Scalar { self.segment.curvature(t) }
+    #[inline]
+    pub fn closest_point_t(&self, p: Point<T::Scalar>) -> T::Scalar { self.segment.closest_point_t(p) }
+    #[inline]
     pub fn split_range(&self, t_range: Range<T::Scalar>) -> Self {
         Self { segment: self.segment.split_range(t_range) }
     }
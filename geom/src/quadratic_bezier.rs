@@ -1,9 +1,11 @@
 use crate::{CubicBezierSegment, Triangle, Line, LineSegment, LineEquation};
+use crate::arc_length::ArcLengthTable;
 use crate::scalar::Scalar;
 use crate::generic_math::{Point, Vector, Rect, rect, Transform2D};
 use crate::monotonic::Monotonic;
 use crate::segment::{Segment, FlatteningStep, FlattenedForEach, BoundingRect};
 use crate::segment;
+use crate::utils::cubic_polynomial_roots;
 use arrayvec::ArrayVec;
 
 use std::ops::Range;
@@ -73,6 +75,56 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
         self.from.y * c0 + self.ctrl.y * c1 + self.to.y * c2
     }
 
+    /// Sample the curve's second derivative at t (expecting t between 0 and 1).
+    ///
+    /// The second derivative of a quadratic bézier curve is constant.
+    pub fn second_derivative(&self, _t: S) -> Vector<S> {
+        (self.from.to_vector() - self.ctrl.to_vector() * S::TWO + self.to.to_vector()) * S::TWO
+    }
+
+    /// Sample the signed curvature at t (expecting t between 0 and 1).
+    ///
+    /// See `Segment::curvature`.
+    pub fn curvature(&self, t: S) -> S {
+        segment::curvature(self.derivative(t), self.second_derivative(t))
+    }
+
+    /// Find the parameter t of the point of the curve that is closest to a given position.
+    pub fn closest_point_t(&self, p: Point<S>) -> S {
+        // The distance is minimal where (sample(t) - p) . derivative(t) is zero, which
+        // is a cubic polynomial.
+        let a = self.from - self.ctrl.to_vector() * S::TWO + self.to.to_vector();
+        let a = a.to_vector();
+        let b = (self.ctrl - self.from) * S::TWO;
+        let c = self.from - p;
+
+        let roots = cubic_polynomial_roots(
+            S::TWO * a.dot(a),
+            S::THREE * a.dot(b),
+            b.dot(b) + S::TWO * a.dot(c),
+            b.dot(c),
+        );
+
+        let mut best_t = S::ZERO;
+        let mut best_d = c.square_length();
+        let d = (self.to - p).square_length();
+        if d < best_d {
+            best_t = S::ONE;
+            best_d = d;
+        }
+        for t in roots {
+            if t > S::ZERO && t < S::ONE {
+                let d = (self.sample(t) - p).square_length();
+                if d < best_d {
+                    best_t = t;
+                    best_d = d;
+                }
+            }
+        }
+
+        best_t
+    }
+
     /// Swap the beginning and the end of the segment.
     pub fn flip(&self) -> Self {
         QuadraticBezierSegment {
@@ -346,6 +398,30 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
         segment::approximate_length_from_flattening(self, tolerance)
     }
 
+    /// Compute the length of the curve between its start and the point at `t`, using a
+    /// flattened approximation.
+    pub fn approximate_length_to_t(&self, t: S, tolerance: S) -> S {
+        if t <= S::ZERO {
+            return S::ZERO;
+        }
+        self.before_split(S::min(t, S::ONE)).approximate_length(tolerance)
+    }
+
+    /// Find the parameter t of the point at a given distance along the curve, using a
+    /// flattened approximation.
+    ///
+    /// For repeated queries on the same curve, build an `ArcLengthTable` with
+    /// `arc_length_table` instead.
+    pub fn approximate_t_at_length(&self, length: S, tolerance: S) -> S {
+        self.arc_length_table(tolerance).t_at_length(length)
+    }
+
+    /// Build a lookup table to map the parameter `t` to the distance along the curve
+    /// and vice versa.
+    pub fn arc_length_table(&self, tolerance: S) -> ArcLengthTable<S> {
+        ArcLengthTable::new(self, tolerance)
+    }
+
     /// Returns a triangle containing this curve segment.
     pub fn bounding_triangle(&self) -> Triangle<S> {
         Triangle {
@@ -426,21 +502,30 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
     /// point along curve. To get the intersection points, sample the curve
     /// at the corresponding values.
     pub fn line_intersections_t(&self, line: &Line<S>) -> ArrayVec<[S; 2]> {
-        // TODO: a specific quadratic bézier vs line intersection function
-        // would allow for better performance.
-        let intersections = self.to_cubic().line_intersections_t(line);
-
         let mut result = ArrayVec::new();
-        for t in intersections {
-            result.push(t);
+        if line.vector.square_length() < S::EPSILON {
+            return result;
         }
 
-        return result;
+        // Substitute the curve's polynomial form into the line's equation.
+        let a = self.from.to_vector() - self.ctrl.to_vector() * S::TWO + self.to.to_vector();
+        let b = (self.ctrl - self.from) * S::TWO;
+        let c = self.from - line.point;
+        let v = line.vector;
+
+        let roots = cubic_polynomial_roots(S::ZERO, v.cross(a), v.cross(b), v.cross(c));
+        for root in roots {
+            if root > S::ZERO && root < S::ONE {
+                result.push(root);
+            }
+        }
+
+        result
     }
 
     /// Computes the intersection points (if any) between this segment a line.
     pub fn line_intersections(&self, line: &Line<S>) -> ArrayVec<[Point<S>;2]> {
-        let intersections = self.to_cubic().line_intersections_t(line);
+        let intersections = self.line_intersections_t(line);
 
         let mut result = ArrayVec::new();
         for t in intersections {
@@ -456,17 +541,21 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
     /// point along curve and segment. To get the intersection points, sample
     /// the segments at the corresponding values.
     pub fn line_segment_intersections_t(&self, segment: &LineSegment<S>) -> ArrayVec<[(S, S); 2]> {
-        // TODO: a specific quadratic bézier vs line intersection function
-        // would allow for better performance.
-        let intersections = self.to_cubic().line_segment_intersections_t(&segment);
-        assert!(intersections.len() <= 2);
-
         let mut result = ArrayVec::new();
-        for t in intersections {
-            result.push(t);
+        if !self.fast_bounding_rect().intersects(&segment.bounding_rect()) {
+            return result;
         }
 
-        return result;
+        let v = segment.to_vector();
+        let inv_square_length = S::ONE / v.square_length();
+        for t in self.line_intersections_t(&segment.to_line()) {
+            let t2 = (self.sample(t) - segment.from).dot(v) * inv_square_length;
+            if t2 >= S::ZERO && t2 <= S::ONE {
+                result.push((t, t2));
+            }
+        }
+
+        result
     }
 
     #[inline]
@@ -477,8 +566,7 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
 
     /// Computes the intersection points (if any) between this segment a line segment.
     pub fn line_segment_intersections(&self, segment: &LineSegment<S>) -> ArrayVec<[Point<S>; 2]> {
-        let intersections = self.to_cubic().line_segment_intersections_t(&segment);
-        assert!(intersections.len() <= 2);
+        let intersections = self.line_segment_intersections_t(segment);
 
         let mut result = ArrayVec::new();
         for (t, _) in intersections {
@@ -487,6 +575,152 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
 
         return result;
     }
+
+    /// Computes the intersections (if any) between this segment and another one.
+    ///
+    /// The result is provided in the form of the `t` parameters of each point along both
+    /// curves. To get the intersection points, sample the curves at the corresponding values.
+    ///
+    /// Returns endpoint intersections where an endpoint intersects the interior of the other curve,
+    /// but not endpoint/endpoint intersections.
+    ///
+    /// Returns no intersections if the curves overlap.
+    pub fn quadratic_intersections_t(&self, curve: &QuadraticBezierSegment<S>) -> ArrayVec<[(S, S); 4]> {
+        let mut result = ArrayVec::new();
+        if !self.fast_bounding_rect().intersects(&curve.fast_bounding_rect())
+            || self == curve
+            || *self == curve.flip()
+        {
+            return result;
+        }
+
+        // The implicit equation of a quadratic bézier curve is simpler with control points that
+        // form a proper triangle.
+        let self_area = (self.ctrl - self.from).cross(self.to - self.from);
+        let curve_area = (curve.ctrl - curve.from).cross(curve.to - curve.from);
+        if S::abs(curve_area) > S::abs(self_area) {
+            for (t1, t2) in curve.quadratic_intersections_t(self) {
+                result.push((t2, t1));
+            }
+            return result;
+        }
+
+        if S::abs(self_area) <= S::EPSILON {
+            // Both curves are (almost) straight.
+            for (t1, t2) in self.to_cubic().cubic_intersections_t(&curve.to_cubic()) {
+                result.push((t1, t2));
+            }
+            return result;
+        }
+
+        // Using the barycentric coordinates (b0, b1, b2) of a point relative to the triangle
+        // formed by the control points, the curve is the set of points where b1² = 4 * b0 * b2.
+        // Substituting the other curve into this equation gives a polynomial of degree 4.
+        let e1 = self.ctrl - self.from;
+        let e2 = self.to - self.from;
+        let qa = curve.from.to_vector() - curve.ctrl.to_vector() * S::TWO + curve.to.to_vector();
+        let qb = (curve.ctrl - curve.from) * S::TWO;
+        let qc = curve.from - self.from;
+
+        // The barycentric coordinates along the other curve, multiplied by the area, as
+        // polynomials of degree 2 (constant term first).
+        let b1 = [qc.cross(e2), qb.cross(e2), qa.cross(e2)];
+        let b2 = [e1.cross(qc), e1.cross(qb), e1.cross(qa)];
+        let b0 = [self_area - b1[0] - b2[0], -b1[1] - b2[1], -b1[2] - b2[2]];
+
+        let mut poly = [S::ZERO; 5];
+        for i in 0..3 {
+            for j in 0..3 {
+                poly[i + j] += b1[i] * b1[j] - S::FOUR * b0[i] * b2[j];
+            }
+        }
+
+        let eval = |p: &[S], u: S| p.iter().rev().fold(S::ZERO, |acc, &c| acc * u + c);
+        let eval2 = |p: &[S; 3], u: S| p[0] + (p[1] + p[2] * u) * u;
+
+        let report = |u: S, result: &mut ArrayVec<[(S, S); 4]>| {
+            // The polynomial also vanishes on the rest of the parabola, where b1 is negative.
+            let b1 = eval2(&b1, u) / self_area;
+            let b2 = eval2(&b2, u) / self_area;
+            if b1 < -S::EPSILON || b2 < -S::EPSILON {
+                return;
+            }
+            let (b1, b2) = (S::max(b1, S::ZERO), S::max(b2, S::ZERO));
+            let t = if b1 + b2 == S::ZERO { S::ZERO } else { b2 / (b1 * S::HALF + b2) };
+
+            let endpoint = |t: S| t <= S::EPSILON || t >= S::ONE - S::EPSILON;
+            if (endpoint(t) && endpoint(u)) || result.is_full() {
+                return;
+            }
+            result.push((S::min(t, S::ONE), u));
+        };
+
+        // Split the unit interval at the local extrema of the polynomial and look for a root
+        // in each monotonic interval.
+        let mut bounds: ArrayVec<[S; 5]> = ArrayVec::new();
+        bounds.push(S::ZERO);
+        let mut extrema = cubic_polynomial_roots(S::FOUR * poly[4], S::THREE * poly[3], S::TWO * poly[2], poly[1]);
+        extrema.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+        for t in extrema {
+            if t > S::ZERO && t < S::ONE {
+                bounds.push(t);
+            }
+        }
+        bounds.push(S::ONE);
+
+        let scale = poly.iter().fold(S::ZERO, |acc, c| S::max(acc, S::abs(*c)));
+        let threshold = scale * S::EPSILON * S::EPSILON;
+
+        let mut prev = -S::ONE;
+        for i in 0..bounds.len() {
+            let u = bounds[i];
+            let f = eval(&poly, u);
+            if S::abs(f) <= threshold {
+                // A root at an extremum (tangent curves) or at the ends of the interval.
+                if S::abs(u - prev) > S::EPSILON {
+                    report(u, &mut result);
+                    prev = u;
+                }
+                continue;
+            }
+
+            if i == 0 {
+                continue;
+            }
+
+            let (mut a, mut b) = (bounds[i - 1], u);
+            let fa = eval(&poly, a);
+            if S::abs(fa) <= threshold || (fa < S::ZERO) == (f < S::ZERO) {
+                continue;
+            }
+            for _ in 0..64 {
+                let mid = (a + b) * S::HALF;
+                if mid <= a || mid >= b {
+                    break;
+                }
+                if (eval(&poly, mid) < S::ZERO) == (fa < S::ZERO) {
+                    a = mid;
+                } else {
+                    b = mid;
+                }
+            }
+            let root = (a + b) * S::HALF;
+            report(root, &mut result);
+            prev = root;
+        }
+
+        result
+    }
+
+    /// Computes the intersection points (if any) between this segment and another one.
+    pub fn quadratic_intersections(&self, curve: &QuadraticBezierSegment<S>) -> ArrayVec<[Point<S>; 4]> {
+        let mut result = ArrayVec::new();
+        for (t, _) in self.quadratic_intersections_t(curve) {
+            result.push(self.sample(t));
+        }
+
+        result
+    }
 }
 
 impl<S: Scalar> Segment for QuadraticBezierSegment<S> { impl_segment!(S); }
@@ -796,3 +1030,144 @@ fn test_flattening() {
     check_tolerance(&c3, 0.001);
     check_tolerance(&c3, 0.0001);
 }
+
+#[test]
+fn closest_point_and_curvature() {
+    use crate::math::point;
+
+    let c = QuadraticBezierSegment {
+        from: point(0.0f32, 0.0),
+        ctrl: point(1.0, 2.0),
+        to: point(2.0, 0.0),
+    };
+
+    assert!((c.closest_point_t(point(1.0, 5.0)) - 0.5).abs() < 1e-5);
+    assert_eq!(c.closest_point_t(point(-1.0, 0.0)), 0.0);
+    assert_eq!(c.closest_point_t(point(3.0, -1.0)), 1.0);
+
+    let t = c.closest_point_t(point(0.0, 1.0));
+    for i in 0..=100 {
+        let other = i as f32 / 100.0;
+        assert!((c.sample(t) - point(0.0, 1.0)).length() <= (c.sample(other) - point(0.0, 1.0)).length() + 1e-5);
+    }
+
+    // At the apex, the curve turns toward negative angles with a radius of 0.5.
+    assert!((c.curvature(0.5) + 2.0).abs() < 1e-5);
+    assert!((c.flip().curvature(0.5) - 2.0).abs() < 1e-5);
+}
+
+#[test]
+fn length_to_t_and_t_at_length() {
+    use crate::math::point;
+
+    let c = QuadraticBezierSegment {
+        from: point(0.0f32, 0.0),
+        ctrl: point(10.0, 20.0),
+        to: point(20.0, 0.0),
+    };
+
+    let length = c.approximate_length(0.0001);
+    // The curve is symmetric.
+    assert!((c.approximate_length_to_t(0.5, 0.0001) - length * 0.5).abs() < 0.01);
+    assert!((c.approximate_t_at_length(length * 0.5, 0.001) - 0.5).abs() < 0.001);
+    assert_eq!(c.approximate_length_to_t(0.0, 0.01), 0.0);
+
+    let t = c.approximate_t_at_length(length * 0.25, 0.001);
+    assert!((c.approximate_length_to_t(t, 0.0001) - length * 0.25).abs() < 0.01);
+}
+
+#[test]
+fn line_segment_intersections() {
+    use crate::generic_math::point;
+
+    let curve = QuadraticBezierSegment {
+        from: point(0.0f64, 0.0),
+        ctrl: point(5.0, 10.0),
+        to: point(10.0, 0.0),
+    };
+
+    // Crosses the curve twice.
+    let segment = LineSegment { from: point(-1.0, 2.0), to: point(11.0, 2.0) };
+    let intersections = curve.line_segment_intersections_t(&segment);
+    assert_eq!(intersections.len(), 2);
+    for (t1, t2) in intersections {
+        assert!((curve.sample(t1) - segment.sample(t2)).length() < 1e-9);
+        assert!((curve.sample(t1).y - 2.0).abs() < 1e-9);
+    }
+
+    // Only reaches one side of the curve.
+    let segment = LineSegment { from: point(-1.0, 2.0), to: point(5.0, 2.0) };
+    assert_eq!(curve.line_segment_intersections_t(&segment).len(), 1);
+
+    // Above the curve.
+    let segment = LineSegment { from: point(-1.0, 6.0), to: point(11.0, 6.0) };
+    assert!(curve.line_segment_intersections_t(&segment).is_empty());
+}
+
+#[test]
+fn quadratic_intersections() {
+    use crate::generic_math::point;
+
+    let a = QuadraticBezierSegment {
+        from: point(0.0f64, 0.0),
+        ctrl: point(5.0, 10.0),
+        to: point(10.0, 0.0),
+    };
+    let b = QuadraticBezierSegment {
+        from: point(0.0, 4.0),
+        ctrl: point(5.0, -6.0),
+        to: point(10.0, 4.0),
+    };
+
+    let intersections = a.quadratic_intersections_t(&b);
+    assert_eq!(intersections.len(), 2);
+    for &(t1, t2) in &intersections {
+        assert!((a.sample(t1) - b.sample(t2)).length() < 1e-6);
+        assert!((a.sample(t1).y - 2.0).abs() < 1e-6);
+    }
+    assert_eq!(a.quadratic_intersections(&b).len(), 2);
+
+    // The result doesn't depend on the order of the curves.
+    let reversed = b.quadratic_intersections_t(&a);
+    assert_eq!(reversed.len(), 2);
+    for &(t2, t1) in &reversed {
+        assert!(intersections.iter().any(|&(u1, u2)| (u1 - t1).abs() < 1e-6 && (u2 - t2).abs() < 1e-6));
+    }
+
+    // Four intersections.
+    let c = QuadraticBezierSegment {
+        from: point(0.0, 2.0),
+        ctrl: point(20.0, 5.0),
+        to: point(0.0, 8.0),
+    };
+    let d = QuadraticBezierSegment {
+        from: point(2.0, 0.0),
+        ctrl: point(5.0, 20.0),
+        to: point(8.0, 0.0),
+    };
+    let intersections = c.quadratic_intersections_t(&d);
+    assert_eq!(intersections.len(), 4);
+    for &(t1, t2) in &intersections {
+        assert!((c.sample(t1) - d.sample(t2)).length() < 1e-6);
+    }
+
+    // Tangent curves.
+    let e = QuadraticBezierSegment {
+        from: point(0.0, 10.0),
+        ctrl: point(5.0, 0.0),
+        to: point(10.0, 10.0),
+    };
+    let intersections = a.quadratic_intersections_t(&e);
+    assert_eq!(intersections.len(), 1);
+    assert!((a.sample(intersections[0].0) - point(5.0, 5.0)).length() < 1e-3);
+
+    // Overlapping and disjoint curves.
+    assert!(a.quadratic_intersections_t(&a).is_empty());
+    assert!(a.quadratic_intersections_t(&a.flip()).is_empty());
+    let f = QuadraticBezierSegment {
+        from: point(0.0, 20.0),
+        ctrl: point(5.0, 30.0),
+        to: point(10.0, 20.0),
+    };
+    assert!(a.quadratic_intersections_t(&f).is_empty());
+}
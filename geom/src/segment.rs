@@ -2,6 +2,8 @@ use crate::scalar::{Scalar, One};
 use crate::generic_math::{Point, Vector, Rect};
 use crate::{LineSegment, QuadraticBezierSegment, CubicBezierSegment};
 
+use arrayvec::ArrayVec;
+
 use std::ops::Range;
 
 /// Common APIs to segment types.
@@ -32,6 +34,42 @@ pub trait Segment: Copy + Sized {
     /// Sample y derivative at t (expecting t between 0 and 1).
     fn dy(&self, t: Self::Scalar) -> Self::Scalar { self.derivative(t).y }
 
+    /// Sample the second derivative at t (expecting t between 0 and 1).
+    ///
+    /// The default implementation approximates it with finite differences of the
+    /// derivative.
+    fn second_derivative(&self, t: Self::Scalar) -> Vector<Self::Scalar> {
+        let h = Self::Scalar::value(0.001);
+        let t0 = if t - h > Self::Scalar::ZERO { t - h } else { Self::Scalar::ZERO };
+        let t1 = if t + h < Self::Scalar::ONE { t + h } else { Self::Scalar::ONE };
+        (self.derivative(t1) - self.derivative(t0)) / (t1 - t0)
+    }
+
+    /// Sample the signed curvature at t (expecting t between 0 and 1).
+    ///
+    /// The curvature is positive where the curve turns in the direction of positive
+    /// angles (clockwise if the y axis points downwards). The radius of curvature is
+    /// the inverse of its absolute value.
+    ///
+    /// The curvature is not defined (infinite or NaN) where the derivative is zero.
+    fn curvature(&self, t: Self::Scalar) -> Self::Scalar {
+        curvature(self.derivative(t), self.second_derivative(t))
+    }
+
+    /// Find the parameter t of the point of the curve that is closest to a given position.
+    ///
+    /// The default implementation samples the curve and refines the closest candidates
+    /// with Newton iterations.
+    fn closest_point_t(&self, p: Point<Self::Scalar>) -> Self::Scalar {
+        closest_point_t_by_sampling(
+            p,
+            |t| self.sample(t),
+            |t| self.derivative(t),
+            |t| self.second_derivative(t),
+            16,
+        )
+    }
+
     /// Split this curve into two sub-curves.
     fn split(&self, t: Self::Scalar) -> (Self, Self);
 
@@ -164,6 +202,105 @@ where T: FlattenedForEach<Scalar=S>
     return len;
 }
 
+/// Signed curvature computed from the first and second derivatives of a curve.
+#[inline]
+pub(crate) fn curvature<S: Scalar>(d: Vector<S>, dd: Vector<S>) -> S {
+    let len = d.length();
+    d.cross(dd) / (len * len * len)
+}
+
+/// Find the parameter t of the closest point of a curve to a position.
+///
+/// The local minima of the distance are the roots of `(sample(t) - p) . derivative(t)`
+/// where it goes from negative to positive. They are bracketed by sampling the curve
+/// and refined using a Newton iteration that falls back to bisection when it leaves
+/// the bracket.
+pub(crate) fn closest_point_t_by_sampling<S, F, D, DD>(
+    p: Point<S>,
+    sample: F,
+    derivative: D,
+    second_derivative: DD,
+    num_samples: u32,
+) -> S
+where
+    S: Scalar,
+    F: Fn(S) -> Point<S>,
+    D: Fn(S) -> Vector<S>,
+    DD: Fn(S) -> Vector<S>,
+{
+    let f = |t: S| (sample(t) - p).dot(derivative(t));
+    let df = |t: S| derivative(t).square_length() + (sample(t) - p).dot(second_derivative(t));
+    let square_distance = |t: S| (sample(t) - p).square_length();
+
+    let refine = |mut a: S, mut b: S| -> S {
+        let mut t = (a + b) * S::HALF;
+        for _ in 0..32 {
+            let ft = f(t);
+            if ft == S::ZERO {
+                return t;
+            }
+            if ft < S::ZERO {
+                a = t;
+            } else {
+                b = t;
+            }
+
+            let dft = df(t);
+            let newton = t - ft / dft;
+            t = if dft > S::ZERO && newton > a && newton < b {
+                newton
+            } else {
+                (a + b) * S::HALF
+            };
+
+            if b - a <= S::EPSILON {
+                break;
+            }
+        }
+
+        t
+    };
+
+    let mut best_t = S::ZERO;
+    let mut best_d = square_distance(S::ZERO);
+    let consider = |t: S, best_t: &mut S, best_d: &mut S| {
+        let d = square_distance(t);
+        if d < *best_d {
+            *best_t = t;
+            *best_d = d;
+        }
+    };
+
+    let step = S::ONE / S::value(num_samples as f32);
+    let mut t0 = S::ZERO;
+    let mut f0 = f(t0);
+    for i in 1..=num_samples {
+        let t1 = if i == num_samples { S::ONE } else { S::value(i as f32) * step };
+        let f1 = f(t1);
+        consider(t1, &mut best_t, &mut best_d);
+        if f0 < S::ZERO && f1 >= S::ZERO {
+            let t = refine(t0, t1);
+            consider(t, &mut best_t, &mut best_d);
+        }
+        t0 = t1;
+        f0 = f1;
+    }
+
+    // Two roots can fall between the same pair of samples. In that case the closest
+    // sample is usually in the neighborhood of the minimum, so try to improve it as well.
+    let mut t = best_t;
+    for _ in 0..8 {
+        let dft = df(t);
+        if dft <= S::ZERO {
+            break;
+        }
+        t = S::min(S::max(t - f(t) / dft, S::ZERO), S::ONE);
+    }
+    consider(t, &mut best_t, &mut best_d);
+
+    best_t
+}
+
 macro_rules! impl_segment {
     ($S:ty) => (
         type Scalar = $S;
@@ -175,6 +312,9 @@ macro_rules! impl_segment {
         fn derivative(&self, t: $S) -> Vector<$S> { self.derivative(t) }
         fn dx(&self, t: $S) -> $S { self.dx(t) }
         fn dy(&self, t: $S) -> $S { self.dy(t) }
+        fn second_derivative(&self, t: $S) -> Vector<$S> { self.second_derivative(t) }
+        fn curvature(&self, t: $S) -> $S { self.curvature(t) }
+        fn closest_point_t(&self, p: Point<$S>) -> $S { self.closest_point_t(p) }
         fn split(&self, t: $S) -> (Self, Self) { self.split(t) }
         fn before_split(&self, t: $S) -> Self { self.before_split(t) }
         fn after_split(&self, t: $S) -> Self { self.after_split(t) }
@@ -257,6 +397,28 @@ impl<S: Scalar> BezierSegment<S> {
             }
         }
     }
+
+    /// Computes the intersections (if any) between this segment and another one.
+    ///
+    /// The result is provided in the form of the `t` parameters of each point along both
+    /// segments. To get the intersection points, sample the segments at the corresponding values.
+    pub fn intersections_t(&self, other: &BezierSegment<S>) -> ArrayVec<[(S, S); 9]> {
+        fn swapped<S: Copy, A: IntoIterator<Item = (S, S)>>(intersections: A) -> ArrayVec<[(S, S); 9]> {
+            intersections.into_iter().map(|(a, b)| (b, a)).collect()
+        }
+
+        match (self, other) {
+            (BezierSegment::Linear(a), BezierSegment::Linear(b)) => a.intersection_t(b).into_iter().collect(),
+            (BezierSegment::Linear(a), BezierSegment::Quadratic(b)) => swapped(b.line_segment_intersections_t(a)),
+            (BezierSegment::Linear(a), BezierSegment::Cubic(b)) => swapped(b.line_segment_intersections_t(a)),
+            (BezierSegment::Quadratic(a), BezierSegment::Linear(b)) => a.line_segment_intersections_t(b).into_iter().collect(),
+            (BezierSegment::Quadratic(a), BezierSegment::Quadratic(b)) => a.quadratic_intersections_t(b).into_iter().collect(),
+            (BezierSegment::Quadratic(a), BezierSegment::Cubic(b)) => swapped(b.quadratic_intersections_t(a)),
+            (BezierSegment::Cubic(a), BezierSegment::Linear(b)) => a.line_segment_intersections_t(b).into_iter().collect(),
+            (BezierSegment::Cubic(a), BezierSegment::Quadratic(b)) => a.quadratic_intersections_t(b),
+            (BezierSegment::Cubic(a), BezierSegment::Cubic(b)) => a.cubic_intersections_t(b),
+        }
+    }
 }
 
 impl<S> From<LineSegment<S>> for BezierSegment<S> {
@@ -277,3 +439,80 @@ impl<S> From<CubicBezierSegment<S>> for BezierSegment<S> {
     }
 }
 
+
+#[test]
+fn bezier_segment_intersections() {
+    use crate::math::point;
+
+    let line = BezierSegment::Linear(LineSegment { from: point(-1.0f32, 2.0), to: point(11.0, 2.0) });
+    let quadratic = BezierSegment::Quadratic(QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(5.0, 10.0),
+        to: point(10.0, 0.0),
+    });
+    let cubic = BezierSegment::Cubic(CubicBezierSegment {
+        from: point(0.0, 4.0),
+        ctrl1: point(3.0, -4.0),
+        ctrl2: point(7.0, -4.0),
+        to: point(10.0, 4.0),
+    });
+    let vertical = BezierSegment::Linear(LineSegment { from: point(5.0, -5.0), to: point(5.0, 10.0) });
+
+    let segments = [line, quadratic, cubic, vertical];
+    for a in &segments {
+        for b in &segments {
+            if a == b {
+                continue;
+            }
+            let intersections = a.intersections_t(b);
+            assert!(!intersections.is_empty());
+            for &(t1, t2) in &intersections {
+                assert!((a.sample(t1) - b.sample(t2)).length() < 0.01);
+            }
+            assert_eq!(intersections.len(), b.intersections_t(a).len());
+        }
+    }
+}
+
+#[test]
+fn default_closest_point() {
+    use crate::math::point;
+
+    // Only implements the required methods, forwarding to a quadratic bézier curve.
+    #[derive(Copy, Clone)]
+    struct Curve(QuadraticBezierSegment<f32>);
+
+    impl Segment for Curve {
+        type Scalar = f32;
+        fn from(&self) -> Point<f32> { self.0.from }
+        fn to(&self) -> Point<f32> { self.0.to }
+        fn sample(&self, t: f32) -> Point<f32> { self.0.sample(t) }
+        fn derivative(&self, t: f32) -> Vector<f32> { self.0.derivative(t) }
+        fn split(&self, t: f32) -> (Self, Self) {
+            let (a, b) = self.0.split(t);
+            (Curve(a), Curve(b))
+        }
+        fn before_split(&self, t: f32) -> Self { Curve(self.0.before_split(t)) }
+        fn after_split(&self, t: f32) -> Self { Curve(self.0.after_split(t)) }
+        fn split_range(&self, t_range: Range<f32>) -> Self { Curve(self.0.split_range(t_range)) }
+        fn flip(&self) -> Self { Curve(self.0.flip()) }
+        fn approximate_length(&self, tolerance: f32) -> f32 { self.0.approximate_length(tolerance) }
+    }
+
+    let quadratic = QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(5.0, 10.0),
+        to: point(10.0, 0.0),
+    };
+    let curve = Curve(quadratic);
+
+    for &t in &[0.0, 0.3, 0.5, 1.0] {
+        assert!((curve.second_derivative(t) - quadratic.second_derivative(t)).length() < 0.05);
+    }
+
+    for &p in &[point(5.0, 8.0), point(2.0, 1.0), point(-3.0, -1.0), point(9.0, 4.0)] {
+        let t = curve.closest_point_t(p);
+        let expected = quadratic.closest_point_t(p);
+        assert!((curve.sample(t) - quadratic.sample(expected)).length() < 0.001);
+    }
+}
@@ -0,0 +1,367 @@
+//! Conversion of Hermite, Catmull-Rom and uniform B-splines into cubic bézier segments.
+//!
+//! Each of these spline representations describes a sequence of cubic polynomial pieces
+//! which can be expressed exactly as cubic bézier segments.
+//!
+//! # Examples
+//!
+//! ```
+//! use lyon_geom::spline::{for_each_catmull_rom_segment, CatmullRomParameterization};
+//! use lyon_geom::math::point;
+//!
+//! let points = [
+//!     point(0.0, 0.0),
+//!     point(10.0, 10.0),
+//!     point(20.0, 0.0),
+//!     point(30.0, 10.0),
+//! ];
+//!
+//! let mut segments = Vec::new();
+//! for_each_catmull_rom_segment(
+//!     &points,
+//!     CatmullRomParameterization::Centripetal,
+//!     false,
+//!     &mut |segment| { segments.push(*segment); }
+//! );
+//!
+//! // The spline interpolates all of the points.
+//! assert_eq!(segments.len(), 3);
+//! assert_eq!(segments[1].from, points[1]);
+//! assert_eq!(segments[1].to, points[2]);
+//! ```
+
+use crate::scalar::Scalar;
+use crate::generic_math::{Point, Vector};
+use crate::CubicBezierSegment;
+
+/// The knot parameterization of a Catmull-Rom spline.
+///
+/// The centripetal parameterization is guaranteed to produce neither cusps nor
+/// self-intersections within a segment, and follows the control points more tightly
+/// than the uniform one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum CatmullRomParameterization {
+    /// Knots are evenly spaced (alpha = 0).
+    Uniform,
+    /// Knots are spaced according to the square root of the distance between the
+    /// points (alpha = 0.5).
+    Centripetal,
+    /// Knots are spaced according to the distance between the points (alpha = 1).
+    Chordal,
+}
+
+impl CatmullRomParameterization {
+    /// The exponent applied to the distance between consecutive points to compute the
+    /// knot intervals.
+    pub fn alpha<S: Scalar>(self) -> S {
+        match self {
+            CatmullRomParameterization::Uniform => S::ZERO,
+            CatmullRomParameterization::Centripetal => S::HALF,
+            CatmullRomParameterization::Chordal => S::ONE,
+        }
+    }
+}
+
+/// Converts a cubic Hermite segment, defined by its endpoints and the tangents at
+/// these endpoints, into a cubic bézier segment.
+pub fn hermite_to_cubic_bezier<S: Scalar>(
+    from: Point<S>,
+    from_tangent: Vector<S>,
+    to: Point<S>,
+    to_tangent: Vector<S>,
+) -> CubicBezierSegment<S> {
+    CubicBezierSegment {
+        from,
+        ctrl1: from + from_tangent / S::THREE,
+        ctrl2: to - to_tangent / S::THREE,
+        to,
+    }
+}
+
+/// Converts the Catmull-Rom segment between `p1` and `p2` into a cubic bézier segment.
+///
+/// `p0` and `p3` are the neighbouring control points of the spline.
+pub fn catmull_rom_to_cubic_bezier<S: Scalar>(
+    p0: Point<S>,
+    p1: Point<S>,
+    p2: Point<S>,
+    p3: Point<S>,
+    parameterization: CatmullRomParameterization,
+) -> CubicBezierSegment<S> {
+    let alpha: S = parameterization.alpha();
+    let knot_interval = |a: Point<S>, b: Point<S>| {
+        S::powf((b - a).square_length(), alpha * S::HALF)
+    };
+
+    let d1 = knot_interval(p0, p1);
+    let d2 = knot_interval(p1, p2);
+    let d3 = knot_interval(p2, p3);
+
+    // See "On the Parameterization of Catmull-Rom Curves", Yuksel et al. 2009.
+    // When two consecutive points coincide, the tangent is taken from the other side.
+    let ctrl1 = if d1 > S::EPSILON {
+        let d1_sq = d1 * d1;
+        let d2_sq = d2 * d2;
+        let num = p2.to_vector() * d1_sq
+            - p0.to_vector() * d2_sq
+            + p1.to_vector() * (S::TWO * d1_sq + S::THREE * d1 * d2 + d2_sq);
+        (num / (S::THREE * d1 * (d1 + d2))).to_point()
+    } else {
+        p1
+    };
+
+    let ctrl2 = if d3 > S::EPSILON {
+        let d3_sq = d3 * d3;
+        let d2_sq = d2 * d2;
+        let num = p1.to_vector() * d3_sq
+            - p3.to_vector() * d2_sq
+            + p2.to_vector() * (S::TWO * d3_sq + S::THREE * d3 * d2 + d2_sq);
+        (num / (S::THREE * d3 * (d3 + d2))).to_point()
+    } else {
+        p2
+    };
+
+    CubicBezierSegment { from: p1, ctrl1, ctrl2, to: p2 }
+}
+
+/// Converts the uniform cubic B-spline segment defined by four consecutive control points
+/// into a cubic bézier segment.
+///
+/// The resulting segment does not go through the control points.
+pub fn b_spline_to_cubic_bezier<S: Scalar>(
+    p0: Point<S>,
+    p1: Point<S>,
+    p2: Point<S>,
+    p3: Point<S>,
+) -> CubicBezierSegment<S> {
+    let six = S::value(6.0);
+    let ctrl1 = p1.lerp(p2, S::ONE / S::THREE);
+    let ctrl2 = p1.lerp(p2, S::TWO / S::THREE);
+    CubicBezierSegment {
+        from: ((p0.to_vector() + p1.to_vector() * S::FOUR + p2.to_vector()) / six).to_point(),
+        ctrl1,
+        ctrl2,
+        to: ((p1.to_vector() + p2.to_vector() * S::FOUR + p3.to_vector()) / six).to_point(),
+    }
+}
+
+/// Invokes a callback for each cubic bézier segment of a Hermite spline.
+///
+/// `tangents` must contain one tangent per point. If `closed` is true, an extra segment
+/// joins the last point to the first one.
+pub fn for_each_hermite_segment<S: Scalar, F>(
+    points: &[Point<S>],
+    tangents: &[Vector<S>],
+    closed: bool,
+    cb: &mut F,
+)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    assert_eq!(points.len(), tangents.len());
+    let n = points.len();
+    if n < 2 {
+        return;
+    }
+
+    let num_segments = if closed { n } else { n - 1 };
+    for i in 0..num_segments {
+        let j = (i + 1) % n;
+        cb(&hermite_to_cubic_bezier(points[i], tangents[i], points[j], tangents[j]));
+    }
+}
+
+/// Invokes a callback for each cubic bézier segment of a Catmull-Rom spline.
+///
+/// The spline goes through all of the points. For open splines, the tangents at the
+/// first and last points are computed by mirroring their neighbour. If `closed` is true,
+/// an extra segment joins the last point to the first one.
+pub fn for_each_catmull_rom_segment<S: Scalar, F>(
+    points: &[Point<S>],
+    parameterization: CatmullRomParameterization,
+    closed: bool,
+    cb: &mut F,
+)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    let n = points.len();
+    if n < 2 {
+        return;
+    }
+
+    let point = |i: isize| -> Point<S> {
+        if closed {
+            return points[i.rem_euclid(n as isize) as usize];
+        }
+        if i < 0 {
+            return points[0] + (points[0] - points[1]);
+        }
+        if i >= n as isize {
+            return points[n - 1] + (points[n - 1] - points[n - 2]);
+        }
+        points[i as usize]
+    };
+
+    let num_segments = if closed { n } else { n - 1 } as isize;
+    for i in 0..num_segments {
+        cb(&catmull_rom_to_cubic_bezier(
+            point(i - 1),
+            point(i),
+            point(i + 1),
+            point(i + 2),
+            parameterization,
+        ));
+    }
+}
+
+/// Invokes a callback for each cubic bézier segment of a uniform cubic B-spline.
+///
+/// An open spline made of `n` control points has `n - 3` segments and does not go
+/// through its first and last control points. If `closed` is true, the control points
+/// wrap around and the spline has `n` segments.
+pub fn for_each_b_spline_segment<S: Scalar, F>(
+    points: &[Point<S>],
+    closed: bool,
+    cb: &mut F,
+)
+where
+    F: FnMut(&CubicBezierSegment<S>)
+{
+    let n = points.len();
+    if closed {
+        if n < 3 {
+            return;
+        }
+        for i in 0..n {
+            cb(&b_spline_to_cubic_bezier(
+                points[i],
+                points[(i + 1) % n],
+                points[(i + 2) % n],
+                points[(i + 3) % n],
+            ));
+        }
+    } else {
+        for window in points.windows(4) {
+            cb(&b_spline_to_cubic_bezier(window[0], window[1], window[2], window[3]));
+        }
+    }
+}
+
+#[test]
+fn hermite_segment() {
+    use crate::math::{point, vector};
+
+    let curve = hermite_to_cubic_bezier(
+        point(0.0, 0.0),
+        vector(3.0, 0.0),
+        point(10.0, 0.0),
+        vector(0.0, 6.0),
+    );
+
+    assert_eq!(curve.ctrl1, point(1.0, 0.0));
+    assert_eq!(curve.ctrl2, point(10.0, -2.0));
+    assert_eq!(curve.derivative(0.0), vector(3.0, 0.0));
+    assert_eq!(curve.derivative(1.0), vector(0.0, 6.0));
+}
+
+#[test]
+fn uniform_catmull_rom() {
+    use crate::math::point;
+
+    let p0 = point(0.0, 0.0);
+    let p1 = point(1.0, 2.0);
+    let p2 = point(4.0, 2.0);
+    let p3 = point(5.0, -1.0);
+
+    let curve = catmull_rom_to_cubic_bezier(p0, p1, p2, p3, CatmullRomParameterization::Uniform);
+    assert_eq!(curve.from, p1);
+    assert_eq!(curve.to, p2);
+    // The tangents of a uniform Catmull-Rom spline are (p[i+1] - p[i-1]) / 2.
+    assert!((curve.derivative(0.0) - (p2 - p0) * 0.5).length() < 1e-5);
+    assert!((curve.derivative(1.0) - (p3 - p1) * 0.5).length() < 1e-5);
+}
+
+#[test]
+fn centripetal_catmull_rom() {
+    use crate::math::point;
+    use crate::CubicBezierType;
+
+    // Unevenly spaced points produce a loop with the uniform parameterization but
+    // not with the centripetal one.
+    let points = [
+        point(-11.0, -6.0),
+        point(0.0, 0.0),
+        point(1.0, 0.0),
+        point(12.0, -6.0),
+    ];
+
+    let mut segments = Vec::new();
+    for_each_catmull_rom_segment(
+        &points,
+        CatmullRomParameterization::Centripetal,
+        false,
+        &mut |segment| { segments.push(*segment); }
+    );
+    assert_eq!(segments.len(), 3);
+    for (i, segment) in segments.iter().enumerate() {
+        assert_eq!(segment.from, points[i]);
+        assert_eq!(segment.to, points[i + 1]);
+        assert_eq!(segment.self_intersection_t(), None);
+    }
+
+    // Consecutive segments are tangent-continuous.
+    for pair in segments.windows(2) {
+        let a = pair[0].derivative(1.0).normalize();
+        let b = pair[1].derivative(0.0).normalize();
+        assert!((a - b).length() < 1e-4);
+    }
+
+    let uniform = catmull_rom_to_cubic_bezier(
+        points[0],
+        points[1],
+        points[2],
+        points[3],
+        CatmullRomParameterization::Uniform,
+    );
+    assert!(match uniform.classify() {
+        CubicBezierType::Loop(t1, t2) => t1 >= 0.0 && t2 <= 1.0,
+        _ => false,
+    });
+}
+
+#[test]
+fn closed_splines() {
+    use crate::math::point;
+
+    let points = [
+        point(0.0, 0.0),
+        point(10.0, 0.0),
+        point(10.0, 10.0),
+        point(0.0, 10.0),
+    ];
+
+    let mut segments = Vec::new();
+    for_each_catmull_rom_segment(
+        &points,
+        CatmullRomParameterization::Chordal,
+        true,
+        &mut |segment| { segments.push(*segment); }
+    );
+    assert_eq!(segments.len(), 4);
+    assert_eq!(segments[3].to, segments[0].from);
+
+    let mut segments = Vec::new();
+    for_each_b_spline_segment(&points, true, &mut |segment| { segments.push(*segment); });
+    assert_eq!(segments.len(), 4);
+    for i in 0..4 {
+        assert_eq!(segments[i].to, segments[(i + 1) % 4].from);
+    }
+    // By symmetry the first segment starts at (p0 + 4 * p1 + p2) / 6.
+    assert!((segments[0].from - point(25.0 / 3.0, 5.0 / 3.0)).length() < 1e-5);
+
+    let mut count = 0;
+    for_each_b_spline_segment(&points, false, &mut |_| { count += 1; });
+    assert_eq!(count, 1);
+}
@@ -76,8 +76,10 @@
 
 use crate::math::*;
 use crate::events::{PathEvent, FlattenedEvent, SvgEvent};
-use crate::geom::{CubicBezierSegment, QuadraticBezierSegment, SvgArc, Arc, ArcFlags};
+use crate::geom::{CubicBezierSegment, QuadraticBezierSegment, ConicSegment, SvgArc, Arc, ArcFlags};
+use crate::geom::spline::{self, CatmullRomParameterization};
 use crate::path_state::PathState;
+use crate::iterator::DEFAULT_CONIC_TOLERANCE;
 use std::marker::Sized;
 
 pub trait Build {
@@ -133,6 +135,22 @@ pub trait FlatPathBuilder {
 pub trait PathBuilder: FlatPathBuilder {
     fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point);
     fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point);
+    /// Adds a rational quadratic bézier curve (conic) to the current sub-path.
+    ///
+    /// The weight of the control point is expected to be positive.
+    ///
+    /// The default implementation approximates the conic with quadratic bézier curves
+    /// using `DEFAULT_CONIC_TOLERANCE`.
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        ConicSegment {
+            from: self.current_position(),
+            ctrl,
+            to,
+            weight,
+        }.for_each_quadratic_bezier(DEFAULT_CONIC_TOLERANCE, &mut |curve| {
+            self.quadratic_bezier_to(curve.ctrl, curve.to);
+        });
+    }
     fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle);
 
     fn path_event(&mut self, event: PathEvent) {
@@ -149,6 +167,9 @@ pub trait PathBuilder: FlatPathBuilder {
             PathEvent::Cubic(segment) => {
                 self.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
             }
+            PathEvent::Conic(segment) => {
+                self.conic_to(segment.ctrl, segment.to, segment.weight);
+            }
             PathEvent::Close(..) => {
                 self.close();
             }
@@ -157,6 +178,9 @@ pub trait PathBuilder: FlatPathBuilder {
 
     /// Returns a builder that support svg commands.
     fn with_svg(self) -> SvgPathBuilder<Self> where Self : Sized { SvgPathBuilder::new(self) }
+
+    /// Returns a builder that supports adding splines.
+    fn with_splines(self) -> SplineBuilder<Self> where Self : Sized { SplineBuilder::new(self) }
 }
 
 /// A path building interface that tries to stay close to SVG's path specification.
@@ -256,7 +280,9 @@ pub trait PolygonBuilder {
     fn polygon(&mut self, points: &[Point]);
 }
 
-#[doc(hidden)]
+/// Adds a closed polygon to a builder.
+///
+/// Nothing is added if there are less than two points.
 pub fn build_polygon<Builder: FlatPathBuilder>(builder: &mut Builder, points: &[Point]) {
     if points.len() < 2 {
         return;
@@ -322,6 +348,11 @@ impl<Builder: PathBuilder> PathBuilder for SvgPathBuilder<Builder> {
         self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
     }
 
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        self.state.conic_to(ctrl, to, weight);
+        self.builder.conic_to(ctrl, to, weight);
+    }
+
     fn arc(
         &mut self,
         center: Point,
@@ -473,6 +504,15 @@ impl<Builder: FlatPathBuilder> PathBuilder for FlatteningBuilder<Builder> {
         }.for_each_flattened(self.tolerance, &mut |point| { self.line_to(point); });
     }
 
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        ConicSegment {
+            from: self.current_position(),
+            ctrl,
+            to,
+            weight,
+        }.for_each_flattened(self.tolerance, &mut |point| { self.line_to(point); });
+    }
+
     fn arc(
         &mut self,
         center: Point,
@@ -503,3 +543,283 @@ impl<Builder: FlatPathBuilder> FlatteningBuilder<Builder> {
 
     pub fn set_tolerance(&mut self, tolerance: f32) { self.tolerance = tolerance }
 }
+
+/// Adds Hermite, Catmull-Rom and uniform B-splines to a PathBuilder as sequences of
+/// cubic bézier segments.
+///
+/// Each spline is added as a new sub-path.
+pub struct SplineBuilder<Builder> {
+    builder: Builder,
+}
+
+impl<Builder: PathBuilder> SplineBuilder<Builder> {
+    pub fn new(builder: Builder) -> SplineBuilder<Builder> {
+        SplineBuilder { builder }
+    }
+
+    /// Add a Hermite spline going through the points with the provided tangents.
+    ///
+    /// `tangents` must contain one tangent per point.
+    pub fn hermite(&mut self, points: &[Point], tangents: &[Vector], closed: bool) {
+        if points.len() < 2 {
+            return;
+        }
+
+        self.builder.move_to(points[0]);
+        spline::for_each_hermite_segment(points, tangents, closed, &mut |segment| {
+            self.builder.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+        });
+        if closed {
+            self.builder.close();
+        }
+    }
+
+    /// Add a Catmull-Rom spline going through the points.
+    pub fn catmull_rom(
+        &mut self,
+        points: &[Point],
+        parameterization: CatmullRomParameterization,
+        closed: bool,
+    ) {
+        if points.len() < 2 {
+            return;
+        }
+
+        self.builder.move_to(points[0]);
+        spline::for_each_catmull_rom_segment(points, parameterization, closed, &mut |segment| {
+            self.builder.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+        });
+        if closed {
+            self.builder.close();
+        }
+    }
+
+    /// Add a uniform cubic B-spline.
+    ///
+    /// Open B-splines need at least four control points and closed ones at least three.
+    pub fn b_spline(&mut self, points: &[Point], closed: bool) {
+        let mut first = true;
+        let builder = &mut self.builder;
+        spline::for_each_b_spline_segment(points, closed, &mut |segment| {
+            if first {
+                builder.move_to(segment.from);
+                first = false;
+            }
+            builder.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+        });
+        if closed && !first {
+            builder.close();
+        }
+    }
+}
+
+impl<Builder: Build> Build for SplineBuilder<Builder> {
+    type PathType = Builder::PathType;
+
+    fn build(self) -> Builder::PathType { self.builder.build() }
+
+    fn build_and_reset(&mut self) -> Builder::PathType { self.builder.build_and_reset() }
+}
+
+impl<Builder: FlatPathBuilder> FlatPathBuilder for SplineBuilder<Builder> {
+    fn move_to(&mut self, to: Point) { self.builder.move_to(to); }
+
+    fn line_to(&mut self, to: Point) { self.builder.line_to(to); }
+
+    fn close(&mut self) { self.builder.close() }
+
+    fn current_position(&self) -> Point { self.builder.current_position() }
+}
+
+impl<Builder: PathBuilder> PathBuilder for SplineBuilder<Builder> {
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        self.builder.quadratic_bezier_to(ctrl, to);
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        self.builder.conic_to(ctrl, to, weight);
+    }
+
+    fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
+        self.builder.arc(center, radii, sweep_angle, x_rotation);
+    }
+}
+
+/// Builds a path from double-precision coordinates, storing them relative to an origin.
+///
+/// Single-precision floats lose precision quickly as coordinates get large (for example
+/// geographic data in projected meters). Subtracting an origin close to the data in double
+/// precision before converting to `f32` preserves the precision of the shape. Tessellating
+/// the resulting path produces geometry relative to the origin, which can be added back
+/// when rendering (typically in the transform).
+pub struct RecenteringBuilder<Builder> {
+    builder: Builder,
+    origin: F64Point,
+}
+
+impl<Builder: PathBuilder> RecenteringBuilder<Builder> {
+    pub fn new(builder: Builder, origin: F64Point) -> RecenteringBuilder<Builder> {
+        RecenteringBuilder { builder, origin }
+    }
+
+    /// The origin that is subtracted from the input coordinates.
+    pub fn origin(&self) -> F64Point { self.origin }
+
+    /// Converts a double-precision point into a single-precision one relative to the origin.
+    pub fn recenter(&self, p: F64Point) -> Point {
+        point((p.x - self.origin.x) as f32, (p.y - self.origin.y) as f32)
+    }
+
+    pub fn move_to(&mut self, to: F64Point) {
+        let to = self.recenter(to);
+        self.builder.move_to(to);
+    }
+
+    pub fn line_to(&mut self, to: F64Point) {
+        let to = self.recenter(to);
+        self.builder.line_to(to);
+    }
+
+    pub fn quadratic_bezier_to(&mut self, ctrl: F64Point, to: F64Point) {
+        let ctrl = self.recenter(ctrl);
+        let to = self.recenter(to);
+        self.builder.quadratic_bezier_to(ctrl, to);
+    }
+
+    pub fn cubic_bezier_to(&mut self, ctrl1: F64Point, ctrl2: F64Point, to: F64Point) {
+        let ctrl1 = self.recenter(ctrl1);
+        let ctrl2 = self.recenter(ctrl2);
+        let to = self.recenter(to);
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    pub fn conic_to(&mut self, ctrl: F64Point, to: F64Point, weight: f32) {
+        let ctrl = self.recenter(ctrl);
+        let to = self.recenter(to);
+        self.builder.conic_to(ctrl, to, weight);
+    }
+
+    pub fn close(&mut self) { self.builder.close(); }
+
+    /// Add a closed polygon.
+    pub fn polygon(&mut self, points: &[F64Point]) {
+        if points.len() < 2 {
+            return;
+        }
+
+        self.move_to(points[0]);
+        for p in &points[1..] {
+            self.line_to(*p);
+        }
+        self.close();
+    }
+}
+
+impl<Builder: Build> Build for RecenteringBuilder<Builder> {
+    type PathType = Builder::PathType;
+
+    fn build(self) -> Builder::PathType { self.builder.build() }
+
+    fn build_and_reset(&mut self) -> Builder::PathType { self.builder.build_and_reset() }
+}
+
+#[test]
+fn spline_builder() {
+    use crate::Path;
+
+    let points = [
+        point(0.0, 0.0),
+        point(10.0, 0.0),
+        point(10.0, 10.0),
+        point(0.0, 10.0),
+    ];
+
+    let mut builder = Path::builder().with_splines();
+    builder.catmull_rom(&points, CatmullRomParameterization::Centripetal, true);
+    builder.catmull_rom(&points, CatmullRomParameterization::Uniform, false);
+    builder.b_spline(&points, false);
+    builder.hermite(&points[..2], &[vector(10.0, 0.0), vector(10.0, 0.0)], false);
+    let path = builder.build();
+
+    let mut cubics = 0;
+    let mut sub_paths = Vec::new();
+    for event in path.iter() {
+        match event {
+            PathEvent::MoveTo(to) => { sub_paths.push(to); }
+            PathEvent::Cubic(..) => { cubics += 1; }
+            PathEvent::Close(..) => {}
+            _ => { panic!("unexpected event {:?}", event); }
+        }
+    }
+
+    assert_eq!(sub_paths.len(), 4);
+    assert_eq!(sub_paths[0], points[0]);
+    assert_eq!(sub_paths[1], points[0]);
+    assert_eq!(sub_paths[3], points[0]);
+    assert_eq!(cubics, 4 + 3 + 1 + 1);
+}
+
+#[test]
+fn recentering_builder() {
+    use crate::Path;
+    use crate::geom::LineSegment;
+
+    let origin = F64Point::new(6_500_000.0, 1_250_000.0);
+    let mut builder = RecenteringBuilder::new(Path::builder(), origin);
+    builder.polygon(&[
+        F64Point::new(6_500_000.25, 1_250_000.5),
+        F64Point::new(6_500_001.75, 1_250_000.5),
+        F64Point::new(6_500_001.75, 1_250_001.125),
+    ]);
+    assert_eq!(builder.origin(), origin);
+    let path = builder.build();
+
+    let mut events = path.iter();
+    assert_eq!(events.next(), Some(PathEvent::MoveTo(point(0.25, 0.5))));
+    assert_eq!(events.next(), Some(PathEvent::Line(LineSegment { from: point(0.25, 0.5), to: point(1.75, 0.5) })));
+    assert_eq!(events.next(), Some(PathEvent::Line(LineSegment { from: point(1.75, 0.5), to: point(1.75, 1.125) })));
+}
+
+#[test]
+fn default_conic_to() {
+    // A builder that doesn't override conic_to.
+    struct Quadratics {
+        current: Point,
+        curves: Vec<QuadraticBezierSegment<f32>>,
+    }
+
+    impl FlatPathBuilder for Quadratics {
+        fn move_to(&mut self, to: Point) { self.current = to; }
+        fn line_to(&mut self, to: Point) { self.current = to; }
+        fn close(&mut self) {}
+        fn current_position(&self) -> Point { self.current }
+    }
+
+    impl PathBuilder for Quadratics {
+        fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+            self.curves.push(QuadraticBezierSegment { from: self.current, ctrl, to });
+            self.current = to;
+        }
+        fn cubic_bezier_to(&mut self, _: Point, _: Point, to: Point) { self.current = to; }
+        fn arc(&mut self, _: Point, _: Vector, _: Angle, _: Angle) {}
+    }
+
+    let mut builder = Quadratics { current: point(0.0, 0.0), curves: Vec::new() };
+    builder.move_to(point(1.0, 0.0));
+    builder.conic_to(point(1.0, 1.0), point(0.0, 1.0), std::f32::consts::FRAC_1_SQRT_2);
+
+    // A quarter of the unit circle.
+    assert!(builder.curves.len() > 1);
+    assert_eq!(builder.curves[0].from, point(1.0, 0.0));
+    assert_eq!(builder.current_position(), point(0.0, 1.0));
+    for curve in &builder.curves {
+        for i in 0..=10 {
+            let p = curve.sample(i as f32 / 10.0);
+            assert!((p.to_vector().length() - 1.0).abs() < DEFAULT_CONIC_TOLERANCE);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment};
+use crate::geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, ConicSegment};
 use crate::math::{Point, Vector, Angle, Transform2D, Transform};
 use crate::ArcFlags;
 
@@ -43,6 +43,10 @@ pub enum PathEvent {
     Line(LineSegment<f32>),
     Quadratic(QuadraticBezierSegment<f32>),
     Cubic(CubicBezierSegment<f32>),
+    /// A rational quadratic bézier curve.
+    ///
+    /// Conics don't have an equivalent in SVG's path syntax.
+    Conic(ConicSegment<f32>),
     Close(LineSegment<f32>),
 }
 
@@ -157,6 +161,7 @@ impl Transform for PathEvent {
             PathEvent::Line(ref segment) => { PathEvent::Line(segment.transform(mat)) }
             PathEvent::Quadratic(ref segment) => { PathEvent::Quadratic(segment.transform(mat)) }
             PathEvent::Cubic(ref segment) => { PathEvent::Cubic(segment.transform(mat)) }
+            PathEvent::Conic(ref segment) => { PathEvent::Conic(segment.transform(mat)) }
             PathEvent::Close(ref segment) => { PathEvent::Close(segment.transform(mat)) }
         }
     }
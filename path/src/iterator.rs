@@ -142,9 +142,10 @@ use std::iter;
 
 use crate::math::*;
 use crate::{PathEvent, SvgEvent, FlattenedEvent, QuadraticEvent, PathState};
-use crate::geom::{BezierSegment, QuadraticBezierSegment, CubicBezierSegment, LineSegment, quadratic_bezier, cubic_bezier};
+use crate::geom::{BezierSegment, QuadraticBezierSegment, CubicBezierSegment, LineSegment, quadratic_bezier, cubic_bezier, conic};
 use crate::geom::arc::*;
 use crate::geom::arrayvec::ArrayVec;
+use crate::geom::cubic_to_quadratic::cubic_to_quadratics;
 use crate::builder::SvgBuilder;
 
 /// An extension trait for `PathEvent` iterators.
@@ -161,8 +162,25 @@ pub trait PathIterator: Iterator<Item = PathEvent> + Sized {
     }
 
     /// Returns an iterator of segments.
+    ///
+    /// Conics are approximated with sequences of quadratic bézier segments, using
+    /// `DEFAULT_CONIC_TOLERANCE`.
     fn bezier_segments(self) -> BezierSegments<Self> {
-        BezierSegments { iter: self }
+        BezierSegments { iter: self, conic_to_quadratics: Vec::new() }
+    }
+
+    /// Returns an iterator that approximates cubic bézier curves and conics with
+    /// quadratic bézier curves.
+    fn to_quadratics(self, tolerance: f32) -> ToQuadratics<Self> {
+        ToQuadratics::new(tolerance, self)
+    }
+
+    /// Returns an iterator that represents all curves with cubic bézier curves.
+    ///
+    /// Quadratic bézier curves are converted exactly, while conics are approximated
+    /// within the provided tolerance.
+    fn to_cubics(self, tolerance: f32) -> ToCubics<Self> {
+        ToCubics::new(tolerance, self)
     }
 }
 
@@ -427,6 +445,7 @@ pub struct Flattened<Iter> {
 enum TmpFlatteningIter {
     Quadratic(quadratic_bezier::Flattened<f32>),
     Cubic(cubic_bezier::Flattened<f32>),
+    Conic(Box<conic::Flattened<f32>>),
     None,
 }
 
@@ -463,6 +482,13 @@ where
                     return Some(FlattenedEvent::Line(LineSegment { from, to }));
                 }
             }
+            TmpFlatteningIter::Conic(ref mut it) => {
+                if let Some(to) = it.next() {
+                    let from = self.current_position;
+                    self.current_position = to;
+                    return Some(FlattenedEvent::Line(LineSegment { from, to }));
+                }
+            }
             _ => {}
         }
         self.current_curve = TmpFlatteningIter::None;
@@ -484,6 +510,13 @@ where
                 );
                 self.next()
             }
+            Some(PathEvent::Conic(segment)) => {
+                self.current_position = segment.from;
+                self.current_curve = TmpFlatteningIter::Conic(
+                    Box::new(segment.flattened(self.tolerance))
+                );
+                self.next()
+            }
             None => None,
         }
     }
@@ -662,27 +695,146 @@ where
     }
 }
 
+/// Maximum distance between conics and the quadratic bézier curves approximating them,
+/// where the caller doesn't provide a tolerance.
+pub const DEFAULT_CONIC_TOLERANCE: f32 = 0.01;
+
 /// Turns an iterator of `PathEvent` into an iterator of `BezierSegment<f32>`.
+///
+/// Conics are approximated with quadratic bézier segments.
 pub struct BezierSegments<Iter> {
-    iter: Iter
+    iter: Iter,
+    conic_to_quadratics: Vec<QuadraticBezierSegment<f32>>,
 }
 
 impl<Iter> Iterator for BezierSegments<Iter>
 where Iter: Iterator<Item = PathEvent> {
     type Item = BezierSegment<f32>;
     fn next(&mut self) -> Option<BezierSegment<f32>> {
+        if let Some(segment) = self.conic_to_quadratics.pop() {
+            return Some(BezierSegment::Quadratic(segment));
+        }
         match self.iter.next() {
             Some(PathEvent::Line(segment))
             | Some(PathEvent::Close(segment))
             => Some(BezierSegment::Linear(segment)),
             Some(PathEvent::Quadratic(segment)) => Some(BezierSegment::Quadratic(segment)),
             Some(PathEvent::Cubic(segment)) => Some(BezierSegment::Cubic(segment)),
+            Some(PathEvent::Conic(segment)) => {
+                segment.for_each_quadratic_bezier(DEFAULT_CONIC_TOLERANCE, &mut |curve| {
+                    self.conic_to_quadratics.push(*curve);
+                });
+                // Yield in order.
+                self.conic_to_quadratics.reverse();
+                self.next()
+            }
             Some(PathEvent::MoveTo(..)) => self.next(),
             None => None,
         }
     }
 }
 
+/// An iterator that consumes a `PathEvent` iterator and yields `QuadraticEvent`s.
+///
+/// Cubic bézier curves and conics are approximated with sequences of quadratic bézier
+/// curves.
+pub struct ToQuadratics<Iter> {
+    it: Iter,
+    tolerance: f32,
+    // Stored in reverse order.
+    pending: Vec<QuadraticBezierSegment<f32>>,
+}
+
+impl<Iter: Iterator<Item = PathEvent>> ToQuadratics<Iter> {
+    /// Create the iterator.
+    pub fn new(tolerance: f32, it: Iter) -> Self {
+        ToQuadratics {
+            it,
+            tolerance,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<Iter> Iterator for ToQuadratics<Iter>
+where
+    Iter: Iterator<Item = PathEvent>,
+{
+    type Item = QuadraticEvent;
+    fn next(&mut self) -> Option<QuadraticEvent> {
+        if let Some(segment) = self.pending.pop() {
+            return Some(QuadraticEvent::Quadratic(segment));
+        }
+        match self.it.next() {
+            Some(PathEvent::MoveTo(to)) => Some(QuadraticEvent::MoveTo(to)),
+            Some(PathEvent::Line(segment)) => Some(QuadraticEvent::Line(segment)),
+            Some(PathEvent::Close(segment)) => Some(QuadraticEvent::Close(segment)),
+            Some(PathEvent::Quadratic(segment)) => Some(QuadraticEvent::Quadratic(segment)),
+            Some(PathEvent::Cubic(segment)) => {
+                cubic_to_quadratics(&segment, self.tolerance, &mut |curve| {
+                    self.pending.push(*curve);
+                });
+                self.pending.reverse();
+                self.next()
+            }
+            Some(PathEvent::Conic(segment)) => {
+                segment.for_each_quadratic_bezier(self.tolerance, &mut |curve| {
+                    self.pending.push(*curve);
+                });
+                self.pending.reverse();
+                self.next()
+            }
+            None => None,
+        }
+    }
+}
+
+/// An iterator that consumes a `PathEvent` iterator and yields `PathEvent`s in which
+/// all curves are cubic bézier curves.
+///
+/// Quadratic bézier curves are converted exactly through degree elevation. Conics are
+/// approximated with quadratic bézier curves first.
+pub struct ToCubics<Iter> {
+    it: Iter,
+    tolerance: f32,
+    // Stored in reverse order.
+    pending: Vec<CubicBezierSegment<f32>>,
+}
+
+impl<Iter: Iterator<Item = PathEvent>> ToCubics<Iter> {
+    /// Create the iterator.
+    pub fn new(tolerance: f32, it: Iter) -> Self {
+        ToCubics {
+            it,
+            tolerance,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<Iter> Iterator for ToCubics<Iter>
+where
+    Iter: Iterator<Item = PathEvent>,
+{
+    type Item = PathEvent;
+    fn next(&mut self) -> Option<PathEvent> {
+        if let Some(segment) = self.pending.pop() {
+            return Some(PathEvent::Cubic(segment));
+        }
+        match self.it.next() {
+            Some(PathEvent::Quadratic(segment)) => Some(PathEvent::Cubic(segment.to_cubic())),
+            Some(PathEvent::Conic(segment)) => {
+                segment.for_each_quadratic_bezier(self.tolerance, &mut |curve| {
+                    self.pending.push(curve.to_cubic());
+                });
+                self.pending.reverse();
+                self.next()
+            }
+            evt => evt,
+        }
+    }
+}
+
 /// Turns an iterator of `FlattenedEvent` into an iterator of `LineSegment<f32>`.
 pub struct LineSegments<Iter> {
     iter: Iter
@@ -752,3 +904,101 @@ fn test_from_polyline_closed() {
     assert_eq!(evts.next(), Some(FlattenedEvent::Line(LineSegment { from: point(4.0, 5.0), to: point(5.0, 2.0) })));
     assert_eq!(evts.next(), Some(FlattenedEvent::Close(LineSegment { from: point(5.0, 2.0), to: point(1.0, 1.0) })));
 }
+
+#[test]
+fn test_conic_iterators() {
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(100.0, 0.0));
+    builder.conic_to(point(100.0, 100.0), point(0.0, 100.0), 0.5f32.sqrt());
+    builder.line_to(point(0.0, 0.0));
+    let path = builder.build();
+
+    let mut from = point(100.0, 0.0);
+    let mut num_quadratics = 0;
+    for segment in path.iter().bezier_segments() {
+        assert_eq!(segment.from(), from);
+        if let BezierSegment::Quadratic(..) = segment {
+            num_quadratics += 1;
+            assert!((segment.sample(0.5).to_vector().length() - 100.0).abs() <= 0.01);
+        }
+        from = segment.to();
+    }
+    assert!(num_quadratics > 1);
+    assert_eq!(from, point(0.0, 0.0));
+
+    let mut num_lines = 0;
+    for segment in path.iter().flattened(0.1).line_segments() {
+        if segment.to == point(0.0, 0.0) {
+            break;
+        }
+        num_lines += 1;
+        assert!(100.0 - segment.sample(0.5).to_vector().length() <= 0.1 + 1e-3);
+    }
+    assert!(num_lines > 2);
+}
+
+#[test]
+fn test_to_quadratics_and_cubics() {
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0));
+    builder.cubic_bezier_to(point(20.0, 30.0), point(-10.0, 0.0), point(0.0, 30.0));
+    builder.conic_to(point(0.0, 40.0), point(-10.0, 40.0), 0.5);
+    builder.close();
+    let path = builder.build();
+
+    let mut num_quadratics = 0;
+    let mut from = point(0.0, 0.0);
+    for evt in path.iter().to_quadratics(0.01) {
+        match evt {
+            QuadraticEvent::MoveTo(to) => { from = to; }
+            QuadraticEvent::Line(segment) | QuadraticEvent::Close(segment) => {
+                assert_eq!(segment.from, from);
+                from = segment.to;
+            }
+            QuadraticEvent::Quadratic(segment) => {
+                assert_eq!(segment.from, from);
+                from = segment.to;
+                num_quadratics += 1;
+            }
+        }
+    }
+    assert!(num_quadratics > 3);
+    assert_eq!(from, point(0.0, 0.0));
+
+    let events: Vec<PathEvent> = path.iter().to_cubics(0.01).collect();
+    assert_eq!(events[0], PathEvent::MoveTo(point(0.0, 0.0)));
+    assert_eq!(events[1], PathEvent::Line(LineSegment { from: point(0.0, 0.0), to: point(10.0, 0.0) }));
+    let quadratic = QuadraticBezierSegment {
+        from: point(10.0, 0.0),
+        ctrl: point(20.0, 0.0),
+        to: point(20.0, 10.0),
+    };
+    assert_eq!(events[2], PathEvent::Cubic(quadratic.to_cubic()));
+    assert_eq!(
+        events[3],
+        PathEvent::Cubic(CubicBezierSegment {
+            from: point(20.0, 10.0),
+            ctrl1: point(20.0, 30.0),
+            ctrl2: point(-10.0, 0.0),
+            to: point(0.0, 30.0),
+        })
+    );
+    for evt in &events[4..events.len() - 1] {
+        match evt {
+            PathEvent::Cubic(..) => {}
+            _ => { panic!("unexpected event {:?}", evt); }
+        }
+    }
+    match events.last() {
+        Some(PathEvent::Close(segment)) => {
+            assert_eq!(segment.from, point(-10.0, 40.0));
+        }
+        evt => { panic!("unexpected event {:?}", evt); }
+    }
+}
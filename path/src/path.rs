@@ -4,7 +4,7 @@ use crate::builder::*;
 use crate::PathEvent;
 use crate::VertexId;
 use crate::math::*;
-use crate::geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, Arc};
+use crate::geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, ConicSegment, Arc};
 
 use std::iter::IntoIterator;
 use std::ops;
@@ -22,6 +22,7 @@ enum Verb {
     LineTo,
     QuadraticTo,
     CubicTo,
+    ConicTo,
     Close,
 }
 
@@ -33,6 +34,8 @@ enum Verb {
 pub struct Path {
     points: Box<[Point]>,
     verbs: Box<[Verb]>,
+    // The weights of the conic segments, in order.
+    weights: Box<[f32]>,
 }
 
 /// A view on a `Path`.
@@ -40,6 +43,7 @@ pub struct Path {
 pub struct PathSlice<'l> {
     points: &'l [Point],
     verbs: &'l [Verb],
+    weights: &'l [f32],
 }
 
 impl Path {
@@ -51,6 +55,7 @@ impl Path {
         Path {
             points: Box::new([]),
             verbs: Box::new([]),
+            weights: Box::new([]),
         }
     }
 
@@ -59,11 +64,12 @@ impl Path {
         PathSlice {
             points: &self.points[..],
             verbs: &self.verbs[..],
+            weights: &self.weights[..],
         }
     }
 
     /// Iterates over the entire `Path`.
-    pub fn iter(&self) -> Iter { Iter::new(&self.points[..], &self.verbs[..]) }
+    pub fn iter(&self) -> Iter<'_> { Iter::new(&self.points[..], &self.verbs[..], &self.weights[..]) }
 
     pub fn points(&self) -> &[Point] { &self.points[..] }
 
@@ -77,10 +83,14 @@ impl Path {
         verbs.extend_from_slice(&other.verbs);
         points.extend_from_slice(&self.points);
         points.extend_from_slice(&other.points);
+        let mut weights = Vec::with_capacity(self.weights.len() + other.weights.len());
+        weights.extend_from_slice(&self.weights);
+        weights.extend_from_slice(&other.weights);
 
         Path {
             verbs: verbs.into_boxed_slice(),
             points: points.into_boxed_slice(),
+            weights: weights.into_boxed_slice(),
         }
     }
 
@@ -91,6 +101,7 @@ impl Path {
             verb: 0,
             first_vertex: VertexId(0),
             first_verb: 0,
+            weight: 0,
         }
     }
 }
@@ -112,13 +123,14 @@ impl<'l> Into<PathSlice<'l>> for &'l Path {
 impl<'l> PathSlice<'l> {
 
     pub fn iter<'a>(&'a self) -> Iter<'l> {
-        Iter::new(self.points, self.verbs)
+        Iter::new(self.points, self.verbs, self.weights)
     }
 
     pub fn iter_from(&self, cursor: Cursor) -> Iter {
         Iter::new(
             &self.points[cursor.vertex.offset() as usize..],
             &self.verbs[cursor.verb as usize..],
+            &self.weights[cursor.weight as usize..],
         )
     }
 
@@ -126,6 +138,7 @@ impl<'l> PathSlice<'l> {
         Iter::new(
             &self.points[..cursor.vertex.offset() as usize],
             &self.verbs[..cursor.verb as usize],
+            &self.weights[..cursor.weight as usize],
         )
     }
 
@@ -133,6 +146,7 @@ impl<'l> PathSlice<'l> {
         Iter::new(
             &self.points[cursor.start.vertex.offset() as usize .. cursor.end.vertex.offset() as usize],
             &self.verbs[cursor.start.verb as usize .. cursor.end.verb as usize],
+            &self.weights[cursor.start.weight as usize .. cursor.end.weight as usize],
         )
     }
 
@@ -159,6 +173,7 @@ impl<'l, 'a> IntoIterator for &'a PathSlice<'l> {
 pub struct Builder {
     points: Vec<Point>,
     verbs: Vec<Verb>,
+    weights: Vec<f32>,
     current_position: Point,
     first_position: Point,
     first_vertex: VertexId,
@@ -173,6 +188,7 @@ impl Builder {
         Builder {
             points: Vec::with_capacity(cap),
             verbs: Vec::with_capacity(cap),
+            weights: Vec::new(),
             current_position: Point::new(0.0, 0.0),
             first_position: Point::new(0.0, 0.0),
             first_vertex: VertexId(0),
@@ -248,6 +264,18 @@ impl Builder {
         self.current_position = to;
     }
 
+    pub fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        nan_check(ctrl);
+        nan_check(to);
+        debug_assert!(weight.is_finite());
+        self.move_to_if_needed();
+        self.points.push(ctrl);
+        self.points.push(to);
+        self.weights.push(weight);
+        self.verbs.push(Verb::ConicTo);
+        self.current_position = to;
+    }
+
     pub fn arc(
         &mut self,
         center: Point,
@@ -287,12 +315,14 @@ impl Builder {
     pub fn cursor(&self) -> Cursor {
         if let Some(verb) = self.verbs.last() {
             let p = self.points.len() - n_stored_points(*verb) as usize;
+            let w = self.weights.len() - if *verb == Verb::ConicTo { 1 } else { 0 };
 
             Cursor {
                 vertex: VertexId::from_usize(p),
                 verb: self.verbs.len() as u32 - 1,
                 first_vertex: self.first_vertex,
                 first_verb: self.first_verb,
+                weight: w as u32,
             }
         } else {
             Cursor {
@@ -300,6 +330,7 @@ impl Builder {
                 verb: 0,
                 first_vertex: VertexId(0),
                 first_verb: 0,
+                weight: 0,
             }
         }
     }
@@ -308,6 +339,7 @@ impl Builder {
         Path {
             points: self.points.into_boxed_slice(),
             verbs: self.verbs.into_boxed_slice(),
+            weights: self.weights.into_boxed_slice(),
         }
     }
 }
@@ -320,6 +352,8 @@ pub struct Cursor {
     verb: u32,
     first_vertex: VertexId,
     first_verb: u32,
+    // Number of conic segments before the cursor.
+    weight: u32,
 }
 
 impl Cursor {
@@ -343,7 +377,7 @@ impl Cursor {
     pub fn event<'l, P>(&self, path: P) -> PathEvent
     where P : Into<PathSlice<'l>> {
         let path = path.into();
-        event_at_cursor(self, &path.points, &path.verbs)
+        event_at_cursor(self, path.points, path.verbs, path.weights)
     }
 }
 
@@ -351,6 +385,8 @@ pub fn reverse_path(path: PathSlice, builder: &mut dyn PathBuilder) {
     let points = &path.points[..];
     // At each iteration, p points to the first point after the current verb.
     let mut p = points.len();
+    // Same thing for the conic weights.
+    let mut w = path.weights.len();
     let mut need_close = false;
     let mut need_moveto = true;
 
@@ -361,7 +397,8 @@ pub fn reverse_path(path: PathSlice, builder: &mut dyn PathBuilder) {
         match v {
             Verb::LineTo
             | Verb::QuadraticTo
-            | Verb::CubicTo => {
+            | Verb::CubicTo
+            | Verb::ConicTo => {
                 if need_moveto {
                     need_moveto = false;
                     builder.move_to(points[p - 1]);
@@ -392,6 +429,10 @@ pub fn reverse_path(path: PathSlice, builder: &mut dyn PathBuilder) {
             Verb::CubicTo => {
                 builder.cubic_bezier_to(points[p - 2], points[p - 3], points[p - 4]);
             }
+            Verb::ConicTo => {
+                w -= 1;
+                builder.conic_to(points[p - 2], points[p - 3], path.weights[w]);
+            }
         }
         p -= n_stored_points(v) as usize;
     }
@@ -508,8 +549,9 @@ impl Build for Builder {
         self.first_position = Point::new(0.0, 0.0);
 
         Path {
-            points: mem::replace(&mut self.points, Vec::new()).into_boxed_slice(),
-            verbs: mem::replace(&mut self.verbs, Vec::new()).into_boxed_slice(),
+            points: mem::take(&mut self.points).into_boxed_slice(),
+            verbs: mem::take(&mut self.verbs).into_boxed_slice(),
+            weights: mem::take(&mut self.weights).into_boxed_slice(),
         }
     }
 }
@@ -565,6 +607,10 @@ impl PathBuilder for Builder {
         self.cubic_bezier_to(ctrl1, ctrl2, to);
     }
 
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        self.conic_to(ctrl, to, weight);
+    }
+
     fn arc(
         &mut self,
         center: Point,
@@ -581,15 +627,17 @@ impl PathBuilder for Builder {
 pub struct Iter<'l> {
     points: ::std::slice::Iter<'l, Point>,
     verbs: ::std::slice::Iter<'l, Verb>,
+    weights: ::std::slice::Iter<'l, f32>,
     current: Point,
     first: Point,
 }
 
 impl<'l> Iter<'l> {
-    fn new(points: &'l [Point], verbs: &'l [Verb]) -> Self {
+    fn new(points: &'l [Point], verbs: &'l [Verb], weights: &'l [f32]) -> Self {
         Iter {
             points: points.iter(),
             verbs: verbs.iter(),
+            weights: weights.iter(),
             current: point(0.0, 0.0),
             first: point(0.0, 0.0),
         }
@@ -629,6 +677,15 @@ impl<'l> Iterator for Iter<'l> {
                     from, ctrl1, ctrl2, to: self.current
                 }))
             }
+            Some(&Verb::ConicTo) => {
+                let from = self.current;
+                let ctrl = *self.points.next().unwrap();
+                self.current = *self.points.next().unwrap();
+                let weight = *self.weights.next().unwrap();
+                Some(PathEvent::Conic(ConicSegment {
+                    from, ctrl, to: self.current, weight
+                }))
+            }
             Some(&Verb::Close) => {
                 let from = self.current;
                 self.current = self.first;
@@ -648,6 +705,7 @@ fn n_stored_points(verb: Verb) -> u32 {
         Verb::LineTo => 1,
         Verb::QuadraticTo => 2,
         Verb::CubicTo => 3,
+        Verb::ConicTo => 2,
         Verb::Close => 0,
     }
 }
@@ -663,6 +721,9 @@ fn next_cursor(cursor: &mut Cursor, verbs: &[Verb]) -> bool {
         cursor.first_verb = cursor.verb;
     }
 
+    if verbs[cursor.verb as usize] == Verb::ConicTo {
+        cursor.weight += 1;
+    }
     cursor.vertex = cursor.vertex + n_stored_points(verb);
     cursor.verb += 1;
 
@@ -691,11 +752,14 @@ fn prev_cursor(cursor: &mut Cursor, verbs: &[Verb]) -> bool {
 
     cursor.vertex = cursor.vertex - n_stored_points(verbs[cursor.verb as usize - 1]);
     cursor.verb = cursor.verb - 1;
+    if verbs[cursor.verb as usize] == Verb::ConicTo {
+        cursor.weight -= 1;
+    }
 
     true
 }
 
-fn event_at_cursor(cursor: &Cursor, points: &[Point], verbs: &[Verb]) -> PathEvent {
+fn event_at_cursor(cursor: &Cursor, points: &[Point], verbs: &[Verb], weights: &[f32]) -> PathEvent {
     let p = cursor.vertex.to_usize();
     match verbs[cursor.verb as usize] {
         Verb::MoveTo => PathEvent::MoveTo(points[p]),
@@ -714,6 +778,12 @@ fn event_at_cursor(cursor: &Cursor, points: &[Point], verbs: &[Verb]) -> PathEve
             ctrl2: points[p + 1],
             to: points[p + 2],
         }),
+        Verb::ConicTo => PathEvent::Conic(ConicSegment {
+            from: points[p - 1],
+            ctrl: points[p],
+            to: points[p + 1],
+            weight: weights[cursor.weight as usize],
+        }),
         Verb::Close => PathEvent::Close(LineSegment {
             from: points[p - 1],
             to: points[cursor.first_vertex.to_usize()],
@@ -968,3 +1038,64 @@ fn test_prev_cursor() {
 
     assert_eq!(c2.first_verb, start1.verb);
 }
+
+#[test]
+fn test_conic_path() {
+    let conic = ConicSegment {
+        from: point(1.0, 0.0),
+        ctrl: point(1.0, 1.0),
+        to: point(0.0, 1.0),
+        weight: 0.5,
+    };
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    let start = builder.cursor();
+    builder.conic_to(point(1.0, 1.0), point(0.0, 1.0), 0.5);
+    let mut c = builder.cursor();
+    builder.conic_to(point(-1.0, 1.0), point(-1.0, 0.0), 2.0);
+    builder.close();
+    let end = builder.cursor();
+    let path = builder.build();
+
+    let second = ConicSegment {
+        from: point(0.0, 1.0),
+        ctrl: point(-1.0, 1.0),
+        to: point(-1.0, 0.0),
+        weight: 2.0,
+    };
+
+    let mut it = path.iter();
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(0.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::Line(LineSegment { from: point(0.0, 0.0), to: point(1.0, 0.0) })));
+    assert_eq!(it.next(), Some(PathEvent::Conic(conic)));
+    assert_eq!(it.next(), Some(PathEvent::Conic(second)));
+    assert_eq!(it.next(), Some(PathEvent::Close(LineSegment { from: point(-1.0, 0.0), to: point(0.0, 0.0) })));
+    assert_eq!(it.next(), None);
+
+    // Cursors keep track of the conic weights.
+    assert_eq!(c.event(&path), PathEvent::Conic(conic));
+    assert!(c.next(&path));
+    assert_eq!(c.event(&path), PathEvent::Conic(second));
+    assert!(c.previous(&path));
+    assert_eq!(c.event(&path), PathEvent::Conic(conic));
+
+    let slice = path.as_slice();
+    let mut it = slice.iter_range(start..end);
+    assert_eq!(it.next(), Some(PathEvent::Line(LineSegment { from: point(0.0, 0.0), to: point(1.0, 0.0) })));
+    assert_eq!(it.next(), Some(PathEvent::Conic(conic)));
+    assert_eq!(it.next(), Some(PathEvent::Conic(second)));
+    assert_eq!(it.next(), None);
+
+    let merged = path.merge(&path);
+    assert_eq!(merged.iter().filter(|evt| *evt == PathEvent::Conic(second)).count(), 2);
+
+    let mut builder = Path::builder();
+    reverse_path(path.as_slice(), &mut builder);
+    let reversed = builder.build();
+    let mut it = reversed.iter();
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(-1.0, 0.0))));
+    assert_eq!(it.next(), Some(PathEvent::Conic(second.flip())));
+    assert_eq!(it.next(), Some(PathEvent::Conic(conic.flip())));
+}
@@ -61,6 +61,11 @@ impl PathState {
         self.current = to;
     }
 
+    pub fn conic_to(&mut self, _ctrl: Point, to: Point, _weight: f32) {
+        self.last_ctrl = LastCtrl::None;
+        self.current = to;
+    }
+
     pub fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
         let start_angle = (self.current - center).angle_from_x_axis() - x_rotation;
         let arc = Arc {
@@ -113,6 +118,10 @@ impl PathBuilder for PathState {
         self.cubic_bezier_to(ctrl1, ctrl2, to);
     }
 
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        self.conic_to(ctrl, to, weight);
+    }
+
     fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
         self.arc(center, radii, sweep_angle, x_rotation);
     }
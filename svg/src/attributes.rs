@@ -0,0 +1,220 @@
+//! Parsing of the SVG `transform` attribute and of the presentation attributes that
+//! map to lyon's tessellation parameters.
+//!
+//! These helpers don't depend on a particular XML parser: they take the name and the
+//! value of attributes as strings.
+//!
+//! The fill and stroke attributes produce lyon_tessellation's `FillOptions` and
+//! `StrokeOptions`, and are only available with the `tessellation` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate lyon_svg as svg;
+//! # use svg::attributes::*;
+//! # use svg::path::math::point;
+//! # fn main() {
+//! let transform = parse_transform("translate(10 20) scale(2)").unwrap();
+//! assert_eq!(transform.transform_point(point(1.0, 1.0)), point(12.0, 22.0));
+//! # }
+//! ```
+
+use crate::path::math::{Angle, Point, Transform2D, point, vector};
+use crate::path_utils::parse_number;
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "tessellation")]
+mod style;
+#[cfg(feature = "tessellation")]
+pub use self::style::*;
+
+/// An error produced when an attribute value can't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeError {
+    /// The name of the attribute or style property.
+    pub attribute: String,
+    /// The value that could not be parsed.
+    pub value: String,
+    /// Byte offset of the error in `value`.
+    pub offset: usize,
+}
+
+impl AttributeError {
+    pub(crate) fn new(attribute: &str, value: &str, offset: usize) -> Self {
+        AttributeError {
+            attribute: attribute.to_string(),
+            value: value.to_string(),
+            offset,
+        }
+    }
+}
+
+impl fmt::Display for AttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "invalid value for attribute '{}' at offset {}: '{}'",
+            self.attribute, self.offset, self.value
+        )
+    }
+}
+
+impl Error for AttributeError {}
+
+fn skip_whitespace(src: &str, mut position: usize) -> usize {
+    let bytes = src.as_bytes();
+    while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+        position += 1;
+    }
+    position
+}
+
+pub(crate) fn skip_separators(src: &str, mut position: usize) -> usize {
+    let bytes = src.as_bytes();
+    while position < bytes.len() && (bytes[position].is_ascii_whitespace() || bytes[position] == b',') {
+        position += 1;
+    }
+    position
+}
+
+/// Parses the value of an SVG `transform` attribute.
+///
+/// Supports the `matrix`, `translate`, `scale`, `rotate`, `skewX` and `skewY`
+/// transform functions. As in SVG, the rightmost function of the list is applied first.
+pub fn parse_transform(src: &str) -> Result<Transform2D, AttributeError> {
+    let error = |offset| AttributeError::new("transform", src, offset);
+
+    let mut transform = Transform2D::identity();
+    let mut position = skip_whitespace(src, 0);
+    while position < src.len() {
+        let name_start = position;
+        let name_len = src[name_start..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(src.len() - name_start);
+        let name = &src[name_start..name_start + name_len];
+
+        position = skip_whitespace(src, name_start + name_len);
+        if !src[position..].starts_with('(') {
+            return Err(error(position));
+        }
+        position = skip_whitespace(src, position + 1);
+
+        let mut args = [0.0; 6];
+        let mut num_args = 0;
+        while !src[position..].starts_with(')') {
+            if num_args == args.len() {
+                return Err(error(position));
+            }
+            match parse_number(src, position) {
+                Some((value, end)) => {
+                    args[num_args] = value;
+                    num_args += 1;
+                    position = skip_separators(src, end);
+                }
+                None => {
+                    return Err(error(position));
+                }
+            }
+        }
+        position += 1;
+
+        let function = match (name, &args[..num_args]) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform2D::row_major(a, b, c, d, e, f),
+            ("translate", &[x]) => Transform2D::create_translation(x, 0.0),
+            ("translate", &[x, y]) => Transform2D::create_translation(x, y),
+            ("scale", &[s]) => Transform2D::create_scale(s, s),
+            ("scale", &[x, y]) => Transform2D::create_scale(x, y),
+            ("rotate", &[angle]) => rotation(angle),
+            ("rotate", &[angle, cx, cy]) => {
+                Transform2D::create_translation(-cx, -cy)
+                    .post_transform(&rotation(angle))
+                    .post_translate(vector(cx, cy))
+            }
+            ("skewX", &[angle]) => {
+                Transform2D::row_major(1.0, 0.0, Angle::degrees(angle).radians.tan(), 1.0, 0.0, 0.0)
+            }
+            ("skewY", &[angle]) => {
+                Transform2D::row_major(1.0, Angle::degrees(angle).radians.tan(), 0.0, 1.0, 0.0, 0.0)
+            }
+            _ => {
+                return Err(error(name_start));
+            }
+        };
+
+        transform = function.post_transform(&transform);
+        position = skip_separators(src, position);
+    }
+
+    Ok(transform)
+}
+
+/// Parses the `points` attribute of the `<polyline>` and `<polygon>` elements.
+///
+/// An odd number of coordinates is an error.
+pub fn parse_points(src: &str) -> Result<Vec<Point>, AttributeError> {
+    let mut coordinates = Vec::new();
+    let mut position = skip_separators(src, 0);
+    while position < src.len() {
+        match parse_number(src, position) {
+            Some((value, end)) => {
+                coordinates.push(value);
+                position = skip_separators(src, end);
+            }
+            None => {
+                return Err(AttributeError::new("points", src, position));
+            }
+        }
+    }
+
+    if coordinates.len() % 2 == 1 {
+        return Err(AttributeError::new("points", src, src.len()));
+    }
+
+    Ok(coordinates.chunks(2).map(|xy| point(xy[0], xy[1])).collect())
+}
+
+/// The rotation matrix of SVG's `rotate` function, with the angle in degrees.
+fn rotation(angle: f32) -> Transform2D {
+    let (sin, cos) = Angle::degrees(angle).radians.sin_cos();
+    Transform2D::row_major(cos, sin, -sin, cos, 0.0, 0.0)
+}
+
+#[test]
+fn transform_attribute() {
+    use crate::path::math::point;
+
+    fn check(src: &str, from: (f32, f32), to: (f32, f32)) {
+        let transform = parse_transform(src).unwrap();
+        let p = transform.transform_point(point(from.0, from.1));
+        assert!((p - point(to.0, to.1)).length() < 1e-4, "{}: {:?}", src, p);
+    }
+
+    check("", (1.0, 2.0), (1.0, 2.0));
+    check("translate(10)", (1.0, 2.0), (11.0, 2.0));
+    check("translate(10,-5)", (1.0, 2.0), (11.0, -3.0));
+    check("scale(2)", (1.0, 2.0), (2.0, 4.0));
+    check("matrix(1 2 3 4 5 6)", (1.0, 1.0), (9.0, 12.0));
+    check("rotate(90)", (1.0, 0.0), (0.0, 1.0));
+    check("rotate(90 10 10)", (10.0, 0.0), (20.0, 10.0));
+    check("skewX(45)", (0.0, 1.0), (1.0, 1.0));
+    check("skewY(45)", (1.0, 0.0), (1.0, 1.0));
+    // The rightmost transform is applied first.
+    check("translate(10 0) scale(2)", (1.0, 1.0), (12.0, 2.0));
+    check(" scale(2),translate(10 0) ", (1.0, 1.0), (22.0, 2.0));
+
+    assert_eq!(parse_transform("scale(2) rotate(1 2)").unwrap_err().offset, 9);
+    assert_eq!(parse_transform("translate(1 x)").unwrap_err().offset, 12);
+    assert_eq!(parse_transform("scale 2").unwrap_err().offset, 6);
+}
+
+#[test]
+fn points_attribute() {
+    assert_eq!(
+        parse_points(" 0,0 10-1.5,3e1 4 "),
+        Ok(vec![point(0.0, 0.0), point(10.0, -1.5), point(30.0, 4.0)])
+    );
+    assert_eq!(parse_points(""), Ok(Vec::new()));
+    assert_eq!(parse_points("0,0 1").unwrap_err().offset, 5);
+    assert_eq!(parse_points("0,0 a").unwrap_err().offset, 4);
+}
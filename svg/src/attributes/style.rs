@@ -0,0 +1,249 @@
+//! The fill and stroke presentation attributes, expressed with lyon_tessellation's
+//! parameters.
+
+use super::{AttributeError, skip_separators};
+use crate::path_utils::parse_number;
+use crate::tessellation::{FillOptions, FillRule, StrokeOptions, LineCap, LineJoin};
+
+/// Stroke parameters described by SVG presentation attributes.
+///
+/// The stroke tessellator doesn't support dashes, so the dash pattern is provided
+/// separately for applications to apply it themselves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    pub options: StrokeOptions,
+    /// Lengths of the alternating dashes and gaps, always of even length.
+    ///
+    /// Empty if the stroke is solid.
+    pub dash_array: Vec<f32>,
+}
+
+impl StrokeStyle {
+    pub fn new(options: StrokeOptions) -> Self {
+        StrokeStyle {
+            options,
+            dash_array: Vec::new(),
+        }
+    }
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self { StrokeStyle::new(StrokeOptions::DEFAULT) }
+}
+
+/// Parses a non-negative length in user units, with an optional `px` suffix.
+fn parse_length(name: &str, value: &str, start: usize) -> Result<(f32, usize), AttributeError> {
+    match parse_number(value, start) {
+        Some((length, end)) if length >= 0.0 => {
+            let end = if value[end..].starts_with("px") { end + 2 } else { end };
+            Ok((length, end))
+        }
+        _ => Err(AttributeError::new(name, value, start)),
+    }
+}
+
+fn parse_single_length(name: &str, value: &str) -> Result<f32, AttributeError> {
+    let (length, end) = parse_length(name, value, 0)?;
+    if end != value.len() {
+        return Err(AttributeError::new(name, value, end));
+    }
+
+    Ok(length)
+}
+
+/// Applies a fill presentation attribute to the fill options.
+///
+/// Currently only `fill-rule` is supported. Returns `Ok(false)` if the attribute
+/// is not a fill attribute. The value `inherit` leaves the options unchanged.
+pub fn apply_fill_attribute(
+    options: &mut FillOptions,
+    name: &str,
+    value: &str,
+) -> Result<bool, AttributeError> {
+    let value = value.trim();
+    if name != "fill-rule" {
+        return Ok(false);
+    }
+
+    options.fill_rule = match value {
+        "nonzero" => FillRule::NonZero,
+        "evenodd" => FillRule::EvenOdd,
+        "inherit" => options.fill_rule,
+        _ => { return Err(AttributeError::new(name, value, 0)); }
+    };
+
+    Ok(true)
+}
+
+/// Applies a stroke presentation attribute to the stroke style.
+///
+/// Supports `stroke-width`, `stroke-linecap`, `stroke-linejoin`, `stroke-miterlimit`
+/// and `stroke-dasharray`. Returns `Ok(false)` if the attribute is not one of them.
+/// The value `inherit` leaves the style unchanged.
+pub fn apply_stroke_attribute(
+    style: &mut StrokeStyle,
+    name: &str,
+    value: &str,
+) -> Result<bool, AttributeError> {
+    let value = value.trim();
+    let error = |offset| AttributeError::new(name, value, offset);
+
+    let known = matches!(
+        name,
+        "stroke-width" | "stroke-linecap" | "stroke-linejoin" | "stroke-miterlimit" | "stroke-dasharray"
+    );
+    if !known {
+        return Ok(false);
+    }
+    if value == "inherit" {
+        return Ok(true);
+    }
+
+    match name {
+        "stroke-width" => {
+            style.options.line_width = parse_single_length(name, value)?;
+        }
+        "stroke-linecap" => {
+            let cap = match value {
+                "butt" => LineCap::Butt,
+                "round" => LineCap::Round,
+                "square" => LineCap::Square,
+                _ => { return Err(error(0)); }
+            };
+            style.options = style.options.with_line_cap(cap);
+        }
+        "stroke-linejoin" => {
+            style.options.line_join = match value {
+                "miter" => LineJoin::Miter,
+                "miter-clip" => LineJoin::MiterClip,
+                "round" => LineJoin::Round,
+                "bevel" => LineJoin::Bevel,
+                _ => { return Err(error(0)); }
+            };
+        }
+        "stroke-miterlimit" => {
+            match parse_number(value, 0) {
+                Some((limit, end)) if end == value.len()
+                    && limit >= StrokeOptions::MINIMUM_MITER_LIMIT => {
+                    style.options.miter_limit = limit;
+                }
+                _ => { return Err(error(0)); }
+            }
+        }
+        "stroke-dasharray" => {
+            style.dash_array.clear();
+            if value == "none" {
+                return Ok(true);
+            }
+
+            let mut position = 0;
+            while position < value.len() {
+                let (length, end) = parse_length(name, value, position)?;
+                style.dash_array.push(length);
+                let next = skip_separators(value, end);
+                if next == end && next < value.len() {
+                    return Err(error(end));
+                }
+                position = next;
+            }
+
+            // An odd number of values is repeated to yield an even number of values,
+            // and a pattern of zero length renders as a solid line.
+            if style.dash_array.len() % 2 == 1 {
+                let pattern = style.dash_array.clone();
+                style.dash_array.extend_from_slice(&pattern);
+            }
+            if style.dash_array.iter().all(|length| *length == 0.0) {
+                style.dash_array.clear();
+            }
+        }
+        _ => unreachable!(),
+    }
+
+    Ok(true)
+}
+
+/// Applies a fill or stroke presentation attribute.
+///
+/// Returns `Ok(false)` if the attribute doesn't affect the fill or stroke parameters.
+pub fn apply_presentation_attribute(
+    name: &str,
+    value: &str,
+    fill: &mut FillOptions,
+    stroke: &mut StrokeStyle,
+) -> Result<bool, AttributeError> {
+    Ok(apply_fill_attribute(fill, name, value)? || apply_stroke_attribute(stroke, name, value)?)
+}
+
+/// Applies the declarations of an SVG `style` attribute.
+///
+/// Properties that don't affect the fill or stroke parameters are ignored.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_svg as svg;
+/// # use svg::attributes::*;
+/// # use svg::tessellation::{FillOptions, FillRule, LineCap};
+/// # fn main() {
+/// let mut fill = FillOptions::default();
+/// let mut stroke = StrokeStyle::default();
+/// apply_style("fill-rule: evenodd; stroke-width: 2px; stroke-linecap: round", &mut fill, &mut stroke).unwrap();
+/// assert_eq!(fill.fill_rule, FillRule::EvenOdd);
+/// assert_eq!(stroke.options.line_width, 2.0);
+/// assert_eq!(stroke.options.start_cap, LineCap::Round);
+/// # }
+/// ```
+pub fn apply_style(
+    style: &str,
+    fill: &mut FillOptions,
+    stroke: &mut StrokeStyle,
+) -> Result<(), AttributeError> {
+    for declaration in style.split(';') {
+        if declaration.trim().is_empty() {
+            continue;
+        }
+
+        let mut parts = declaration.splitn(2, ':');
+        let name = parts.next().unwrap().trim();
+        let value = match parts.next() {
+            Some(value) => value,
+            None => { return Err(AttributeError::new("style", style, 0)); }
+        };
+
+        apply_presentation_attribute(name, value, fill, stroke)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn style_attributes() {
+    let mut fill = FillOptions::even_odd();
+    let mut stroke = StrokeStyle::default();
+
+    assert_eq!(apply_presentation_attribute("fill-rule", "nonzero", &mut fill, &mut stroke), Ok(true));
+    assert_eq!(fill.fill_rule, FillRule::NonZero);
+    assert_eq!(apply_presentation_attribute("fill", "red", &mut fill, &mut stroke), Ok(false));
+
+    apply_style(
+        "stroke-width:3;stroke-linejoin: bevel ;stroke-miterlimit:10; stroke-dasharray: 1, 2 3;",
+        &mut fill,
+        &mut stroke,
+    ).unwrap();
+    assert_eq!(stroke.options.line_width, 3.0);
+    assert_eq!(stroke.options.line_join, LineJoin::Bevel);
+    assert_eq!(stroke.options.miter_limit, 10.0);
+    assert_eq!(stroke.dash_array, vec![1.0, 2.0, 3.0, 1.0, 2.0, 3.0]);
+
+    apply_stroke_attribute(&mut stroke, "stroke-dasharray", "none").unwrap();
+    assert!(stroke.dash_array.is_empty());
+    apply_stroke_attribute(&mut stroke, "stroke-linecap", "inherit").unwrap();
+    assert_eq!(stroke.options.end_cap, LineCap::Butt);
+
+    let error = apply_stroke_attribute(&mut stroke, "stroke-width", "2em").unwrap_err();
+    assert_eq!(error.offset, 1);
+    assert!(apply_stroke_attribute(&mut stroke, "stroke-miterlimit", "0.5").is_err());
+    assert!(apply_stroke_attribute(&mut stroke, "stroke-dasharray", "1 -2").is_err());
+    assert!(apply_style("stroke-width", &mut fill, &mut stroke).is_err());
+}
@@ -11,6 +11,11 @@
 #![allow(dead_code)]
 
 pub extern crate lyon_path as path;
+#[cfg(feature = "tessellation")]
+pub extern crate lyon_tessellation as tessellation;
 pub extern crate svgparser as parser;
 
 pub mod path_utils;
+pub mod attributes;
+pub mod shapes;
+pub mod paint;
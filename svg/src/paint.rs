@@ -0,0 +1,1007 @@
+//! Fill and stroke paint: solid colors and gradients.
+//!
+//! Like the rest of this crate, the paint helpers don't depend on a particular XML
+//! parser. Gradient elements are registered in `PaintServers` from their attributes
+//! and stops, after which `fill` and `stroke` values can be resolved into a `Paint`,
+//! including `url(#id)` references. `parse_path_element` produces the path of a
+//! `<path>` element along with its fill and stroke paint.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate lyon_svg as svg;
+//! # use svg::paint::*;
+//! # fn main() {
+//! let mut servers = PaintServers::new();
+//! let stops = vec![
+//!     parse_stop(vec![("offset", "0"), ("stop-color", "red")], Color::BLACK).unwrap(),
+//!     parse_stop(vec![("offset", "100%"), ("style", "stop-color: #00f; stop-opacity: 0.5")], Color::BLACK).unwrap(),
+//! ];
+//! servers.add_linear_gradient(vec![("id", "fade"), ("x2", "0"), ("y2", "1")], stops).unwrap();
+//!
+//! match servers.resolve("url(#fade)", Color::BLACK).unwrap() {
+//!     Paint::LinearGradient(gradient) => {
+//!         assert_eq!(gradient.stops[1].color, Color::rgba(0, 0, 255, 128));
+//!     }
+//!     _ => panic!(),
+//! }
+//!
+//! let paint = servers.resolve("currentColor", Color::rgb(10, 20, 30)).unwrap();
+//! assert_eq!(paint.with_opacity(0.5), Paint::Color(Color::rgba(10, 20, 30, 128)));
+//! # }
+//! ```
+
+use crate::attributes::{AttributeError, parse_transform, skip_separators};
+use crate::path::Path;
+use crate::path::math::{Point, Size, Transform2D, point, size};
+use crate::path_utils::{build_path_lenient, parse_number};
+
+use std::collections::HashMap;
+
+/// An RGBA color with 8 bits per channel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Self = Color { r: 0, g: 0, b: 0, a: 255 };
+    pub const TRANSPARENT: Self = Color { r: 0, g: 0, b: 0, a: 0 };
+
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// Multiplies the alpha channel by an opacity between 0 and 1.
+    pub fn with_opacity(self, opacity: f32) -> Self {
+        let opacity = opacity.clamp(0.0, 1.0);
+        Color {
+            a: (self.a as f32 * opacity).round() as u8,
+            ..self
+        }
+    }
+}
+
+/// Parses a color value.
+///
+/// Supports hexadecimal colors, the `rgb()` and `rgba()` functions, named colors,
+/// `transparent` and `currentColor` which resolves to `current_color`.
+pub fn parse_color(value: &str, current_color: Color) -> Result<Color, AttributeError> {
+    let value = value.trim();
+    let error = |offset| AttributeError::new("color", value, offset);
+
+    if value.eq_ignore_ascii_case("currentColor") {
+        return Ok(current_color);
+    }
+    if value.eq_ignore_ascii_case("transparent") {
+        return Ok(Color::TRANSPARENT);
+    }
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error(1));
+        }
+        let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap();
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        return match hex.len() {
+            3 => Ok(Color::rgb(digit(0) * 17, digit(1) * 17, digit(2) * 17)),
+            4 => Ok(Color::rgba(digit(0) * 17, digit(1) * 17, digit(2) * 17, digit(3) * 17)),
+            6 => Ok(Color::rgb(byte(0), byte(2), byte(4))),
+            8 => Ok(Color::rgba(byte(0), byte(2), byte(4), byte(6))),
+            _ => Err(error(0)),
+        };
+    }
+
+    let function = if value.starts_with("rgba(") {
+        Some(5)
+    } else if value.starts_with("rgb(") {
+        Some(4)
+    } else {
+        None
+    };
+
+    if let Some(start) = function {
+        if !value.ends_with(')') {
+            return Err(error(value.len()));
+        }
+
+        let mut channels = [0.0; 4];
+        let mut num_channels = 0;
+        let mut position = skip_separators(value, start);
+        while position < value.len() - 1 {
+            if num_channels == channels.len() {
+                return Err(error(position));
+            }
+            let (number, mut end) = parse_number(value, position).ok_or_else(|| error(position))?;
+            let mut channel = if num_channels == 3 { number } else { number / 255.0 };
+            if value[end..].starts_with('%') {
+                channel = number / 100.0;
+                end += 1;
+            }
+            channels[num_channels] = channel.clamp(0.0, 1.0);
+            num_channels += 1;
+            position = skip_separators(value, end);
+        }
+
+        let to_u8 = |v: f32| (v * 255.0).round() as u8;
+        return match num_channels {
+            3 => Ok(Color::rgb(to_u8(channels[0]), to_u8(channels[1]), to_u8(channels[2]))),
+            4 => Ok(Color::rgba(
+                to_u8(channels[0]), to_u8(channels[1]), to_u8(channels[2]), to_u8(channels[3])
+            )),
+            _ => Err(error(position)),
+        };
+    }
+
+    let name = value.to_ascii_lowercase();
+    match NAMED_COLORS.binary_search_by(|(color_name, _)| color_name.cmp(&name.as_str())) {
+        Ok(idx) => {
+            let [r, g, b] = NAMED_COLORS[idx].1;
+            Ok(Color::rgb(r, g, b))
+        }
+        Err(_) => Err(error(0)),
+    }
+}
+
+/// Parses an opacity value, either a number or a percentage, clamped between 0 and 1.
+pub fn parse_opacity(value: &str) -> Result<f32, AttributeError> {
+    let value = value.trim();
+    match parse_number(value, 0) {
+        Some((opacity, end)) if end == value.len() => Ok(opacity.clamp(0.0, 1.0)),
+        Some((opacity, end)) if &value[end..] == "%" => Ok((opacity / 100.0).clamp(0.0, 1.0)),
+        _ => Err(AttributeError::new("opacity", value, 0)),
+    }
+}
+
+/// Whether the gradient's coordinates are relative to the bounding box of the
+/// painted shape or expressed in user space.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GradientUnits {
+    ObjectBoundingBox,
+    UserSpaceOnUse,
+}
+
+/// How a gradient is extended beyond its start and end.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpreadMethod {
+    Pad,
+    Reflect,
+    Repeat,
+}
+
+/// A color stop of a gradient.
+///
+/// The color's alpha channel includes the stop opacity.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearGradient {
+    pub from: Point,
+    pub to: Point,
+    pub units: GradientUnits,
+    pub transform: Transform2D,
+    pub spread_method: SpreadMethod,
+    /// At least two stops, with increasing offsets between 0 and 1.
+    pub stops: Vec<GradientStop>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadialGradient {
+    pub center: Point,
+    pub radius: f32,
+    pub focal: Point,
+    pub units: GradientUnits,
+    pub transform: Transform2D,
+    pub spread_method: SpreadMethod,
+    /// At least two stops, with increasing offsets between 0 and 1.
+    pub stops: Vec<GradientStop>,
+}
+
+/// The paint of a fill or a stroke.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    None,
+    Color(Color),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+}
+
+impl Paint {
+    /// Applies a `fill-opacity`, `stroke-opacity` or `opacity` value to the paint.
+    pub fn with_opacity(self, opacity: f32) -> Paint {
+        let apply = |stops: &mut Vec<GradientStop>| {
+            for stop in stops {
+                stop.color = stop.color.with_opacity(opacity);
+            }
+        };
+
+        match self {
+            Paint::None => Paint::None,
+            Paint::Color(color) => Paint::Color(color.with_opacity(opacity)),
+            Paint::LinearGradient(mut gradient) => {
+                apply(&mut gradient.stops);
+                Paint::LinearGradient(gradient)
+            }
+            Paint::RadialGradient(mut gradient) => {
+                apply(&mut gradient.stops);
+                Paint::RadialGradient(gradient)
+            }
+        }
+    }
+}
+
+/// Parses the attributes of a `<stop>` element, including its `style` attribute.
+pub fn parse_stop<'l, Attributes>(
+    attributes: Attributes,
+    current_color: Color,
+) -> Result<GradientStop, AttributeError>
+where
+    Attributes: IntoIterator<Item = (&'l str, &'l str)>
+{
+    let mut offset = 0.0;
+    let mut color = Color::BLACK;
+    let mut opacity = 1.0;
+
+    let mut apply = |name: &str, value: &str| -> Result<(), AttributeError> {
+        match name {
+            "offset" => { offset = parse_opacity(value)?; }
+            "stop-color" => { color = parse_color(value, current_color)?; }
+            "stop-opacity" => { opacity = parse_opacity(value)?; }
+            _ => {}
+        }
+        Ok(())
+    };
+
+    for (name, value) in attributes {
+        if name == "style" {
+            for declaration in value.split(';') {
+                let mut parts = declaration.splitn(2, ':');
+                let name = parts.next().unwrap().trim();
+                if let Some(value) = parts.next() {
+                    apply(name, value)?;
+                }
+            }
+        } else {
+            apply(name, value)?;
+        }
+    }
+
+    Ok(GradientStop { offset, color: color.with_opacity(opacity) })
+}
+
+/// The attributes of a gradient element.
+///
+/// Attributes that are not specified are `None` so that they can be inherited from
+/// the gradient referenced by `href`.
+#[derive(Clone, Debug, Default)]
+struct GradientDefinition {
+    radial: bool,
+    href: Option<String>,
+    x1: Option<Coordinate>,
+    y1: Option<Coordinate>,
+    x2: Option<Coordinate>,
+    y2: Option<Coordinate>,
+    cx: Option<Coordinate>,
+    cy: Option<Coordinate>,
+    r: Option<Coordinate>,
+    fx: Option<Coordinate>,
+    fy: Option<Coordinate>,
+    units: Option<GradientUnits>,
+    transform: Option<Transform2D>,
+    spread_method: Option<SpreadMethod>,
+    stops: Vec<GradientStop>,
+}
+
+/// A gradient coordinate or length.
+///
+/// Percentages can only be resolved once the gradient units are known, which may
+/// be inherited from another gradient.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Coordinate {
+    Number(f32),
+    Percentage(f32),
+}
+
+impl Coordinate {
+    /// Resolves the coordinate, with percentages relative to `reference`.
+    ///
+    /// With `objectBoundingBox` units, percentages are fractions of the bounding box
+    /// and the reference is ignored.
+    fn resolve(self, units: GradientUnits, reference: f32) -> f32 {
+        match (self, units) {
+            (Coordinate::Number(value), _) => value,
+            (Coordinate::Percentage(value), GradientUnits::ObjectBoundingBox) => value / 100.0,
+            (Coordinate::Percentage(value), GradientUnits::UserSpaceOnUse) => value / 100.0 * reference,
+        }
+    }
+}
+
+fn parse_coordinate(name: &str, value: &str) -> Result<Coordinate, AttributeError> {
+    let value = value.trim();
+    let (number, end) = parse_number(value, 0).ok_or_else(|| AttributeError::new(name, value, 0))?;
+    match &value[end..] {
+        "" | "px" => Ok(Coordinate::Number(number)),
+        "%" => Ok(Coordinate::Percentage(number)),
+        _ => Err(AttributeError::new(name, value, end)),
+    }
+}
+
+/// The gradients of a document, indexed by id.
+#[derive(Clone, Debug)]
+pub struct PaintServers {
+    gradients: HashMap<String, GradientDefinition>,
+    viewport: Size,
+}
+
+impl Default for PaintServers {
+    fn default() -> Self {
+        PaintServers::new()
+    }
+}
+
+impl PaintServers {
+    pub fn new() -> Self {
+        PaintServers {
+            gradients: HashMap::new(),
+            viewport: size(100.0, 100.0),
+        }
+    }
+
+    /// Sets the size of the viewport that percentages in `userSpaceOnUse` gradients
+    /// are relative to.
+    ///
+    /// Defaults to 100 by 100, so that percentages map to user units.
+    pub fn set_viewport(&mut self, viewport: Size) {
+        self.viewport = viewport;
+    }
+
+    /// Registers a `<linearGradient>` element from its attributes and stops.
+    ///
+    /// Gradients without an `id` attribute can't be referenced and are ignored.
+    pub fn add_linear_gradient<'l, Attributes>(
+        &mut self,
+        attributes: Attributes,
+        stops: Vec<GradientStop>,
+    ) -> Result<(), AttributeError>
+    where
+        Attributes: IntoIterator<Item = (&'l str, &'l str)>
+    {
+        self.add_gradient(false, attributes, stops)
+    }
+
+    /// Registers a `<radialGradient>` element from its attributes and stops.
+    ///
+    /// Gradients without an `id` attribute can't be referenced and are ignored.
+    pub fn add_radial_gradient<'l, Attributes>(
+        &mut self,
+        attributes: Attributes,
+        stops: Vec<GradientStop>,
+    ) -> Result<(), AttributeError>
+    where
+        Attributes: IntoIterator<Item = (&'l str, &'l str)>
+    {
+        self.add_gradient(true, attributes, stops)
+    }
+
+    fn add_gradient<'l, Attributes>(
+        &mut self,
+        radial: bool,
+        attributes: Attributes,
+        mut stops: Vec<GradientStop>,
+    ) -> Result<(), AttributeError>
+    where
+        Attributes: IntoIterator<Item = (&'l str, &'l str)>
+    {
+        let mut id = None;
+        let mut gradient = GradientDefinition { radial, ..GradientDefinition::default() };
+        for (name, value) in attributes {
+            let error = || AttributeError::new(name, value, 0);
+            match name {
+                "id" => { id = Some(value.to_string()); }
+                "href" | "xlink:href" => {
+                    gradient.href = Some(value.trim().trim_start_matches('#').to_string());
+                }
+                "x1" => { gradient.x1 = Some(parse_coordinate(name, value)?); }
+                "y1" => { gradient.y1 = Some(parse_coordinate(name, value)?); }
+                "x2" => { gradient.x2 = Some(parse_coordinate(name, value)?); }
+                "y2" => { gradient.y2 = Some(parse_coordinate(name, value)?); }
+                "cx" => { gradient.cx = Some(parse_coordinate(name, value)?); }
+                "cy" => { gradient.cy = Some(parse_coordinate(name, value)?); }
+                "r" => { gradient.r = Some(parse_coordinate(name, value)?); }
+                "fx" => { gradient.fx = Some(parse_coordinate(name, value)?); }
+                "fy" => { gradient.fy = Some(parse_coordinate(name, value)?); }
+                "gradientUnits" => {
+                    gradient.units = Some(match value.trim() {
+                        "objectBoundingBox" => GradientUnits::ObjectBoundingBox,
+                        "userSpaceOnUse" => GradientUnits::UserSpaceOnUse,
+                        _ => { return Err(error()); }
+                    });
+                }
+                "gradientTransform" => {
+                    gradient.transform = Some(parse_transform(value)?);
+                }
+                "spreadMethod" => {
+                    gradient.spread_method = Some(match value.trim() {
+                        "pad" => SpreadMethod::Pad,
+                        "reflect" => SpreadMethod::Reflect,
+                        "repeat" => SpreadMethod::Repeat,
+                        _ => { return Err(error()); }
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // Each stop offset is at least as large as the previous ones.
+        let mut min_offset = 0.0f32;
+        for stop in &mut stops {
+            stop.offset = stop.offset.max(min_offset).min(1.0);
+            min_offset = stop.offset;
+        }
+        gradient.stops = stops;
+
+        if let Some(id) = id {
+            self.gradients.insert(id, gradient);
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the value of a `fill` or `stroke` attribute.
+    ///
+    /// References to unknown gradients resolve to the fallback color if any, and to
+    /// `Paint::None` otherwise. As in SVG, gradients without stops resolve to
+    /// `Paint::None` even if there is a fallback, and gradients with a single stop
+    /// resolve to the color of the stop.
+    pub fn resolve(&self, value: &str, current_color: Color) -> Result<Paint, AttributeError> {
+        let value = value.trim();
+        if value == "none" {
+            return Ok(Paint::None);
+        }
+
+        if value.starts_with("url(") {
+            let end = value.find(')').ok_or_else(|| AttributeError::new("paint", value, value.len()))?;
+            let id = value[4..end].trim().trim_matches(|c| c == '"' || c == '\'');
+            if !id.starts_with('#') {
+                return Err(AttributeError::new("paint", value, 4));
+            }
+
+            if let Some(paint) = self.resolve_gradient(&id[1..]) {
+                return Ok(paint);
+            }
+
+            let fallback = value[end + 1..].trim();
+            if fallback.is_empty() {
+                return Ok(Paint::None);
+            }
+            return self.resolve(fallback, current_color);
+        }
+
+        parse_color(value, current_color).map(Paint::Color)
+    }
+
+    /// Returns `None` if there is no gradient with this id.
+    fn resolve_gradient(&self, id: &str) -> Option<Paint> {
+        // The chain of gradients referenced through href, stopping at cycles.
+        let mut chain: Vec<(&str, &GradientDefinition)> = Vec::new();
+        let mut next = Some(id);
+        while let Some(id) = next {
+            if chain.iter().any(|(visited, _)| *visited == id) {
+                break;
+            }
+            let gradient = match self.gradients.get(id) {
+                Some(gradient) => gradient,
+                None => { break; }
+            };
+            chain.push((id, gradient));
+            next = gradient.href.as_deref();
+        }
+
+        let radial = chain.first()?.1.radial;
+        let units = chain.iter().filter_map(|(_, g)| g.units).next()
+            .unwrap_or(GradientUnits::ObjectBoundingBox);
+        // Percentages of user space lengths are relative to the normalized diagonal
+        // of the viewport.
+        let (width, height) = (self.viewport.width, self.viewport.height);
+        let diagonal = ((width * width + height * height) / 2.0).sqrt();
+        let inherit = |attribute: &dyn Fn(&GradientDefinition) -> Option<Coordinate>,
+                       default: Coordinate,
+                       reference: f32| {
+            chain.iter()
+                .filter(|(_, gradient)| gradient.radial == radial)
+                .filter_map(|(_, gradient)| attribute(gradient))
+                .next()
+                .unwrap_or(default)
+                .resolve(units, reference)
+        };
+
+        let transform = chain.iter().filter_map(|(_, g)| g.transform).next()
+            .unwrap_or_else(Transform2D::identity);
+        let spread_method = chain.iter().filter_map(|(_, g)| g.spread_method).next()
+            .unwrap_or(SpreadMethod::Pad);
+        let stops = match chain.iter().map(|(_, g)| &g.stops).find(|stops| !stops.is_empty()) {
+            Some(stops) => stops.clone(),
+            None => { return Some(Paint::None); }
+        };
+
+        if stops.len() == 1 {
+            return Some(Paint::Color(stops[0].color));
+        }
+
+        Some(if radial {
+            let half = Coordinate::Percentage(50.0);
+            let cx = inherit(&|g| g.cx, half, width);
+            let cy = inherit(&|g| g.cy, half, height);
+            Paint::RadialGradient(RadialGradient {
+                center: point(cx, cy),
+                radius: inherit(&|g| g.r, half, diagonal),
+                focal: point(
+                    inherit(&|g| g.fx, Coordinate::Number(cx), width),
+                    inherit(&|g| g.fy, Coordinate::Number(cy), height),
+                ),
+                units,
+                transform,
+                spread_method,
+                stops,
+            })
+        } else {
+            Paint::LinearGradient(LinearGradient {
+                from: point(
+                    inherit(&|g| g.x1, Coordinate::Percentage(0.0), width),
+                    inherit(&|g| g.y1, Coordinate::Percentage(0.0), height),
+                ),
+                to: point(
+                    inherit(&|g| g.x2, Coordinate::Percentage(100.0), width),
+                    inherit(&|g| g.y2, Coordinate::Percentage(0.0), height),
+                ),
+                units,
+                transform,
+                spread_method,
+                stops,
+            })
+        })
+    }
+}
+
+/// Resolves the fill and stroke paint of an element from its attributes, including
+/// its `style` attribute.
+///
+/// `fill-opacity`, `stroke-opacity` and `opacity` are applied to the paint, and the
+/// element's `color` attribute overrides `current_color`. As in SVG, the fill
+/// defaults to black, the stroke to none, and declarations of the `style` attribute
+/// take precedence over presentation attributes.
+pub fn parse_paint<'l, Attributes>(
+    attributes: Attributes,
+    servers: &PaintServers,
+    current_color: Color,
+) -> Result<(Paint, Paint), AttributeError>
+where
+    Attributes: IntoIterator<Item = (&'l str, &'l str)>
+{
+    let mut fill = None;
+    let mut stroke = None;
+    let mut color = current_color;
+    let mut fill_opacity = 1.0;
+    let mut stroke_opacity = 1.0;
+    let mut opacity = 1.0;
+
+    let mut apply = |name: &str, value: &'l str| -> Result<(), AttributeError> {
+        if value.trim() == "inherit" {
+            return Ok(());
+        }
+        match name {
+            "fill" => { fill = Some(value); }
+            "stroke" => { stroke = Some(value); }
+            "color" => { color = parse_color(value, current_color)?; }
+            "fill-opacity" => { fill_opacity = parse_opacity(value)?; }
+            "stroke-opacity" => { stroke_opacity = parse_opacity(value)?; }
+            "opacity" => { opacity = parse_opacity(value)?; }
+            _ => {}
+        }
+        Ok(())
+    };
+
+    let mut style = None;
+    for (name, value) in attributes {
+        if name == "style" {
+            style = Some(value);
+        } else {
+            apply(name, value)?;
+        }
+    }
+    for declaration in style.iter().flat_map(|style| style.split(';')) {
+        let mut parts = declaration.splitn(2, ':');
+        let name = parts.next().unwrap().trim();
+        if let Some(value) = parts.next() {
+            apply(name, value)?;
+        }
+    }
+
+    // The color is only known once all attributes are parsed.
+    let fill = match fill {
+        Some(value) => servers.resolve(value, color)?,
+        None => Paint::Color(Color::BLACK),
+    };
+    let stroke = match stroke {
+        Some(value) => servers.resolve(value, color)?,
+        None => Paint::None,
+    };
+
+    Ok((fill.with_opacity(fill_opacity * opacity), stroke.with_opacity(stroke_opacity * opacity)))
+}
+
+/// Builds the path of a `<path>` element along with its fill and stroke paint.
+///
+/// The paint is resolved as in `parse_paint`. As browsers do, the path is made of the
+/// commands of the `d` attribute that precede the first error, if any, and is empty
+/// if there is no `d` attribute.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_svg as svg;
+/// # use svg::paint::*;
+/// # fn main() {
+/// let mut servers = PaintServers::new();
+/// let stops = vec![
+///     parse_stop(vec![("offset", "0"), ("stop-color", "red")], Color::BLACK).unwrap(),
+///     parse_stop(vec![("offset", "1"), ("stop-color", "blue")], Color::BLACK).unwrap(),
+/// ];
+/// servers.add_radial_gradient(vec![("id", "glow")], stops).unwrap();
+///
+/// let (path, fill, stroke) = parse_path_element(
+///     vec![
+///         ("d", "M 0 0 L 10 0 L 10 10 Z"),
+///         ("fill", "url(#glow)"),
+///         ("style", "stroke: currentColor; stroke-opacity: 0.5"),
+///     ],
+///     &servers,
+///     Color::rgb(0, 128, 0),
+/// ).unwrap();
+///
+/// assert_eq!(path.iter().count(), 4);
+/// match fill {
+///     Paint::RadialGradient(gradient) => { assert_eq!(gradient.stops.len(), 2); }
+///     _ => panic!(),
+/// }
+/// assert_eq!(stroke, Paint::Color(Color::rgba(0, 128, 0, 128)));
+/// # }
+/// ```
+pub fn parse_path_element<'l, Attributes>(
+    attributes: Attributes,
+    servers: &PaintServers,
+    current_color: Color,
+) -> Result<(Path, Paint, Paint), AttributeError>
+where
+    Attributes: IntoIterator<Item = (&'l str, &'l str)>
+{
+    let attributes: Vec<(&str, &str)> = attributes.into_iter().collect();
+    let (fill, stroke) = parse_paint(attributes.iter().cloned(), servers, current_color)?;
+    let d = attributes.iter().find(|(name, _)| *name == "d").map_or("", |(_, value)| *value);
+    let (path, _) = build_path_lenient(Path::builder().with_svg(), d);
+
+    Ok((path, fill, stroke))
+}
+
+const NAMED_COLORS: &[(&str, [u8; 3])] = &[
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aqua", [0, 255, 255]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("fuchsia", [255, 0, 255]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
+
+#[test]
+fn colors() {
+    let current = Color::rgb(1, 2, 3);
+    assert_eq!(parse_color("#f80", current), Ok(Color::rgb(255, 136, 0)));
+    assert_eq!(parse_color(" #FF8000 ", current), Ok(Color::rgb(255, 128, 0)));
+    assert_eq!(parse_color("#ff800080", current), Ok(Color::rgba(255, 128, 0, 128)));
+    assert_eq!(parse_color("rgb(255, 0, 10)", current), Ok(Color::rgb(255, 0, 10)));
+    assert_eq!(parse_color("rgb(100%,50%,0%)", current), Ok(Color::rgb(255, 128, 0)));
+    assert_eq!(parse_color("rgba(0 0 255 / 0.5)", current).unwrap_err().offset, 13);
+    assert_eq!(parse_color("rgba(0, 0, 255, 0.5)", current), Ok(Color::rgba(0, 0, 255, 128)));
+    assert_eq!(parse_color("CornflowerBlue", current), Ok(Color::rgb(100, 149, 237)));
+    assert_eq!(parse_color("currentColor", current), Ok(current));
+    assert_eq!(parse_color("transparent", current), Ok(Color::TRANSPARENT));
+    assert!(parse_color("#ff80", current).is_ok());
+    assert!(parse_color("#ff8", current).is_ok());
+    assert!(parse_color("#ff800", current).is_err());
+    assert!(parse_color("#gg0000", current).is_err());
+    assert!(parse_color("notacolor", current).is_err());
+
+    assert_eq!(parse_opacity("0.25"), Ok(0.25));
+    assert_eq!(parse_opacity("50%"), Ok(0.5));
+    assert_eq!(parse_opacity("2"), Ok(1.0));
+    assert!(parse_opacity("a").is_err());
+}
+
+#[test]
+fn gradients() {
+    let black = Color::BLACK;
+    let stop = |offset: &str, color: &str| {
+        parse_stop(vec![("offset", offset), ("stop-color", color)], black).unwrap()
+    };
+
+    let mut servers = PaintServers::new();
+    servers.set_viewport(size(200.0, 50.0));
+    servers.add_linear_gradient(
+        vec![
+            ("id", "base"),
+            ("x1", "10%"),
+            ("spreadMethod", "reflect"),
+            ("gradientUnits", "userSpaceOnUse"),
+        ],
+        vec![stop("0", "red"), stop("0.8", "lime"), stop("0.5", "blue")],
+    ).unwrap();
+    servers.add_linear_gradient(
+        vec![("id", "derived"), ("xlink:href", "#base"), ("x2", "20"), ("gradientTransform", "scale(2)")],
+        Vec::new(),
+    ).unwrap();
+    servers.add_radial_gradient(
+        vec![("id", "radial"), ("href", "#derived"), ("fx", "0.25")],
+        Vec::new(),
+    ).unwrap();
+    servers.add_radial_gradient(vec![("id", "single")], vec![stop("0", "blue")]).unwrap();
+    servers.add_radial_gradient(vec![("id", "empty")], Vec::new()).unwrap();
+    servers.add_linear_gradient(
+        vec![("id", "bbox"), ("href", "#base"), ("gradientUnits", "objectBoundingBox"), ("y2", "50%")],
+        Vec::new(),
+    ).unwrap();
+    servers.add_linear_gradient(vec![("id", "cycle1"), ("href", "#cycle2")], Vec::new()).unwrap();
+    servers.add_linear_gradient(vec![("id", "cycle2"), ("href", "#cycle1")], Vec::new()).unwrap();
+
+    let derived = match servers.resolve("url(#derived)", black).unwrap() {
+        Paint::LinearGradient(gradient) => gradient,
+        paint => panic!("{:?}", paint),
+    };
+    // Percentages of user space coordinates are relative to the viewport.
+    assert_eq!(derived.from, point(20.0, 0.0));
+    assert_eq!(derived.to, point(20.0, 0.0));
+    assert_eq!(derived.units, GradientUnits::UserSpaceOnUse);
+    assert_eq!(derived.spread_method, SpreadMethod::Reflect);
+    assert_eq!(derived.transform, Transform2D::create_scale(2.0, 2.0));
+    // Offsets are made monotonic.
+    let offsets: Vec<f32> = derived.stops.iter().map(|s| s.offset).collect();
+    assert_eq!(offsets, vec![0.0, 0.8, 0.8]);
+
+    // Geometry is only inherited from gradients of the same kind.
+    let radial = match servers.resolve("url('#radial')", black).unwrap() {
+        Paint::RadialGradient(gradient) => gradient,
+        paint => panic!("{:?}", paint),
+    };
+    assert_eq!(radial.center, point(100.0, 25.0));
+    assert_eq!(radial.radius, 0.5 * ((200.0f32 * 200.0 + 50.0 * 50.0) / 2.0).sqrt());
+    assert_eq!(radial.focal, point(0.25, 25.0));
+    assert_eq!(radial.stops.len(), 3);
+
+    // Inherited percentages are resolved with the units of the referencing gradient.
+    let bbox = match servers.resolve("url(#bbox)", black).unwrap() {
+        Paint::LinearGradient(gradient) => gradient,
+        paint => panic!("{:?}", paint),
+    };
+    assert_eq!(bbox.from, point(0.1, 0.0));
+    assert_eq!(bbox.to, point(1.0, 0.5));
+
+    assert_eq!(servers.resolve("url(#single)", black), Ok(Paint::Color(Color::rgb(0, 0, 255))));
+    assert_eq!(servers.resolve("url(#empty)", black), Ok(Paint::None));
+    assert_eq!(servers.resolve("url(#empty) green", black), Ok(Paint::None));
+    assert_eq!(servers.resolve("url(#cycle1)", black), Ok(Paint::None));
+    assert_eq!(servers.resolve("url(#missing)", black), Ok(Paint::None));
+    assert_eq!(servers.resolve("url(#missing) green", black), Ok(Paint::Color(Color::rgb(0, 128, 0))));
+    assert_eq!(servers.resolve("none", black), Ok(Paint::None));
+    assert!(servers.resolve("url(missing)", black).is_err());
+    assert!(servers.add_linear_gradient(vec![("id", "bad"), ("spreadMethod", "mirror")], Vec::new()).is_err());
+
+    let faded = servers.resolve("url(#derived)", black).unwrap().with_opacity(0.5);
+    match faded {
+        Paint::LinearGradient(gradient) => { assert_eq!(gradient.stops[0].color, Color::rgba(255, 0, 0, 128)); }
+        paint => panic!("{:?}", paint),
+    }
+}
+
+#[test]
+fn painted_paths() {
+    let black = Color::BLACK;
+    let mut servers = PaintServers::new();
+    servers.add_linear_gradient(
+        vec![("id", "fade")],
+        vec![
+            parse_stop(vec![("offset", "0"), ("stop-color", "red")], black).unwrap(),
+            parse_stop(vec![("offset", "1"), ("stop-color", "blue")], black).unwrap(),
+        ],
+    ).unwrap();
+
+    // SVG's defaults.
+    let (path, fill, stroke) = parse_path_element(vec![("d", "M 0 0 L 1 0 L 1")], &servers, black).unwrap();
+    assert_eq!(path.iter().count(), 2);
+    assert_eq!(fill, Paint::Color(black));
+    assert_eq!(stroke, Paint::None);
+
+    let (path, fill, stroke) = parse_path_element(Vec::new(), &servers, black).unwrap();
+    assert_eq!(path.iter().count(), 0);
+    assert_eq!(fill, Paint::Color(black));
+    assert_eq!(stroke, Paint::None);
+
+    // The style attribute takes precedence regardless of the order of the attributes,
+    // and currentColor uses the element's color.
+    let (fill, stroke) = parse_paint(
+        vec![
+            ("style", "fill: url(#fade); fill-opacity: 50%"),
+            ("fill", "green"),
+            ("fill-opacity", "0.1"),
+            ("stroke", "currentColor"),
+            ("color", "#00f"),
+            ("opacity", "0.5"),
+        ],
+        &servers,
+        black,
+    ).unwrap();
+    match fill {
+        Paint::LinearGradient(gradient) => {
+            assert_eq!(gradient.stops[0].color, Color::rgba(255, 0, 0, 64));
+            assert_eq!(gradient.stops[1].color, Color::rgba(0, 0, 255, 64));
+        }
+        paint => panic!("{:?}", paint),
+    }
+    assert_eq!(stroke, Paint::Color(Color::rgba(0, 0, 255, 128)));
+
+    let inherited = parse_paint(vec![("fill", "inherit"), ("stroke", "none")], &servers, black).unwrap();
+    assert_eq!(inherited, (Paint::Color(black), Paint::None));
+    assert!(parse_paint(vec![("fill-opacity", "half")], &servers, black).is_err());
+    assert!(parse_path_element(vec![("d", "M 0 0"), ("stroke", "#12")], &servers, black).is_err());
+}
@@ -1,16 +1,67 @@
-use crate::parser::xmlparser::{StrSpan, FromSpan};
-use crate::parser::path::{Tokenizer, Token};
+use crate::parser::xmlparser::StrSpan;
 
-use crate::path::geom::Arc;
+use crate::path::geom::{Arc, ConicSegment};
 use crate::path::math::{Vector, vector, Point, point, Angle};
 use crate::path::{SvgEvent, ArcFlags};
 use crate::path::builder::*;
+use crate::path::iterator::DEFAULT_CONIC_TOLERANCE;
 
+use std::error::Error;
 use std::f32::consts::PI;
+use std::fmt;
 use std::mem;
 
+/// The kind of problem encountered while parsing an SVG path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A character that can't start a command or a number was found, or the path
+    /// doesn't start with a move-to command.
+    UnexpectedToken,
+    /// A command is missing some of its coordinates.
+    MissingCoordinate,
+    /// The large-arc or sweep flag of an arc command is neither `0` nor `1`.
+    InvalidArcFlag,
+}
+
+/// An error produced when parsing an SVG path.
 #[derive(Clone, Debug, PartialEq)]
-pub struct ParseError;
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Byte offset of the offending token in the source string.
+    pub offset: usize,
+    /// The command that was being parsed, if any.
+    pub command: Option<char>,
+    /// The offending token, or an empty string if the end of the input was reached.
+    pub token: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::UnexpectedToken => {
+                write!(f, "unexpected token")?;
+            }
+            ParseErrorKind::MissingCoordinate => {
+                write!(f, "missing coordinate")?;
+            }
+            ParseErrorKind::InvalidArcFlag => {
+                write!(f, "invalid arc flag")?;
+            }
+        }
+
+        if let Some(command) = self.command {
+            write!(f, " in command '{}'", command)?;
+        }
+
+        if self.token.is_empty() {
+            write!(f, " at the end of the input (offset {})", self.offset)
+        } else {
+            write!(f, " at offset {}: '{}'", self.offset, self.token)
+        }
+    }
+}
+
+impl Error for ParseError {}
 
 /// Builds path object using an SvgBuilder and a list of commands.
 /// Once the path is built you can tessellate it.
@@ -46,111 +97,242 @@ where
     Ok(builder.build())
 }
 
+/// Builds a path from the commands that precede the first error, if any.
+///
+/// This mirrors the way browsers render invalid path data: everything up to the last
+/// complete command is kept. The error, if any, is returned alongside the path.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_svg as svg;
+/// # extern crate lyon_path;
+/// # use lyon_path::Path;
+/// # use svg::path_utils::{build_path_lenient, ParseErrorKind};
+/// # fn main() {
+/// let (path, error) = build_path_lenient(Path::builder().with_svg(), "M 0 0 L 10 0 L 10");
+/// assert_eq!(path.iter().count(), 2);
+/// assert_eq!(error.unwrap().kind, ParseErrorKind::MissingCoordinate);
+/// # }
+/// ```
+pub fn build_path_lenient<Builder>(mut builder: Builder, src: &str) -> (Builder::PathType, Option<ParseError>)
+where
+    Builder: SvgBuilder + Build
+{
+    for item in PathTokenizer::new(src) {
+        match item {
+            Ok(event) => { builder.svg_event(event); }
+            Err(err) => { return (builder.build(), Some(err)); }
+        }
+    }
+
+    (builder.build(), None)
+}
 
+/// An iterator over the `SvgEvent`s described by an SVG path string.
+///
+/// Each command is only produced once all of its parameters have been parsed. After an
+/// error the iterator doesn't produce anything.
 pub struct PathTokenizer<'l> {
-    tokenizer: Tokenizer<'l>
+    src: &'l str,
+    position: usize,
+    command: Option<u8>,
+    done: bool,
 }
 
 impl<'l> PathTokenizer<'l> {
     pub fn new(text: &str) -> PathTokenizer {
         PathTokenizer {
-            tokenizer: Tokenizer::from_str(text)
+            src: text,
+            position: 0,
+            command: None,
+            done: false,
         }
     }
 
     pub fn from_span(span: StrSpan) -> PathTokenizer {
-        PathTokenizer {
-            tokenizer: Tokenizer::from_span(span)
+        PathTokenizer::new(span.to_str())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.position).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') | Some(b'\x0C') = self.peek() {
+            self.position += 1;
         }
     }
-}
 
-impl<'l> Iterator for PathTokenizer<'l> {
-    type Item = Result<SvgEvent, ParseError>;
+    fn skip_separator(&mut self) {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.position += 1;
+            self.skip_whitespace();
+        }
+    }
 
-    fn next(&mut self) -> Option<Result<SvgEvent, ParseError>> {
-        self.tokenizer.next().map(|token| { Ok(svg_event(&token)) })
+    fn error(&self, kind: ParseErrorKind, offset: usize) -> ParseError {
+        let token = self.src[offset..].chars().next().map(|c| c.to_string());
+        ParseError {
+            kind,
+            offset,
+            command: self.command.map(|c| c as char),
+            token: token.unwrap_or_default(),
+        }
     }
-}
 
-fn svg_event(token: &Token) -> SvgEvent {
-    fn vec2(x: f64, y: f64) -> Vector { vector(x as f32, y as f32) }
-    fn point2(x: f64, y: f64) -> Point { point(x as f32, y as f32) }
-    match *token {
-        Token::MoveTo { abs, x, y } => {
-            if abs {
-                SvgEvent::MoveTo(point2(x, y))
-            } else {
-                SvgEvent::RelativeMoveTo(vec2(x, y))
-            }
-        },
-        Token::LineTo { abs, x, y } => {
-            if abs {
-                SvgEvent::LineTo(point2(x, y))
-            } else {
-                SvgEvent::RelativeLineTo(vec2(x, y))
-            }
-        },
-        Token::HorizontalLineTo { abs, x } => {
-            if abs {
-                SvgEvent::HorizontalLineTo(x as f32)
-            } else {
-                SvgEvent::RelativeHorizontalLineTo(x as f32)
-            }
-        },
-        Token::VerticalLineTo { abs, y } => {
-            if abs {
-                SvgEvent::VerticalLineTo(y as f32)
-            } else {
-                SvgEvent::RelativeVerticalLineTo(y as f32)
+    fn number(&mut self) -> Result<f32, ParseError> {
+        self.skip_separator();
+        match parse_number(self.src, self.position) {
+            Some((value, end)) => {
+                self.position = end;
+                Ok(value)
             }
-        },
-        Token::CurveTo { abs, x1, y1, x2, y2, x, y } => {
-            if abs {
-                SvgEvent::CubicTo(point2(x1, y1), point2(x2, y2), point2(x, y))
-            } else {
-                SvgEvent::RelativeCubicTo(vec2(x1, y1), vec2(x2, y2), vec2(x, y))
-            }
-        },
-        Token::SmoothCurveTo { abs, x2, y2, x, y } => {
-            if abs {
-                SvgEvent::SmoothCubicTo(point2(x2, y2), point2(x, y))
-            } else {
-                SvgEvent::SmoothRelativeCubicTo(vec2(x2, y2), vec2(x, y))
+            None => Err(self.error(ParseErrorKind::MissingCoordinate, self.position)),
+        }
+    }
+
+    fn flag(&mut self) -> Result<bool, ParseError> {
+        self.skip_separator();
+        match self.peek() {
+            Some(b'0') => { self.position += 1; Ok(false) }
+            Some(b'1') => { self.position += 1; Ok(true) }
+            None => Err(self.error(ParseErrorKind::MissingCoordinate, self.position)),
+            Some(_) => Err(self.error(ParseErrorKind::InvalidArcFlag, self.position)),
+        }
+    }
+
+    fn point(&mut self) -> Result<Point, ParseError> {
+        Ok(point(self.number()?, self.number()?))
+    }
+
+    fn vector(&mut self) -> Result<Vector, ParseError> {
+        Ok(vector(self.number()?, self.number()?))
+    }
+
+    fn next_event(&mut self) -> Option<Result<SvgEvent, ParseError>> {
+        self.skip_whitespace();
+        let start = self.position;
+        let c = self.peek()?;
+
+        let command = if c.is_ascii_alphabetic() {
+            if self.command.is_none() && c != b'M' && c != b'm' {
+                return Some(Err(self.error(ParseErrorKind::UnexpectedToken, start)));
             }
-        },
-        Token::Quadratic { abs, x1, y1, x, y } => {
-            if abs {
-                SvgEvent::QuadraticTo(point2(x1, y1), point2(x, y))
-            } else {
-                SvgEvent::RelativeQuadraticTo(vec2(x1, y1), vec2(x, y))
+            self.position += 1;
+            self.command = Some(c);
+            c
+        } else {
+            // Implicit repetition of the previous command. Coordinates following a
+            // move-to command are implicit line-to commands.
+            match self.command {
+                Some(b'Z') | Some(b'z') | None => {
+                    return Some(Err(self.error(ParseErrorKind::UnexpectedToken, start)));
+                }
+                Some(b'M') => { self.command = Some(b'L'); b'L' }
+                Some(b'm') => { self.command = Some(b'l'); b'l' }
+                Some(command) => command,
             }
-        },
-        Token::SmoothQuadratic { abs, x, y } => {
-            if abs {
-                SvgEvent::SmoothQuadraticTo(point2(x, y))
-            } else {
-                SvgEvent::SmoothRelativeQuadraticTo(vec2(x, y))
+        };
+
+        Some(self.parse_command(command, start))
+    }
+
+    fn parse_command(&mut self, command: u8, start: usize) -> Result<SvgEvent, ParseError> {
+        Ok(match command {
+            b'M' => SvgEvent::MoveTo(self.point()?),
+            b'm' => SvgEvent::RelativeMoveTo(self.vector()?),
+            b'L' => SvgEvent::LineTo(self.point()?),
+            b'l' => SvgEvent::RelativeLineTo(self.vector()?),
+            b'H' => SvgEvent::HorizontalLineTo(self.number()?),
+            b'h' => SvgEvent::RelativeHorizontalLineTo(self.number()?),
+            b'V' => SvgEvent::VerticalLineTo(self.number()?),
+            b'v' => SvgEvent::RelativeVerticalLineTo(self.number()?),
+            b'C' => SvgEvent::CubicTo(self.point()?, self.point()?, self.point()?),
+            b'c' => SvgEvent::RelativeCubicTo(self.vector()?, self.vector()?, self.vector()?),
+            b'S' => SvgEvent::SmoothCubicTo(self.point()?, self.point()?),
+            b's' => SvgEvent::SmoothRelativeCubicTo(self.vector()?, self.vector()?),
+            b'Q' => SvgEvent::QuadraticTo(self.point()?, self.point()?),
+            b'q' => SvgEvent::RelativeQuadraticTo(self.vector()?, self.vector()?),
+            b'T' => SvgEvent::SmoothQuadraticTo(self.point()?),
+            b't' => SvgEvent::SmoothRelativeQuadraticTo(self.vector()?),
+            b'A' | b'a' => {
+                let radii = self.vector()?;
+                let x_rotation = Angle::degrees(self.number()?);
+                let flags = ArcFlags {
+                    large_arc: self.flag()?,
+                    sweep: self.flag()?,
+                };
+                if command == b'A' {
+                    SvgEvent::ArcTo(radii, x_rotation, flags, self.point()?)
+                } else {
+                    SvgEvent::RelativeArcTo(radii, x_rotation, flags, self.vector()?)
+                }
             }
-        },
-        Token::EllipticalArc { abs, rx, ry, x_axis_rotation, large_arc, sweep, x, y } => {
-            if abs {
-                SvgEvent::ArcTo(
-                    vec2(rx, ry),
-                    Angle::degrees(x_axis_rotation as f32),
-                    ArcFlags { large_arc: large_arc, sweep: sweep },
-                    point2(x, y),
-                )
-            } else {
-                SvgEvent::RelativeArcTo(
-                    vec2(rx, ry),
-                    Angle::degrees(x_axis_rotation as f32),
-                    ArcFlags { large_arc: large_arc, sweep: sweep },
-                    vec2(x, y),
-                )
+            b'Z' | b'z' => SvgEvent::Close,
+            _ => {
+                self.command = None;
+                return Err(self.error(ParseErrorKind::UnexpectedToken, start));
             }
-        },
-        Token::ClosePath { .. } => { SvgEvent::Close },
+        })
+    }
+}
+
+/// Parses a number following the SVG grammar, starting at byte offset `start`.
+///
+/// Returns the value and the offset of the end of the number.
+pub(crate) fn parse_number(src: &str, start: usize) -> Option<(f32, usize)> {
+    let bytes = src.as_bytes();
+    let mut i = start;
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while *i < bytes.len() && bytes[*i].is_ascii_digit() {
+            *i += 1;
+        }
+        *i - start
+    };
+
+    if i < bytes.len() && (bytes[i] == b'-' || bytes[i] == b'+') {
+        i += 1;
+    }
+    let mut mantissa_digits = digits(&mut i);
+    if i < bytes.len() && bytes[i] == b'.' {
+        i += 1;
+        mantissa_digits += digits(&mut i);
+    }
+    if mantissa_digits == 0 {
+        return None;
+    }
+
+    // Only consume the exponent if it is well formed.
+    if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+        let mut j = i + 1;
+        if j < bytes.len() && (bytes[j] == b'-' || bytes[j] == b'+') {
+            j += 1;
+        }
+        if digits(&mut j) > 0 {
+            i = j;
+        }
+    }
+
+    src[start..i].parse::<f64>().ok().map(|value| (value as f32, i))
+}
+
+impl<'l> Iterator for PathTokenizer<'l> {
+    type Item = Result<SvgEvent, ParseError>;
+
+    fn next(&mut self) -> Option<Result<SvgEvent, ParseError>> {
+        if self.done {
+            return None;
+        }
+
+        let item = self.next_event();
+        if let Some(Err(..)) | None = item {
+            self.done = true;
+        }
+
+        item
     }
 }
 
@@ -159,10 +341,12 @@ fn svg_event(token: &Token) -> SvgEvent {
 /// using the SVG syntax.
 ///
 /// No effort is put into making the serializer performant or make the
-/// output compact. Intended primarily for debugging purposes.
+/// output compact. Intended primarily for debugging purposes, see
+/// `CompactPathSerializer` for a more compact output.
 pub struct PathSerializer {
     path: String,
     current: Point,
+    conic_tolerance: f32,
 }
 
 impl PathSerializer {
@@ -170,8 +354,16 @@ impl PathSerializer {
         PathSerializer {
             path: String::new(),
             current: point(0.0, 0.0),
+            conic_tolerance: DEFAULT_CONIC_TOLERANCE,
         }
     }
+
+    /// Sets the maximum distance between conics and the quadratic bézier curves
+    /// approximating them in the output.
+    pub fn with_conic_tolerance(mut self, tolerance: f32) -> Self {
+        self.conic_tolerance = tolerance;
+        self
+    }
 }
 
 impl Build for PathSerializer {
@@ -214,6 +406,15 @@ impl PathBuilder for PathSerializer {
         self.path += &format!("C {} {} {} {} {} {}", ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y);
     }
 
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        // SVG paths can't represent conics.
+        let conic = ConicSegment { from: self.current, ctrl, to, weight };
+        conic.for_each_quadratic_bezier(self.conic_tolerance, &mut |curve| {
+            self.path += &format!("Q {} {} {} {}", curve.ctrl.x, curve.ctrl.y, curve.to.x, curve.to.y);
+        });
+        self.current = to;
+    }
+
     fn arc(
         &mut self,
         center: Point,
@@ -322,3 +523,581 @@ impl PolygonBuilder for PathSerializer {
         build_polygon(self, points);
     }
 }
+
+/// A `PathBuilder` that builds a compact `String` representation of the path
+/// using the SVG syntax.
+///
+/// Coordinates are rounded to a configurable number of decimal places, and for each
+/// command the shorter of the absolute and relative forms is picked. Repeated commands
+/// are written implicitly, and the `H`, `V`, `S` and `T` shortcuts are used whenever
+/// they describe the same segment.
+///
+/// Positions are tracked after rounding, so relative commands don't accumulate
+/// rounding errors.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_svg as svg;
+/// # extern crate lyon_path;
+/// # use lyon_path::math::point;
+/// # use lyon_path::builder::*;
+/// # use svg::path_utils::CompactPathSerializer;
+/// # fn main() {
+/// let mut serializer = CompactPathSerializer::with_precision(1);
+/// serializer.move_to(point(100.0, 100.0));
+/// serializer.line_to(point(110.0, 100.0));
+/// serializer.line_to(point(110.0, 110.04));
+/// serializer.line_to(point(105.0, 115.0));
+/// serializer.close();
+///
+/// assert_eq!(serializer.build(), "M100 100h10v10l-5 5Z");
+/// # }
+/// ```
+pub struct CompactPathSerializer {
+    path: String,
+    scale: f64,
+    precision: u32,
+    current: (i64, i64),
+    first: (i64, i64),
+    last_ctrl: LastCtrl,
+    last_command: Option<u8>,
+    // Whether the path ends with a number, and if so whether it contains a decimal point.
+    last_number: Option<bool>,
+    conic_tolerance: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum LastCtrl {
+    Cubic((i64, i64)),
+    Quad((i64, i64)),
+    None,
+}
+
+impl CompactPathSerializer {
+    /// Creates a serializer that writes coordinates with up to three decimal places.
+    pub fn new() -> Self {
+        CompactPathSerializer::with_precision(3)
+    }
+
+    /// Creates a serializer that writes coordinates with up to `decimals` decimal places.
+    pub fn with_precision(decimals: u32) -> Self {
+        assert!(decimals <= 9);
+        CompactPathSerializer {
+            path: String::new(),
+            scale: 10f64.powi(decimals as i32),
+            precision: decimals,
+            current: (0, 0),
+            first: (0, 0),
+            last_ctrl: LastCtrl::None,
+            last_command: None,
+            last_number: None,
+            conic_tolerance: DEFAULT_CONIC_TOLERANCE,
+        }
+    }
+
+    /// Sets the maximum distance between conics and the quadratic bézier curves
+    /// approximating them in the output.
+    pub fn with_conic_tolerance(mut self, tolerance: f32) -> Self {
+        self.conic_tolerance = tolerance;
+        self
+    }
+
+    fn quantize(&self, p: Point) -> (i64, i64) {
+        (
+            (p.x as f64 * self.scale).round() as i64,
+            (p.y as f64 * self.scale).round() as i64,
+        )
+    }
+
+    fn quantize_scalar(&self, v: f32) -> i64 {
+        (v as f64 * self.scale).round() as i64
+    }
+
+    fn to_point(&self, p: (i64, i64)) -> Point {
+        point((p.0 as f64 / self.scale) as f32, (p.1 as f64 / self.scale) as f32)
+    }
+
+    fn relative(&self, p: (i64, i64)) -> (i64, i64) {
+        (p.0 - self.current.0, p.1 - self.current.1)
+    }
+
+    fn reflected_ctrl(&self, cubic: bool) -> (i64, i64) {
+        match (self.last_ctrl, cubic) {
+            (LastCtrl::Cubic(ctrl), true) | (LastCtrl::Quad(ctrl), false) => {
+                (2 * self.current.0 - ctrl.0, 2 * self.current.1 - ctrl.1)
+            }
+            _ => self.current,
+        }
+    }
+
+    fn format_number(&self, value: i64) -> String {
+        let mut digits = value.abs().to_string();
+        let precision = self.precision as usize;
+        if digits.len() <= precision {
+            digits = format!("{}{}", "0".repeat(precision + 1 - digits.len()), digits);
+        }
+
+        let (integer, fraction) = digits.split_at(digits.len() - precision);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            return if value < 0 { format!("-{}", integer) } else { integer.to_string() };
+        }
+
+        // The leading zero is not needed.
+        let integer = if integer == "0" { "" } else { integer };
+        let sign = if value < 0 { "-" } else { "" };
+        format!("{}{}.{}", sign, integer, fraction)
+    }
+
+    /// Formats a command with its parameters, omitting the command letter if it is
+    /// implied by the previous one.
+    fn format_command(&self, command: u8, params: &[i64]) -> (String, Option<bool>) {
+        let implicit = !params.is_empty() && match (self.last_command, command) {
+            (Some(b'M'), b'L') | (Some(b'm'), b'l') => true,
+            (Some(b'M'), _) | (Some(b'm'), _) => false,
+            (Some(last), _) => last == command,
+            (None, _) => false,
+        };
+
+        let mut result = String::new();
+        let mut last_number = None;
+        if implicit {
+            last_number = self.last_number;
+        } else {
+            result.push(command as char);
+        }
+
+        for param in params {
+            let number = self.format_number(*param);
+            let separator_needed = match last_number {
+                Some(has_dot) => {
+                    // A sign or a second decimal point starts a new number.
+                    let starts_new_number = number.starts_with('-')
+                        || (has_dot && number.starts_with('.'));
+                    !starts_new_number
+                }
+                None => false,
+            };
+            if separator_needed {
+                result.push(' ');
+            }
+            last_number = Some(number.contains('.'));
+            result.push_str(&number);
+        }
+
+        (result, last_number)
+    }
+
+    /// Writes the shorter of the absolute and relative forms of a command.
+    fn write_command(&mut self, absolute: (u8, &[i64]), relative: (u8, &[i64])) {
+        let (abs_str, abs_last) = self.format_command(absolute.0, absolute.1);
+        let (rel_str, rel_last) = self.format_command(relative.0, relative.1);
+        if rel_str.len() < abs_str.len() {
+            self.path.push_str(&rel_str);
+            self.last_command = Some(relative.0);
+            self.last_number = rel_last;
+        } else {
+            self.path.push_str(&abs_str);
+            self.last_command = Some(absolute.0);
+            self.last_number = abs_last;
+        }
+    }
+}
+
+impl Default for CompactPathSerializer {
+    fn default() -> Self { CompactPathSerializer::new() }
+}
+
+impl Build for CompactPathSerializer {
+    type PathType = String;
+
+    fn build(self) -> String { self.path }
+
+    fn build_and_reset(&mut self) -> String {
+        self.current = (0, 0);
+        self.first = (0, 0);
+        self.last_ctrl = LastCtrl::None;
+        self.last_command = None;
+        self.last_number = None;
+        mem::take(&mut self.path)
+    }
+}
+
+impl FlatPathBuilder for CompactPathSerializer {
+    fn move_to(&mut self, to: Point) {
+        let to = self.quantize(to);
+        let rel = self.relative(to);
+        self.write_command((b'M', &[to.0, to.1]), (b'm', &[rel.0, rel.1]));
+        self.current = to;
+        self.first = to;
+        self.last_ctrl = LastCtrl::None;
+    }
+
+    fn line_to(&mut self, to: Point) {
+        let to = self.quantize(to);
+        let rel = self.relative(to);
+        if to.1 == self.current.1 {
+            self.write_command((b'H', &[to.0]), (b'h', &[rel.0]));
+        } else if to.0 == self.current.0 {
+            self.write_command((b'V', &[to.1]), (b'v', &[rel.1]));
+        } else {
+            self.write_command((b'L', &[to.0, to.1]), (b'l', &[rel.0, rel.1]));
+        }
+        self.current = to;
+        self.last_ctrl = LastCtrl::None;
+    }
+
+    fn close(&mut self) {
+        self.path.push('Z');
+        self.last_command = Some(b'Z');
+        self.last_number = None;
+        self.current = self.first;
+        self.last_ctrl = LastCtrl::None;
+    }
+
+    fn current_position(&self) -> Point {
+        self.to_point(self.current)
+    }
+}
+
+impl PathBuilder for CompactPathSerializer {
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        let ctrl = self.quantize(ctrl);
+        let to = self.quantize(to);
+        let rel_to = self.relative(to);
+        if ctrl == self.reflected_ctrl(false) {
+            self.write_command((b'T', &[to.0, to.1]), (b't', &[rel_to.0, rel_to.1]));
+        } else {
+            let rel_ctrl = self.relative(ctrl);
+            self.write_command(
+                (b'Q', &[ctrl.0, ctrl.1, to.0, to.1]),
+                (b'q', &[rel_ctrl.0, rel_ctrl.1, rel_to.0, rel_to.1]),
+            );
+        }
+        self.current = to;
+        self.last_ctrl = LastCtrl::Quad(ctrl);
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        let ctrl1 = self.quantize(ctrl1);
+        let ctrl2 = self.quantize(ctrl2);
+        let to = self.quantize(to);
+        let rel_ctrl2 = self.relative(ctrl2);
+        let rel_to = self.relative(to);
+        if ctrl1 == self.reflected_ctrl(true) {
+            self.write_command(
+                (b'S', &[ctrl2.0, ctrl2.1, to.0, to.1]),
+                (b's', &[rel_ctrl2.0, rel_ctrl2.1, rel_to.0, rel_to.1]),
+            );
+        } else {
+            let rel_ctrl1 = self.relative(ctrl1);
+            self.write_command(
+                (b'C', &[ctrl1.0, ctrl1.1, ctrl2.0, ctrl2.1, to.0, to.1]),
+                (b'c', &[rel_ctrl1.0, rel_ctrl1.1, rel_ctrl2.0, rel_ctrl2.1, rel_to.0, rel_to.1]),
+            );
+        }
+        self.current = to;
+        self.last_ctrl = LastCtrl::Cubic(ctrl2);
+    }
+
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        // SVG paths can't represent conics.
+        let conic = ConicSegment { from: self.current_position(), ctrl, to, weight };
+        conic.for_each_quadratic_bezier(self.conic_tolerance, &mut |curve| {
+            self.quadratic_bezier_to(curve.ctrl, curve.to);
+        });
+    }
+
+    fn arc(
+        &mut self,
+        center: Point,
+        radii: Vector,
+        sweep_angle: Angle,
+        x_rotation: Angle
+    ) {
+        let start_angle = (self.current_position() - center).angle_from_x_axis() - x_rotation;
+        let arc = Arc { center, radii, start_angle, sweep_angle, x_rotation };
+        // A single SVG arc can't describe a full ellipse.
+        let arcs = if sweep_angle.get().abs() >= PI {
+            let (a, b) = arc.split(0.5);
+            vec![a, b]
+        } else {
+            vec![arc]
+        };
+
+        for arc in arcs {
+            let svg = arc.to_svg_arc();
+            self.arc_to(svg.radii, svg.x_rotation, svg.flags, svg.to);
+        }
+    }
+}
+
+impl SvgBuilder for CompactPathSerializer {
+    fn relative_move_to(&mut self, to: Vector) {
+        let to = self.current_position() + to;
+        self.move_to(to);
+    }
+
+    fn relative_line_to(&mut self, to: Vector) {
+        let to = self.current_position() + to;
+        self.line_to(to);
+    }
+
+    fn relative_quadratic_bezier_to(&mut self, ctrl: Vector, to: Vector) {
+        let offset = self.current_position();
+        self.quadratic_bezier_to(offset + ctrl, offset + to);
+    }
+
+    fn relative_cubic_bezier_to(&mut self, ctrl1: Vector, ctrl2: Vector, to: Vector) {
+        let offset = self.current_position();
+        self.cubic_bezier_to(offset + ctrl1, offset + ctrl2, offset + to);
+    }
+
+    fn smooth_cubic_bezier_to(&mut self, ctrl2: Point, to: Point) {
+        let ctrl1 = self.to_point(self.reflected_ctrl(true));
+        self.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    fn smooth_relative_cubic_bezier_to(&mut self, ctrl2: Vector, to: Vector) {
+        let offset = self.current_position();
+        self.smooth_cubic_bezier_to(offset + ctrl2, offset + to);
+    }
+
+    fn smooth_quadratic_bezier_to(&mut self, to: Point) {
+        let ctrl = self.to_point(self.reflected_ctrl(false));
+        self.quadratic_bezier_to(ctrl, to);
+    }
+
+    fn smooth_relative_quadratic_bezier_to(&mut self, to: Vector) {
+        let to = self.current_position() + to;
+        self.smooth_quadratic_bezier_to(to);
+    }
+
+    fn horizontal_line_to(&mut self, x: f32) {
+        let y = self.current_position().y;
+        self.line_to(point(x, y));
+    }
+
+    fn relative_horizontal_line_to(&mut self, dx: f32) {
+        let to = self.current_position() + vector(dx, 0.0);
+        self.line_to(to);
+    }
+
+    fn vertical_line_to(&mut self, y: f32) {
+        let x = self.current_position().x;
+        self.line_to(point(x, y));
+    }
+
+    fn relative_vertical_line_to(&mut self, dy: f32) {
+        let to = self.current_position() + vector(0.0, dy);
+        self.line_to(to);
+    }
+
+    fn arc_to(
+        &mut self,
+        radii: Vector,
+        x_rotation: Angle,
+        flags: ArcFlags,
+        to: Point
+    ) {
+        let to = self.quantize(to);
+        let rel = self.relative(to);
+        let rx = self.quantize_scalar(radii.x);
+        let ry = self.quantize_scalar(radii.y);
+        let rotation = self.quantize_scalar(x_rotation.get().to_degrees());
+        let large_arc = if flags.large_arc { self.scale as i64 } else { 0 };
+        let sweep = if flags.sweep { self.scale as i64 } else { 0 };
+        self.write_command(
+            (b'A', &[rx, ry, rotation, large_arc, sweep, to.0, to.1]),
+            (b'a', &[rx, ry, rotation, large_arc, sweep, rel.0, rel.1]),
+        );
+        self.current = to;
+        self.last_ctrl = LastCtrl::None;
+    }
+
+    fn relative_arc_to(
+        &mut self,
+        radii: Vector,
+        x_rotation: Angle,
+        flags: ArcFlags,
+        to: Vector,
+    ) {
+        let to = self.current_position() + to;
+        self.arc_to(radii, x_rotation, flags, to);
+    }
+}
+
+impl PolygonBuilder for CompactPathSerializer {
+    fn polygon(&mut self, points: &[Point]) {
+        build_polygon(self, points);
+    }
+}
+
+#[test]
+fn parse_path() {
+    use crate::path::Path;
+
+    let path = build_path(
+        Path::builder().with_svg(),
+        "M10-20l30.5.5e1 H 1e2 v-10,5 z m 1 1 2 2,3 3",
+    ).unwrap();
+    assert_eq!(path.iter().count(), 9);
+
+    // Arc flags don't need to be separated from the following numbers.
+    let events: Vec<SvgEvent> = PathTokenizer::new("M 0 0 a5,5 30 1010 20")
+        .map(|evt| evt.unwrap())
+        .collect();
+    assert_eq!(
+        events[1],
+        SvgEvent::RelativeArcTo(
+            vector(5.0, 5.0),
+            Angle::degrees(30.0),
+            ArcFlags { large_arc: true, sweep: false },
+            vector(10.0, 20.0),
+        )
+    );
+}
+
+#[test]
+fn parse_errors() {
+    use crate::path::Path;
+
+    fn parse_error(src: &str) -> ParseError {
+        build_path(Path::builder().with_svg(), src).unwrap_err()
+    }
+
+    let error = parse_error("M 0 0 L 10 0 L 10");
+    assert_eq!(error.kind, ParseErrorKind::MissingCoordinate);
+    assert_eq!(error.offset, 17);
+    assert_eq!(error.command, Some('L'));
+    assert_eq!(error.token, "");
+
+    let error = parse_error("M 0 0 L 10 x");
+    assert_eq!(error.kind, ParseErrorKind::MissingCoordinate);
+    assert_eq!(error.offset, 11);
+    assert_eq!(error.token, "x");
+
+    let error = parse_error("M 0 0 K 10 0");
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(error.offset, 6);
+    assert_eq!(error.command, None);
+    assert_eq!(error.token, "K");
+
+    let error = parse_error("L 10 0");
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(error.offset, 0);
+
+    let error = parse_error("M 0 0 A 5 5 0 2 0 10 10");
+    assert_eq!(error.kind, ParseErrorKind::InvalidArcFlag);
+    assert_eq!(error.offset, 14);
+    assert_eq!(error.command, Some('A'));
+    assert_eq!(error.token, "2");
+
+    let error = parse_error("M 0 0 Z 10");
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(error.offset, 8);
+
+    assert_eq!(
+        format!("{}", parse_error("M 0 0 A 5 5 0 2 0 10 10")),
+        "invalid arc flag in command 'A' at offset 14: '2'"
+    );
+}
+
+#[test]
+fn parse_lenient() {
+    use crate::path::{Path, PathEvent};
+    use crate::path::geom::LineSegment;
+
+    let (path, error) = build_path_lenient(Path::builder().with_svg(), "M 0 0 L 10 0 L 10 10 Q 5");
+    assert_eq!(error.unwrap().offset, 24);
+    let events: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[2], PathEvent::Line(LineSegment { from: point(10.0, 0.0), to: point(10.0, 10.0) }));
+
+    let (path, error) = build_path_lenient(Path::builder().with_svg(), "M 0 0 L 10 0 Z");
+    assert!(error.is_none());
+    assert_eq!(path.iter().count(), 3);
+}
+
+#[test]
+fn compact_serializer_output() {
+    let mut serializer = CompactPathSerializer::with_precision(2);
+    serializer.move_to(point(0.5, -0.25));
+    serializer.line_to(point(1.234, 0.5));
+    serializer.line_to(point(100.0, -0.001));
+    serializer.line_to(point(100.0, 50.0));
+    serializer.close();
+    assert_eq!(serializer.build_and_reset(), "M.5-.25 1.23.5 100 0V50Z");
+
+    // Smooth curves.
+    serializer.move_to(point(0.0, 0.0));
+    serializer.cubic_bezier_to(point(0.0, 10.0), point(10.0, 10.0), point(10.0, 0.0));
+    serializer.cubic_bezier_to(point(10.0, -10.0), point(20.0, -10.0), point(20.0, 0.0));
+    serializer.quadratic_bezier_to(point(20.0, 0.0), point(25.0, 5.0));
+    serializer.quadratic_bezier_to(point(30.0, 10.0), point(35.0, 5.0));
+    assert_eq!(serializer.build_and_reset(), "M0 0C0 10 10 10 10 0S20-10 20 0t5 5T35 5");
+
+    // Conics are approximated with more curves when the tolerance is lower.
+    let output_length = |tolerance: f32| {
+        let mut serializer = CompactPathSerializer::new().with_conic_tolerance(tolerance);
+        serializer.move_to(point(0.0, 0.0));
+        serializer.conic_to(point(100.0, 0.0), point(100.0, 100.0), 0.2);
+        serializer.build().len()
+    };
+    assert!(output_length(0.001) > output_length(1.0));
+}
+
+#[test]
+fn compact_serializer_round_trip() {
+    use crate::path::{Path, PathEvent};
+
+    let events = [
+        SvgEvent::MoveTo(point(1.0, 2.0)),
+        SvgEvent::LineTo(point(101.5, 2.0)),
+        SvgEvent::RelativeVerticalLineTo(-30.25),
+        SvgEvent::RelativeLineTo(vector(-3.333, 4.5)),
+        SvgEvent::CubicTo(point(110.0, 5.0), point(120.0, -10.0), point(130.0, 0.0)),
+        SvgEvent::SmoothCubicTo(point(150.0, 20.0), point(160.0, 0.0)),
+        SvgEvent::RelativeQuadraticTo(vector(5.0, 5.0), vector(10.0, 0.0)),
+        SvgEvent::SmoothRelativeQuadraticTo(vector(10.0, 0.0)),
+        SvgEvent::ArcTo(vector(20.0, 10.0), Angle::degrees(30.0), ArcFlags { large_arc: true, sweep: false }, point(200.0, 40.0)),
+        SvgEvent::Close,
+        SvgEvent::RelativeMoveTo(vector(-1000.0, 0.125)),
+        SvgEvent::HorizontalLineTo(-0.5),
+        SvgEvent::QuadraticTo(point(-10.0, -10.0), point(-20.0, 0.0)),
+        SvgEvent::LineTo(point(-20.0, 0.0)),
+        SvgEvent::Close,
+    ];
+
+    for &precision in &[0, 2, 5] {
+        let mut builder = Path::builder().with_svg();
+        let mut serializer = CompactPathSerializer::with_precision(precision);
+        for event in &events {
+            builder.svg_event(*event);
+            serializer.svg_event(*event);
+        }
+
+        let expected = builder.build();
+        let serialized = serializer.build();
+        let parsed = build_path(Path::builder().with_svg(), &serialized).unwrap();
+
+        fn endpoint(event: &PathEvent) -> Point {
+            match *event {
+                PathEvent::MoveTo(to) => to,
+                PathEvent::Line(segment) | PathEvent::Close(segment) => segment.to,
+                PathEvent::Quadratic(segment) => segment.to,
+                PathEvent::Cubic(segment) => segment.to,
+                PathEvent::Conic(segment) => segment.to,
+            }
+        }
+
+        let tolerance = 0.1f32.powi(precision as i32) * 2.0;
+        let expected: Vec<PathEvent> = expected.iter().collect();
+        let parsed: Vec<PathEvent> = parsed.iter().collect();
+        assert_eq!(expected.len(), parsed.len(), "{}", serialized);
+        for (a, b) in expected.iter().zip(parsed.iter()) {
+            assert!((endpoint(a) - endpoint(b)).length() < tolerance, "{:?} != {:?} ({})", a, b, serialized);
+        }
+    }
+}
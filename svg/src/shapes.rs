@@ -0,0 +1,226 @@
+//! Conversion of SVG's basic shapes into paths.
+//!
+//! The shapes are built following the equivalent paths described in the
+//! [SVG specification](https://svgwg.org/svg2-draft/shapes.html), so that they can go
+//! through the same pipeline as any other path. Shapes that disable rendering in SVG
+//! (for example a rectangle with a width of zero) don't produce anything.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate lyon_svg as svg;
+//! # use svg::shapes::build_rect;
+//! # use svg::path::Path;
+//! # use svg::path::math::rect;
+//! # fn main() {
+//! // Equivalent to <rect x="0" y="0" width="100" height="50" rx="10"/>.
+//! let mut builder = Path::builder();
+//! build_rect(&mut builder, &rect(0.0, 0.0, 100.0, 50.0), Some(10.0), None);
+//! let path = builder.build();
+//! # }
+//! ```
+
+use crate::path::builder::{FlatPathBuilder, PathBuilder};
+use crate::path::math::{Angle, Point, Rect, Vector, point, vector};
+
+fn quarter_arc<Builder: PathBuilder>(builder: &mut Builder, center: Point, radii: Vector) {
+    builder.arc(center, radii, Angle::frac_pi_2(), Angle::zero());
+}
+
+/// Builds the path of an SVG `<rect>` element.
+///
+/// `rx` and `ry` are the radii of the rounded corners. As in SVG, if only one of them
+/// is specified the other one takes the same value, negative values are ignored and the
+/// radii are clamped to half of the width and height of the rectangle.
+pub fn build_rect<Builder: PathBuilder>(
+    builder: &mut Builder,
+    rect: &Rect,
+    rx: Option<f32>,
+    ry: Option<f32>,
+) {
+    let w = rect.size.width;
+    let h = rect.size.height;
+    if w <= 0.0 || h <= 0.0 {
+        return;
+    }
+
+    let rx = rx.filter(|r| *r >= 0.0);
+    let ry = ry.filter(|r| *r >= 0.0);
+    let (rx, ry) = match (rx, ry) {
+        (Some(rx), Some(ry)) => (rx, ry),
+        (Some(r), None) | (None, Some(r)) => (r, r),
+        (None, None) => (0.0, 0.0),
+    };
+    let rx = rx.min(w * 0.5);
+    let ry = ry.min(h * 0.5);
+
+    let x_min = rect.min_x();
+    let y_min = rect.min_y();
+    let x_max = rect.max_x();
+    let y_max = rect.max_y();
+
+    if rx == 0.0 || ry == 0.0 {
+        builder.move_to(point(x_min, y_min));
+        builder.line_to(point(x_max, y_min));
+        builder.line_to(point(x_max, y_max));
+        builder.line_to(point(x_min, y_max));
+        builder.close();
+        return;
+    }
+
+    // Skip the straight edges when the corners meet.
+    let horizontal_edges = rx < w * 0.5;
+    let vertical_edges = ry < h * 0.5;
+
+    let radii = vector(rx, ry);
+    builder.move_to(point(x_min + rx, y_min));
+    if horizontal_edges {
+        builder.line_to(point(x_max - rx, y_min));
+    }
+    quarter_arc(builder, point(x_max - rx, y_min + ry), radii);
+    if vertical_edges {
+        builder.line_to(point(x_max, y_max - ry));
+    }
+    quarter_arc(builder, point(x_max - rx, y_max - ry), radii);
+    if horizontal_edges {
+        builder.line_to(point(x_min + rx, y_max));
+    }
+    quarter_arc(builder, point(x_min + rx, y_max - ry), radii);
+    if vertical_edges {
+        builder.line_to(point(x_min, y_min + ry));
+    }
+    quarter_arc(builder, point(x_min + rx, y_min + ry), radii);
+    builder.close();
+}
+
+/// Builds the path of an SVG `<circle>` element.
+pub fn build_circle<Builder: PathBuilder>(builder: &mut Builder, center: Point, radius: f32) {
+    build_ellipse(builder, center, vector(radius, radius));
+}
+
+/// Builds the path of an SVG `<ellipse>` element.
+///
+/// The path starts at the rightmost point of the ellipse and goes through the bottom,
+/// left and top points, in this order.
+pub fn build_ellipse<Builder: PathBuilder>(builder: &mut Builder, center: Point, radii: Vector) {
+    if radii.x <= 0.0 || radii.y <= 0.0 {
+        return;
+    }
+
+    builder.move_to(center + vector(radii.x, 0.0));
+    for _ in 0..4 {
+        quarter_arc(builder, center, radii);
+    }
+    builder.close();
+}
+
+/// Builds the path of an SVG `<line>` element.
+pub fn build_line<Builder: FlatPathBuilder>(builder: &mut Builder, from: Point, to: Point) {
+    builder.move_to(from);
+    builder.line_to(to);
+}
+
+/// Builds the path of an SVG `<polyline>` element.
+///
+/// Nothing is built if there are less than two points. `<polygon>` elements are
+/// built with `lyon_path::builder::build_polygon`.
+pub fn build_polyline<Builder: FlatPathBuilder>(builder: &mut Builder, points: &[Point]) {
+    if points.len() < 2 {
+        return;
+    }
+
+    builder.move_to(points[0]);
+    for p in &points[1..] {
+        builder.line_to(*p);
+    }
+}
+
+#[cfg(test)]
+fn endpoints(path: &crate::path::Path) -> Vec<Point> {
+    use crate::path::PathEvent;
+
+    path.iter().map(|event| match event {
+        PathEvent::MoveTo(to) => to,
+        PathEvent::Line(segment) | PathEvent::Close(segment) => segment.to,
+        PathEvent::Quadratic(segment) => segment.to,
+        PathEvent::Cubic(segment) => segment.to,
+        PathEvent::Conic(segment) => segment.to,
+    }).collect()
+}
+
+#[test]
+fn rect_shapes() {
+    use crate::path::Path;
+    use crate::path::math::rect;
+
+    let mut builder = Path::builder();
+    build_rect(&mut builder, &rect(1.0, 2.0, 10.0, 20.0), None, Some(-1.0));
+    assert_eq!(
+        endpoints(&builder.build()),
+        vec![point(1.0, 2.0), point(11.0, 2.0), point(11.0, 22.0), point(1.0, 22.0), point(1.0, 2.0)]
+    );
+
+    // Empty rectangles disable rendering.
+    let mut builder = Path::builder();
+    build_rect(&mut builder, &rect(1.0, 2.0, 0.0, 20.0), Some(1.0), Some(1.0));
+    assert_eq!(builder.build().iter().count(), 0);
+
+    // rx is clamped to half of the width and ry defaults to rx before being clamped
+    // to half of the height.
+    let mut builder = Path::builder();
+    build_rect(&mut builder, &rect(0.0, 0.0, 10.0, 4.0), Some(100.0), None);
+    let points = endpoints(&builder.build());
+    assert_eq!(points[0], point(5.0, 0.0));
+    for p in &points {
+        assert!(p.x >= -1e-5 && p.x <= 10.0 + 1e-5 && p.y >= -1e-5 && p.y <= 4.0 + 1e-5);
+    }
+    assert!(points.iter().any(|p| (*p - point(10.0, 2.0)).length() < 1e-4));
+    assert!(points.iter().any(|p| (*p - point(0.0, 2.0)).length() < 1e-4));
+    assert!(points.iter().any(|p| (*p - point(5.0, 4.0)).length() < 1e-4));
+    // No line between the corners.
+    let n = points.len();
+    for pair in points[..n - 1].windows(2) {
+        assert!((pair[0] - pair[1]).length() > 0.1);
+    }
+}
+
+#[test]
+fn ellipse_shapes() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    build_circle(&mut builder, point(5.0, 5.0), 2.0);
+    let points = endpoints(&builder.build());
+    assert_eq!(points[0], point(7.0, 5.0));
+    for p in &points {
+        assert!(((*p - point(5.0, 5.0)).length() - 2.0).abs() < 1e-4);
+    }
+    // The circle goes through the bottom point before reaching the left one.
+    let bottom = points.iter().position(|p| (*p - point(5.0, 7.0)).length() < 1e-4).unwrap();
+    let left = points.iter().position(|p| (*p - point(3.0, 5.0)).length() < 1e-4).unwrap();
+    assert!(bottom < left);
+
+    let mut builder = Path::builder();
+    build_ellipse(&mut builder, point(0.0, 0.0), vector(3.0, 0.0));
+    assert_eq!(builder.build().iter().count(), 0);
+}
+
+#[test]
+fn line_shapes() {
+    use crate::path::Path;
+    use crate::path::builder::build_polygon;
+
+    let points = [point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0)];
+
+    let mut builder = Path::builder();
+    build_line(&mut builder, points[0], points[2]);
+    build_polyline(&mut builder, &points);
+    build_polygon(&mut builder, &points);
+    build_polygon(&mut builder, &points[..1]);
+    let path = builder.build();
+    assert_eq!(endpoints(&path), vec![
+        points[0], points[2],
+        points[0], points[1], points[2],
+        points[0], points[1], points[2], points[0],
+    ]);
+}
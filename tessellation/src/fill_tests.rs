@@ -3,9 +3,10 @@ use crate::geometry_builder::*;
 use crate::path::builder::{Build, FlatPathBuilder, PathBuilder};
 use crate::path::{Path, PathSlice};
 use crate::extra::rust_logo::build_logo_path;
+use crate::svg_output::{SvgGeometryBuilder, MeshStyle};
 use crate::{FillTessellator, TessellationError, FillOptions, FillVertex, OnError};
 
-use std::env;
+use std::{env, fs, thread};
 
 type Vertex = FillVertex;
 
@@ -26,6 +27,28 @@ fn tessellate_path(path: PathSlice, log: bool) -> Result<usize, TessellationErro
     return Ok(buffers.indices.len() / 3);
 }
 
+/// Writes the tessellation of a path as an SVG document in the directory specified
+/// by the `LYON_SVG_DUMP` environment variable, if any.
+fn dump_svg(path: PathSlice) {
+    let directory = match env::var("LYON_SVG_DUMP") {
+        Ok(directory) => directory,
+        Err(_) => { return; }
+    };
+
+    // Keep as much of the tessellation as possible.
+    let mut output = SvgGeometryBuilder::new(MeshStyle::default());
+    let _ = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+        FillTessellator::new().tessellate_path(
+            path.iter(),
+            &FillOptions::tolerance(0.05).on_error(OnError::Recover),
+            &mut output
+        )
+    }));
+    let svg = output.to_svg();
+
+    let name = thread::current().name().unwrap_or("fill_test").replace("::", "-");
+    fs::write(format!("{}/{}.svg", directory, name), svg).unwrap();
+}
 
 fn test_path(path: PathSlice) {
     test_path_internal(path, None);
@@ -49,6 +72,7 @@ fn test_path_internal(path: PathSlice, expected_triangle_count: Option<usize>) {
     if let Ok(Ok(num_triangles)) = res {
         if let Some(expected_triangles) = expected_triangle_count {
             if num_triangles != expected_triangles {
+                dump_svg(path);
                 tessellate_path(path, add_logging).unwrap();
                 panic!("expected {} triangles, got {}", expected_triangles, num_triangles);
             }
@@ -67,6 +91,8 @@ fn test_path_internal(path: PathSlice, expected_triangle_count: Option<usize>) {
         }
     }
 
+    dump_svg(path);
+
     panic!();
 }
 
@@ -790,6 +816,41 @@ fn n_segments_intersecting() {
     }
 }
 
+#[test]
+fn nearly_collinear_edges() {
+    // Many vertices within a fraction of a unit from a line, with edges crossing
+    // each other at very shallow angles.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    for i in 1..40 {
+        let x = i as f32 * 25.0;
+        let offset = if i % 2 == 0 { 0.0001 } else { -0.0001 } * (i % 7) as f32;
+        builder.line_to(point(x, x * 0.001 + offset));
+    }
+    for i in (1..40).rev() {
+        let x = i as f32 * 25.0 + 3.0;
+        let offset = if i % 3 == 0 { 0.0002 } else { -0.00015 };
+        builder.line_to(point(x, x * 0.001 + offset));
+    }
+    builder.close();
+
+    test_path_with_rotations(builder.build(), 0.05, None);
+}
+
+#[test]
+fn nearly_overlapping_edges_from_same_vertex() {
+    // Edges leaving the same vertex in almost the same direction.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1000.0, 1.0));
+    builder.line_to(point(0.0, 0.0001));
+    builder.line_to(point(999.0, 1.0001));
+    builder.line_to(point(500.0, 100.0));
+    builder.close();
+
+    test_path_with_rotations(builder.build(), 0.05, None);
+}
+
 #[test]
 fn back_along_previous_edge() {
     // This test has edges that come back along the previous edge.
@@ -1126,3 +1187,75 @@ fn test_exp_no_intersection_01() {
     // SVG path syntax:
     // "M 80.041534 19.24472 L 76.56131 23.062233 L 67.26949 23.039438 L 48.42367 28.978098 Z"
 }
+
+#[test]
+fn test_conic_circle() {
+    let w = 0.5f32.sqrt();
+    let mut builder = Path::builder();
+    builder.move_to(point(100.0, 0.0));
+    builder.conic_to(point(100.0, 100.0), point(0.0, 100.0), w);
+    builder.conic_to(point(-100.0, 100.0), point(-100.0, 0.0), w);
+    builder.conic_to(point(-100.0, -100.0), point(0.0, -100.0), w);
+    builder.conic_to(point(100.0, -100.0), point(100.0, 0.0), w);
+    builder.close();
+    let path = builder.build();
+
+    test_path(path.as_slice());
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &FillOptions::tolerance(0.05),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    for vertex in &buffers.vertices {
+        let d = vertex.position.to_vector().length();
+        assert!((d - 100.0).abs() <= 0.06);
+    }
+}
+
+#[test]
+fn test_f64_recentered() {
+    use crate::path::builder::RecenteringBuilder;
+    use crate::{StrokeTessellator, StrokeOptions, StrokeVertex};
+
+    // Far enough from the origin that f32 can't represent the coordinates
+    // with sub-meter precision.
+    let origin = F64Point::new(6_500_000.0, 1_250_000.0);
+    let mut builder = RecenteringBuilder::new(Path::builder(), origin);
+    builder.polygon(&[
+        F64Point::new(6_500_000.125, 1_250_000.125),
+        F64Point::new(6_500_010.125, 1_250_000.125),
+        F64Point::new(6_500_010.125, 1_250_005.5),
+        F64Point::new(6_500_000.125, 1_250_005.5),
+    ]);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    assert_eq!(buffers.indices.len(), 6);
+    for vertex in &buffers.vertices {
+        let p = vertex.position;
+        assert!(p.x == 0.125 || p.x == 10.125);
+        assert!(p.y == 0.125 || p.y == 5.5);
+    }
+
+    let mut buffers: VertexBuffers<StrokeVertex, u16> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(
+        path.iter(),
+        &StrokeOptions::default().with_line_width(0.5),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    for vertex in &buffers.vertices {
+        let p = vertex.position;
+        assert!(p.x >= -0.125 - 1e-4 && p.x <= 10.375 + 1e-4);
+        assert!(p.y >= -0.125 - 1e-4 && p.y <= 5.75 + 1e-4);
+    }
+}
@@ -1,3 +1,6 @@
+// General purpose fixed point numbers, not all of which are used by the tessellators.
+#![allow(dead_code)]
+
 use std::marker::PhantomData;
 use std::cmp;
 use std::ops;
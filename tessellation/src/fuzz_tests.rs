@@ -549,7 +549,6 @@ fn fuzzing_test_case_12() {
 }
 
 #[test]
-#[ignore]
 fn fuzzing_test_case_13() {
     let mut builder = Path::builder();
 
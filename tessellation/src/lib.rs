@@ -129,6 +129,11 @@
 //! [lyon_path](https://docs.rs/lyon_path/*/lyon_path/) is provided for convenience
 //! (but is optional).
 //!
+//! The tessellators work with single-precision coordinates. Input data with large coordinates
+//! in double precision (for example geographic data) can be brought closer to the origin
+//! using the [RecenteringBuilder](https://docs.rs/lyon_path/*/lyon_path/builder/struct.RecenteringBuilder.html)
+//! before tessellation, in which case the output geometry is relative to the chosen origin.
+//!
 //! ### The output: geometry builders
 //!
 //! The tessellators are parametrized over a type implementing the
@@ -176,7 +181,6 @@
 //! - [Writing a tessellator](geometry_builder/index.html#writing-a-tessellator).
 //!
 
-#![allow(dead_code)]
 //#![allow(needless_return, new_without_default_derive)] // clippy
 
 pub use lyon_path as path;
@@ -190,9 +194,11 @@ pub extern crate serde;
 pub mod basic_shapes;
 pub mod geometry_builder;
 pub mod debugger;
+pub mod svg_output;
 mod path_fill;
 mod path_stroke;
 mod math_utils;
+mod predicates;
 mod fixed;
 
 #[cfg(test)]
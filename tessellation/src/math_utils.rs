@@ -3,14 +3,12 @@
 use crate::fixed;
 use crate::geom::math::*;
 use crate::path_fill::Edge;
+use crate::predicates::orientation;
 use crate::geom::euclid;
 use std::f64;
 
 pub type FixedPoint32 = fixed::Fp32<fixed::_16>;
-pub type FixedPoint64 = fixed::Fp64<fixed::_16>;
-pub type TessVector = euclid::default::Vector2D<FixedPoint32>;
 pub type TessPoint = euclid::default::Point2D<FixedPoint32>;
-pub type TessPoint64 = euclid::default::Point2D<FixedPoint64>;
 #[inline]
 pub fn fixed(val: f32) -> FixedPoint32 { FixedPoint32::from_f32(val) }
 
@@ -45,6 +43,29 @@ pub(crate) fn segment_intersection(
     let a2 = F64Point::new(e2.upper.x.to_f64(), e2.upper.y.to_f64());
     let b2 = F64Point::new(e2.lower.x.to_f64(), e2.lower.y.to_f64());
 
+    // Decide whether the edges intersect with exact predicates, so that nearly
+    // collinear or touching edges are classified consistently.
+    // Intersections at the upper endpoints are ignored.
+    let o1 = orientation(a1, b1, a2);
+    let o2 = orientation(a1, b1, b2);
+    let o3 = orientation(a2, b2, a1);
+    let o4 = orientation(a2, b2, b1);
+
+    if o1 == 0 || o3 == 0 || o1 * o2 > 0 || o3 * o4 > 0 || (o2 == 0 && o4 == 0) {
+        // Either parallel, disjoint or touching at an upper endpoint.
+        return None;
+    }
+
+    if o4 == 0 {
+        // The lower endpoint of e1 is on e2.
+        return Some(e1.lower);
+    }
+
+    if o2 == 0 {
+        // The lower endpoint of e2 is on e1.
+        return Some(e2.lower);
+    }
+
     let v1 = b1 - a1;
     let v2 = b2 - a2;
 
@@ -63,31 +84,31 @@ pub(crate) fn segment_intersection(
     // t and u should be divided by v1_cross_v2, but we postpone that to not lose precision.
     // We have to respect the sign of v1_cross_v2 (and therefore t and u) so we apply it now and
     // will use the absolute value of v1_cross_v2 afterwards.
-    let t = (a2 - a1).cross(v2) * sign_v1_cross_v2;
-    let u = a2_a1_cross_v1 * sign_v1_cross_v2;
-    if t >= 0.0 && t <= abs_v1_cross_v2 && u > 0.0 && u <= abs_v1_cross_v2 {
-
-        // Snap intersections to the edge if it is very close.
-        // This helps with preventing small floating points errors from
-        // accumulating when many edges intersect at the same position.
-        let threshold = 0.000_001;
-        if 1.0 - t / abs_v1_cross_v2 < threshold {
-            return Some(e1.lower);
-        }
-        if 1.0 - u / abs_v1_cross_v2 < threshold {
-            return Some(e2.lower);
-        }
+    // The predicates above guarantee that the edges cross, so t and u are only clamped to
+    // absorb rounding errors.
+    let t = ((a2 - a1).cross(v2) * sign_v1_cross_v2).max(0.0).min(abs_v1_cross_v2);
+    let u = (a2_a1_cross_v1 * sign_v1_cross_v2).max(0.0).min(abs_v1_cross_v2);
+
+    // Snap intersections to the edge if it is very close.
+    // This helps with preventing small floating points errors from
+    // accumulating when many edges intersect at the same position.
+    let threshold = 0.000_001;
+    if 1.0 - t / abs_v1_cross_v2 < threshold {
+        return Some(e1.lower);
+    }
+    if 1.0 - u / abs_v1_cross_v2 < threshold {
+        return Some(e2.lower);
+    }
 
-        let res = a1 + (v1 * t) / abs_v1_cross_v2;
+    let res = a1 + (v1 * t) / abs_v1_cross_v2;
 
-        let res = tess_point(res.x, res.y);
-        // It would be great if the assertion below held, but it happens
-        // to fail due to precision issues.
-        // debug_assert!(res.y <= e1.lower.y && res.y <= e2.lower.y);
-        if res != e1.upper && res != e2.upper
-            && res.y <= e1.lower.y && res.y <= e2.lower.y {
-            return Some(res);
-        }
+    let res = tess_point(res.x, res.y);
+    // It would be great if the assertion below held, but it happens
+    // to fail due to precision issues.
+    // debug_assert!(res.y <= e1.lower.y && res.y <= e2.lower.y);
+    if res != e1.upper && res != e2.upper
+        && res.y <= e1.lower.y && res.y <= e2.lower.y {
+        return Some(res);
     }
 
     None
@@ -28,9 +28,10 @@ use sid::{Id, IdVec};
 use crate::FillVertex as Vertex;
 use crate::{FillOptions, FillRule, Side, OnError, TessellationError, TessellationResult, InternalError};
 use crate::geom::math::*;
-use crate::geom::{QuadraticBezierSegment, CubicBezierSegment};
-use crate::geom::euclid::{self, Trig};
+use crate::geom::{QuadraticBezierSegment, CubicBezierSegment, ConicSegment};
+use crate::geom::euclid;
 use crate::math_utils::*;
+use crate::predicates::{orientation, orient2d};
 use crate::geometry_builder::{GeometryBuilder, GeometryBuilderError, Count, VertexId};
 use crate::path::PathEvent;
 use crate::path::builder::{Build, FlatPathBuilder};
@@ -59,13 +60,10 @@ macro_rules! tess_log {
 }
 
 struct ActiveEdgeTag;
-struct PendingEdgeTag;
 struct SpanTag;
 type ActiveEdgeId = Id<ActiveEdgeTag, usize>;
 type SpanId = Id<SpanTag, usize>;
-type PendingEdgeId = Id<PendingEdgeTag, usize>;
 type ActiveEdges = IdVec<ActiveEdgeId, ActiveEdge>;
-type EdgesBelow = IdVec<PendingEdgeId, PendingEdge>;
 
 #[cfg(feature="debugger")]
 pub mod dbg {
@@ -93,15 +91,6 @@ pub(crate) struct Edge {
     pub(crate) lower: TessPoint,
 }
 
-impl Edge {
-    pub fn new(mut a: TessPoint, mut b: TessPoint) -> Self {
-        if is_after(a, b) {
-            swap(&mut a, &mut b);
-        }
-        Edge { upper: a, lower: b }
-    }
-}
-
 #[derive(Copy, Clone, Debug)]
 struct OrientedEdge {
     upper: TessPoint,
@@ -127,18 +116,6 @@ impl OrientedEdge {
         OrientedEdge { upper: a, lower: b, winding }
     }
 
-    fn to_active_edge(&self, upper_id: VertexId) -> ActiveEdge {
-        ActiveEdge {
-            points: Edge {
-                upper: self.upper,
-                lower: self.lower,
-            },
-            upper_id,
-            winding: self.winding,
-            merge: false,
-        }
-    }
-
     fn edge(&self) -> Edge {
         Edge { upper: self.upper, lower: self.lower }
     }
@@ -148,7 +125,6 @@ impl OrientedEdge {
 struct PendingEdge {
     // The upper vertex is the current vertex, we don't need to store it.
     lower: TessPoint,
-    angle: f32,
     winding: i16,
 }
 
@@ -439,13 +415,11 @@ impl FillTessellator {
                         continue;
                     }
 
-                    let angle = edge_angle(edge.lower - edge.upper);
                     self.pending_edges.push(PendingEdge {
                         lower: edge.lower,
-                        angle,
                         winding: edge.winding,
                     });
-                    tess_log!(self, " edge at {:?} -> {:?} (angle={:?})", edge.upper, edge.lower, angle);
+                    tess_log!(self, " edge at {:?} -> {:?}", edge.upper, edge.lower);
 
                     pending_events = true;
                     continue;
@@ -479,7 +453,6 @@ impl FillTessellator {
                         self.pending_edges.push(
                             PendingEdge {
                                 lower: inter.lower,
-                                angle: edge_angle(inter.lower - self.current_position),
                                 winding: inter.winding,
                             }
                         );
@@ -628,7 +601,7 @@ impl FillTessellator {
         // Go through all pending edges, sort them and handle pairs of overlapping edges.
         // Doing this here avoids some potentially tricky cases with intersections
         // later.
-        prepare_pending_edges(&mut self.pending_edges, &mut self.intersections);
+        prepare_pending_edges(self.current_position, &mut self.pending_edges, &mut self.intersections);
 
         self.log_sl(first_edge_above);
         tess_log!(self, "{:?}", point_type);
@@ -900,7 +873,6 @@ impl FillTessellator {
                 // Split the edge.
                 self.pending_edges.push(PendingEdge {
                     lower: active_edge.points.lower,
-                    angle: edge_angle(active_edge.points.lower - self.current_position),
                     winding: active_edge.winding,
                 });
                 active_edge.points.lower = self.current_position;
@@ -1307,8 +1279,13 @@ impl FillTessellator {
         for b in &self.pending_edges {
             println!("   -- below: {:?}", b);
         }
+        self.log_sl_points();
     }
 
+    #[cfg(not(test))]
+    fn log_sl_winding(&self) {}
+
+    #[cfg(test)]
     fn log_sl_winding(&self) {
         if !self.log {
             return;
@@ -1325,9 +1302,6 @@ impl FillTessellator {
         println!("|");
     }
 
-    #[cfg(not(test))]
-    fn log_sl_points(&self) {}
-
     #[cfg(test)]
     fn log_sl_points(&self) {
         print!("\n sl: [");
@@ -1421,35 +1395,49 @@ fn compare_edge_against_position(
         return;
     }
 
-    // Intersect the edge with the horizontal line passing at the current position.
-    let dy = position.y - edge.upper.y;
-    let x = edge.upper.x + dy.mul_div(v.x, v.y);
+    // The orientation of the position relative to the edge is v.y times the horizontal
+    // distance between the edge and the position, and the length of the edge times their
+    // perpendicular distance. Its sign is exact, so the edge and the position are ordered
+    // consistently even when they are nearly touching.
+    let orient = orient2d(to_f64_point(edge.upper), to_f64_point(edge.lower), to_f64_point(position));
+    let (vx, vy) = (v.x.to_f64(), v.y.to_f64());
+    let horizontal_threshold = threshold.to_f64() * vy;
+    // Positions produced by rounding intersections can be slightly off the edges they are on.
+    // The horizontal distance alone doesn't account for this along nearly horizontal edges.
+    let perpendicular_threshold = FixedPoint32::epsilon().to_f64() * 2.0 * (vx * vx + vy * vy).sqrt();
 
-    //println!("dx = {} ({})", x - position.x, (x - position.x).raw());
-    *on_edge = (x - position.x).abs() <= threshold;
-    *edge_passed_point = !*on_edge && position.x < x;
+    *on_edge = orient.abs() <= horizontal_threshold.max(perpendicular_threshold);
+    *edge_passed_point = !*on_edge && orient > 0.0;
 }
 
 fn prepare_pending_edges(
+    current_position: TessPoint,
     pending_edges: &mut Vec<PendingEdge>,
     intersections: &mut Vec<OrientedEdge>,
 ) {
-    pending_edges.sort_by(|a, b| a.angle.partial_cmp(&b.angle).unwrap_or(Ordering::Equal));
+    // All pending edges start at the current position and point downward (or to the right
+    // if they are horizontal), so sorting them from left to right only requires the exact
+    // orientation of each pair.
+    let origin = to_f64_point(current_position);
+    pending_edges.sort_by(|a, b| {
+        orientation(origin, to_f64_point(a.lower), to_f64_point(b.lower)).cmp(&0)
+    });
 
     if pending_edges.len() >= 2 {
         let mut to_remove = Vec::new();
         let mut i = 0;
         while i + 1 < pending_edges.len() {
-            // This theshold may need to be adjusted if we run into more
-            // precision issues with how angles are computed.
-            let threshold = 0.0035;
             let edge_a = &pending_edges[i];
             let edge_b = &pending_edges[i+1];
             // TODO: Skipping edges loses track of the correct winding number,
             // hence the check that the winding isn't affected but it is far
             // from ideal.
             let doesnt_affect_winding = true; //edge_a.winding + edge_b.winding == 0;
-            if (edge_a.angle - edge_b.angle).abs() < threshold && doesnt_affect_winding {
+            let overlapping = nearly_same_direction(
+                to_f64_point(edge_a.lower) - origin,
+                to_f64_point(edge_b.lower) - origin,
+            );
+            if overlapping && doesnt_affect_winding {
                 to_remove.push(i);
                 if edge_a.lower != edge_b.lower {
                     let furthest = if is_after(edge_a.lower, edge_b.lower) { i } else { i + 1 };
@@ -1469,6 +1457,17 @@ fn prepare_pending_edges(
     }
 }
 
+// Whether two edges starting at the same position are close enough to be considered
+// overlapping.
+fn nearly_same_direction(a: euclid::default::Vector2D<f64>, b: euclid::default::Vector2D<f64>) -> bool {
+    // This theshold may need to be adjusted if we run into more
+    // precision issues.
+    let threshold = 0.0035;
+    // The sine of the angle between the two edges.
+    let sin = a.cross(b) / (a.length() * b.length());
+    sin.abs() < threshold && a.dot(b) > 0.0
+}
+
 #[derive(Copy, Clone, Debug)]
 struct ActiveEdge {
     points: Edge,
@@ -1508,12 +1507,7 @@ fn to_internal(v: Point) -> TessPoint { TessPoint::new(fixed(v.x), fixed(v.y)) }
 #[inline]
 fn to_f32_point(v: TessPoint) -> Point { point(v.x.to_f32(), v.y.to_f32()) }
 #[inline]
-fn to_f32_vector(v: TessVector) -> Vector { vector(v.x.to_f32(), v.y.to_f32()) }
-
-#[inline]
-fn edge_angle(v: TessVector) -> f32 {
-    -Trig::fast_atan2(v.y.to_f32(), v.x.to_f32())
-}
+fn to_f64_point(v: TessPoint) -> F64Point { F64Point::new(v.x.to_f64(), v.y.to_f64()) }
 
 /// A sequence of edges sorted from top to bottom, to be used as the tessellator's input.
 pub struct FillEvents {
@@ -1564,6 +1558,9 @@ impl FillEvents {
                 PathEvent::Cubic(segment) => {
                     builder.cubic_segment(segment);
                 }
+                PathEvent::Conic(segment) => {
+                    builder.conic_segment(segment);
+                }
                 PathEvent::Close(..) => {
                     builder.close();
                 }
@@ -1676,6 +1673,17 @@ impl EventsBuilder {
         self.nth = nth;
     }
 
+    fn conic_segment(&mut self, segment: ConicSegment<f32>) {
+        // Half of the tolerance is used to approximate the conic with quadratic bézier
+        // curves and the other half to flatten them.
+        let tolerance = self.tolerance;
+        self.tolerance = tolerance * 0.5;
+        segment.for_each_quadratic_bezier(self.tolerance, &mut |curve| {
+            self.quadratic_segment(*curve);
+        });
+        self.tolerance = tolerance;
+    }
+
     fn cubic_segment(&mut self, mut segment: CubicBezierSegment<f32>) {
         // This goes through the same gymnastics as quadratic_segment.
 
@@ -1,6 +1,6 @@
 use crate::math_utils::compute_normal;
 use crate::geom::math::*;
-use crate::geom::{QuadraticBezierSegment, CubicBezierSegment, LineSegment, Arc};
+use crate::geom::{QuadraticBezierSegment, CubicBezierSegment, ConicSegment, LineSegment, Arc};
 use crate::geom::utils::{normalized_tangent, directed_angle};
 use crate::geom::euclid::Trig;
 use crate::geometry_builder::{VertexId, GeometryBuilder, GeometryBuilderError};
@@ -11,7 +11,6 @@ use crate::StrokeVertex as Vertex;
 use crate::{Side, Order, LineCap, LineJoin, StrokeOptions, TessellationError, TessellationResult};
 
 use std::f32::consts::PI;
-const EPSILON: f32 = 1e-4;
 
 /// A Context object that can tessellate stroke operations for complex paths.
 ///
@@ -274,6 +273,23 @@ impl<'l> PathBuilder for StrokeBuilder<'l> {
         );
     }
 
+    fn conic_to(&mut self, ctrl: Point, to: Point, weight: f32) {
+        self.previous_command_was_move = false;
+        let mut first = true;
+        ConicSegment {
+            from: self.current,
+            ctrl,
+            to,
+            weight,
+        }.for_each_flattened(
+            self.options.tolerance,
+            &mut |point| {
+                self.edge_to(point, first);
+                first = false;
+            }
+        );
+    }
+
     fn arc(
         &mut self,
         center: Point,
@@ -0,0 +1,306 @@
+//! Adaptive-precision geometric predicates.
+//!
+//! Based on Jonathan Richard Shewchuk's "Adaptive Precision Floating-Point Arithmetic and
+//! Fast Robust Geometric Predicates". The result of the orientation test is computed with
+//! regular floating point arithmetic when it is accurate enough to determine the sign, and
+//! progressively refined using exact expansion arithmetic otherwise, so that the sign is
+//! always correct.
+//!
+//! The coordinates of the fill tessellator are fixed-point numbers which convert to `f64`
+//! without loss of precision, which makes these predicates exact for its purposes.
+
+use crate::geom::math::F64Point;
+
+// Half of the machine epsilon of f64.
+const EPSILON: f64 = 1.110_223_024_625_156_5e-16;
+// 2^ceil(53 / 2) + 1, used to split a f64 into two non-overlapping halves.
+const SPLITTER: f64 = 134_217_729.0;
+
+const RESULT_ERR_BOUND: f64 = (3.0 + 8.0 * EPSILON) * EPSILON;
+const CCW_ERR_BOUND_A: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const CCW_ERR_BOUND_B: f64 = (2.0 + 12.0 * EPSILON) * EPSILON;
+const CCW_ERR_BOUND_C: f64 = (9.0 + 64.0 * EPSILON) * EPSILON * EPSILON;
+
+/// Returns a positive value if `a`, `b` and `c` are in counter-clockwise order (in a
+/// y-up coordinate system), a negative value if they are in clockwise order and zero if
+/// they are collinear.
+///
+/// Only the sign of the result is guaranteed to be exact. Its magnitude approximates twice
+/// the signed area of the triangle.
+pub(crate) fn orient2d(a: F64Point, b: F64Point, c: F64Point) -> f64 {
+    let det_left = (a.x - c.x) * (b.y - c.y);
+    let det_right = (a.y - c.y) * (b.x - c.x);
+    let det = det_left - det_right;
+
+    let det_sum = if det_left > 0.0 {
+        if det_right <= 0.0 {
+            return det;
+        }
+        det_left + det_right
+    } else if det_left < 0.0 {
+        if det_right >= 0.0 {
+            return det;
+        }
+        -det_left - det_right
+    } else {
+        return det;
+    };
+
+    let err_bound = CCW_ERR_BOUND_A * det_sum;
+    if det >= err_bound || -det >= err_bound {
+        return det;
+    }
+
+    orient2d_adapt(a, b, c, det_sum)
+}
+
+/// The sign of `orient2d` as -1, 0 or 1.
+pub(crate) fn orientation(a: F64Point, b: F64Point, c: F64Point) -> i32 {
+    let o = orient2d(a, b, c);
+    if o > 0.0 {
+        1
+    } else if o < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+fn orient2d_adapt(a: F64Point, b: F64Point, c: F64Point, det_sum: f64) -> f64 {
+    let acx = a.x - c.x;
+    let bcx = b.x - c.x;
+    let acy = a.y - c.y;
+    let bcy = b.y - c.y;
+
+    let (det_left, det_left_tail) = two_product(acx, bcy);
+    let (det_right, det_right_tail) = two_product(acy, bcx);
+    let b_exp = two_two_diff(det_left, det_left_tail, det_right, det_right_tail);
+
+    let mut det = estimate(&b_exp);
+    let err_bound = CCW_ERR_BOUND_B * det_sum;
+    if det >= err_bound || -det >= err_bound {
+        return det;
+    }
+
+    let acx_tail = two_diff_tail(a.x, c.x, acx);
+    let bcx_tail = two_diff_tail(b.x, c.x, bcx);
+    let acy_tail = two_diff_tail(a.y, c.y, acy);
+    let bcy_tail = two_diff_tail(b.y, c.y, bcy);
+
+    if acx_tail == 0.0 && acy_tail == 0.0 && bcx_tail == 0.0 && bcy_tail == 0.0 {
+        return det;
+    }
+
+    let err_bound = CCW_ERR_BOUND_C * det_sum + RESULT_ERR_BOUND * det.abs();
+    det += (acx * bcy_tail + bcy * acx_tail) - (acy * bcx_tail + bcx * acy_tail);
+    if det >= err_bound || -det >= err_bound {
+        return det;
+    }
+
+    let (s1, s0) = two_product(acx_tail, bcy);
+    let (t1, t0) = two_product(acy_tail, bcx);
+    let u = two_two_diff(s1, s0, t1, t0);
+    let mut c1 = [0.0; 8];
+    let c1_len = fast_expansion_sum_zero_elim(&b_exp, &u, &mut c1);
+
+    let (s1, s0) = two_product(acx, bcy_tail);
+    let (t1, t0) = two_product(acy, bcx_tail);
+    let u = two_two_diff(s1, s0, t1, t0);
+    let mut c2 = [0.0; 12];
+    let c2_len = fast_expansion_sum_zero_elim(&c1[..c1_len], &u, &mut c2);
+
+    let (s1, s0) = two_product(acx_tail, bcy_tail);
+    let (t1, t0) = two_product(acy_tail, bcx_tail);
+    let u = two_two_diff(s1, s0, t1, t0);
+    let mut d = [0.0; 16];
+    let d_len = fast_expansion_sum_zero_elim(&c2[..c2_len], &u, &mut d);
+
+    d[d_len - 1]
+}
+
+// The exact sum of a and b as a non-overlapping expansion (x, y), assuming |a| >= |b|.
+#[inline]
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virt = x - a;
+    (x, b - b_virt)
+}
+
+#[inline]
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virt = x - a;
+    let a_virt = x - b_virt;
+    let b_round = b - b_virt;
+    let a_round = a - a_virt;
+    (x, a_round + b_round)
+}
+
+#[inline]
+fn two_diff_tail(a: f64, b: f64, x: f64) -> f64 {
+    let b_virt = a - x;
+    let a_virt = x + b_virt;
+    let b_round = b_virt - b;
+    let a_round = a - a_virt;
+    a_round + b_round
+}
+
+#[inline]
+fn two_diff(a: f64, b: f64) -> (f64, f64) {
+    let x = a - b;
+    (x, two_diff_tail(a, b, x))
+}
+
+#[inline]
+fn split(a: f64) -> (f64, f64) {
+    let c = SPLITTER * a;
+    let a_big = c - a;
+    let a_hi = c - a_big;
+    (a_hi, a - a_hi)
+}
+
+#[inline]
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    let (a_hi, a_lo) = split(a);
+    let (b_hi, b_lo) = split(b);
+    let err1 = x - a_hi * b_hi;
+    let err2 = err1 - a_lo * b_hi;
+    let err3 = err2 - a_hi * b_lo;
+    (x, a_lo * b_lo - err3)
+}
+
+// (a1 + a0) - b as a three-component expansion, most significant first.
+#[inline]
+fn two_one_diff(a1: f64, a0: f64, b: f64) -> (f64, f64, f64) {
+    let (i, x0) = two_diff(a0, b);
+    let (x2, x1) = two_sum(a1, i);
+    (x2, x1, x0)
+}
+
+// (a1 + a0) - (b1 + b0) as a four-component expansion, least significant first.
+#[inline]
+fn two_two_diff(a1: f64, a0: f64, b1: f64, b0: f64) -> [f64; 4] {
+    let (j, zero, x0) = two_one_diff(a1, a0, b0);
+    let (x3, x2, x1) = two_one_diff(j, zero, b1);
+    [x0, x1, x2, x3]
+}
+
+fn estimate(e: &[f64]) -> f64 {
+    e.iter().sum()
+}
+
+// Sums two expansions, eliminating zero components from the output.
+// Returns the number of components written to h.
+fn fast_expansion_sum_zero_elim(e: &[f64], f: &[f64], h: &mut [f64]) -> usize {
+    let get = |v: &[f64], i: usize| if i < v.len() { v[i] } else { 0.0 };
+
+    let mut e_idx = 0;
+    let mut f_idx = 0;
+    let mut e_now = e[0];
+    let mut f_now = f[0];
+
+    let mut q;
+    if (f_now > e_now) == (f_now > -e_now) {
+        q = e_now;
+        e_idx += 1;
+        e_now = get(e, e_idx);
+    } else {
+        q = f_now;
+        f_idx += 1;
+        f_now = get(f, f_idx);
+    }
+
+    let mut h_idx = 0;
+    let mut push = |h: &mut [f64], hh: f64| {
+        if hh != 0.0 {
+            h[h_idx] = hh;
+            h_idx += 1;
+        }
+    };
+
+    if e_idx < e.len() && f_idx < f.len() {
+        let (q_new, hh) = if (f_now > e_now) == (f_now > -e_now) {
+            let r = fast_two_sum(e_now, q);
+            e_idx += 1;
+            e_now = get(e, e_idx);
+            r
+        } else {
+            let r = fast_two_sum(f_now, q);
+            f_idx += 1;
+            f_now = get(f, f_idx);
+            r
+        };
+        q = q_new;
+        push(h, hh);
+
+        while e_idx < e.len() && f_idx < f.len() {
+            let (q_new, hh) = if (f_now > e_now) == (f_now > -e_now) {
+                let r = two_sum(q, e_now);
+                e_idx += 1;
+                e_now = get(e, e_idx);
+                r
+            } else {
+                let r = two_sum(q, f_now);
+                f_idx += 1;
+                f_now = get(f, f_idx);
+                r
+            };
+            q = q_new;
+            push(h, hh);
+        }
+    }
+
+    while e_idx < e.len() {
+        let (q_new, hh) = two_sum(q, e_now);
+        e_idx += 1;
+        e_now = get(e, e_idx);
+        q = q_new;
+        push(h, hh);
+    }
+
+    while f_idx < f.len() {
+        let (q_new, hh) = two_sum(q, f_now);
+        f_idx += 1;
+        f_now = get(f, f_idx);
+        q = q_new;
+        push(h, hh);
+    }
+
+    let mut len = h_idx;
+    if q != 0.0 || len == 0 {
+        h[len] = q;
+        len += 1;
+    }
+
+    len
+}
+
+#[test]
+fn orient2d_basic() {
+    let a = F64Point::new(0.0, 0.0);
+    let b = F64Point::new(1.0, 0.0);
+    assert!(orient2d(a, b, F64Point::new(0.5, 1.0)) > 0.0);
+    assert!(orient2d(a, b, F64Point::new(0.5, -1.0)) < 0.0);
+    assert_eq!(orient2d(a, b, F64Point::new(12.0, 0.0)), 0.0);
+}
+
+#[test]
+fn orient2d_nearly_collinear() {
+    // Points on the line y = x, offset by tiny amounts that the naive determinant
+    // can't resolve.
+    let a = F64Point::new(0.5, 0.5);
+    let b = F64Point::new(12.0, 12.0);
+    let c = F64Point::new(24.0, 24.0);
+    assert_eq!(orientation(a, b, c), 0);
+
+    let ulp = f64::EPSILON;
+    for i in 0..64 {
+        for j in 0..64 {
+            let p = F64Point::new(0.5 + ulp * i as f64 * 0.5, 0.5 + ulp * j as f64 * 0.5);
+            let expected = if i < j { 1 } else if i > j { -1 } else { 0 };
+            // Exact sign of (b - p) x (c - p) for points on a tiny grid around a.
+            assert_eq!(orientation(p, b, c), expected, "{} {}", i, j);
+        }
+    }
+}
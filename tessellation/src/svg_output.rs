@@ -0,0 +1,342 @@
+//! Export of tessellated meshes as SVG documents.
+//!
+//! This is intended for debugging: the triangles, vertices and optionally the vertex
+//! normals produced by a tessellator are written as an SVG document that can be
+//! inspected in a browser or compared with other outputs.
+//!
+//! # Examples
+//!
+//! ```
+//! # use lyon_tessellation::svg_output::{SvgGeometryBuilder, MeshStyle};
+//! # use lyon_tessellation::{FillTessellator, FillOptions};
+//! # use lyon_tessellation::path::Path;
+//! # use lyon_tessellation::math::point;
+//! # use lyon_tessellation::path::builder::*;
+//! # fn main() {
+//! let mut path = Path::builder();
+//! path.move_to(point(0.0, 0.0));
+//! path.line_to(point(10.0, 0.0));
+//! path.line_to(point(10.0, 10.0));
+//! path.line_to(point(0.0, 10.0));
+//! path.close();
+//! let path = path.build();
+//!
+//! let mut output = SvgGeometryBuilder::new(MeshStyle::default());
+//! FillTessellator::new().tessellate_path(path.iter(), &FillOptions::default(), &mut output).unwrap();
+//!
+//! let svg_document = output.to_svg();
+//! # assert!(svg_document.starts_with("<svg"));
+//! # }
+//! ```
+
+use crate::math::{Point, Vector};
+use crate::path::VertexId;
+use crate::geometry_builder::{Count, GeometryBuilder, GeometryBuilderError};
+use crate::{FillVertex, StrokeVertex};
+
+use std::fmt::Write;
+
+/// Styling parameters of the SVG output.
+///
+/// Colors are written as is in the SVG document. Sizes are expressed in pixels of
+/// the output image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshStyle {
+    /// Size of the largest dimension of the output image.
+    pub image_size: f32,
+    /// Optional background color.
+    pub background: Option<String>,
+    pub triangle_color: String,
+    pub edge_color: String,
+    pub edge_width: f32,
+    /// Radius of the circles representing the vertices. Zero disables them.
+    pub vertex_radius: f32,
+    pub vertex_color: String,
+    /// Length of the lines representing the vertex normals. Zero disables them.
+    pub normal_length: f32,
+    pub normal_color: String,
+    /// Whether to label each triangle with its index.
+    pub triangle_labels: bool,
+    /// Whether to label each vertex with its index.
+    pub vertex_labels: bool,
+    pub font_size: f32,
+    pub label_color: String,
+}
+
+impl Default for MeshStyle {
+    fn default() -> Self {
+        MeshStyle {
+            image_size: 800.0,
+            background: Some("white".to_string()),
+            triangle_color: "rgba(100, 150, 255, 0.3)".to_string(),
+            edge_color: "rgb(20, 50, 150)".to_string(),
+            edge_width: 1.0,
+            vertex_radius: 2.0,
+            vertex_color: "black".to_string(),
+            normal_length: 0.0,
+            normal_color: "red".to_string(),
+            triangle_labels: true,
+            vertex_labels: false,
+            font_size: 10.0,
+            label_color: "black".to_string(),
+        }
+    }
+}
+
+/// Writes a mesh as an SVG document.
+///
+/// `normals` must either be empty or contain one normal per vertex.
+pub fn mesh_to_svg(
+    positions: &[Point],
+    normals: &[Vector],
+    indices: &[u32],
+    style: &MeshStyle,
+) -> String {
+    assert!(normals.is_empty() || normals.len() == positions.len());
+
+    let (mut min, mut max) = match positions.first() {
+        Some(p) => (*p, *p),
+        None => (Point::zero(), Point::new(1.0, 1.0)),
+    };
+    for p in positions {
+        min = min.min(*p);
+        max = max.max(*p);
+    }
+
+    let extent = (max - min).x.max((max - min).y).max(1e-5);
+    // Number of user units per pixel of the output image.
+    let unit = extent / style.image_size;
+    let margin = (style.vertex_radius + style.normal_length + style.font_size) * unit;
+    let origin = min - Vector::new(margin, margin);
+    let size = max - min + Vector::new(margin, margin) * 2.0;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        (size.x / unit).ceil(), (size.y / unit).ceil(),
+        origin.x, origin.y, size.x, size.y,
+    ).unwrap();
+
+    if let Some(ref background) = style.background {
+        writeln!(
+            svg,
+            r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            origin.x, origin.y, size.x, size.y, background,
+        ).unwrap();
+    }
+
+    writeln!(
+        svg,
+        r#"  <g fill="{}" stroke="{}" stroke-width="{}" stroke-linejoin="round">"#,
+        style.triangle_color, style.edge_color, style.edge_width * unit,
+    ).unwrap();
+    for triangle in indices.chunks(3) {
+        let a = positions[triangle[0] as usize];
+        let b = positions[triangle[1] as usize];
+        let c = positions[triangle[2] as usize];
+        writeln!(
+            svg,
+            r#"    <path d="M {} {} L {} {} L {} {} Z"/>"#,
+            a.x, a.y, b.x, b.y, c.x, c.y,
+        ).unwrap();
+    }
+    writeln!(svg, "  </g>").unwrap();
+
+    if style.normal_length > 0.0 && !normals.is_empty() {
+        writeln!(
+            svg,
+            r#"  <g stroke="{}" stroke-width="{}">"#,
+            style.normal_color, style.edge_width * unit,
+        ).unwrap();
+        for (p, n) in positions.iter().zip(normals.iter()) {
+            let to = *p + *n * style.normal_length * unit;
+            writeln!(svg, r#"    <line x1="{}" y1="{}" x2="{}" y2="{}"/>"#, p.x, p.y, to.x, to.y).unwrap();
+        }
+        writeln!(svg, "  </g>").unwrap();
+    }
+
+    if style.vertex_radius > 0.0 {
+        writeln!(svg, r#"  <g fill="{}">"#, style.vertex_color).unwrap();
+        for p in positions {
+            writeln!(
+                svg,
+                r#"    <circle cx="{}" cy="{}" r="{}"/>"#,
+                p.x, p.y, style.vertex_radius * unit,
+            ).unwrap();
+        }
+        writeln!(svg, "  </g>").unwrap();
+    }
+
+    if style.triangle_labels || style.vertex_labels {
+        writeln!(
+            svg,
+            r#"  <g fill="{}" font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="middle">"#,
+            style.label_color, style.font_size * unit,
+        ).unwrap();
+        if style.triangle_labels {
+            for (i, triangle) in indices.chunks(3).enumerate() {
+                let center = (
+                    positions[triangle[0] as usize].to_vector()
+                    + positions[triangle[1] as usize].to_vector()
+                    + positions[triangle[2] as usize].to_vector()
+                ) / 3.0;
+                writeln!(svg, r#"    <text x="{}" y="{}">{}</text>"#, center.x, center.y, i).unwrap();
+            }
+        }
+        if style.vertex_labels {
+            let offset = (style.vertex_radius + style.font_size * 0.5) * unit;
+            for (i, p) in positions.iter().enumerate() {
+                writeln!(
+                    svg,
+                    r#"    <text x="{}" y="{}" fill-opacity="0.7">{}</text>"#,
+                    p.x + offset, p.y - offset, i,
+                ).unwrap();
+            }
+        }
+        writeln!(svg, "  </g>").unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// A `GeometryBuilder` that records the generated mesh in order to write it as an
+/// SVG document.
+pub struct SvgGeometryBuilder {
+    positions: Vec<Point>,
+    normals: Vec<Vector>,
+    indices: Vec<u32>,
+    vertex_offset: u32,
+    index_offset: u32,
+    style: MeshStyle,
+}
+
+impl SvgGeometryBuilder {
+    pub fn new(style: MeshStyle) -> Self {
+        SvgGeometryBuilder {
+            positions: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+            vertex_offset: 0,
+            index_offset: 0,
+            style,
+        }
+    }
+
+    pub fn style(&self) -> &MeshStyle { &self.style }
+
+    pub fn style_mut(&mut self) -> &mut MeshStyle { &mut self.style }
+
+    /// Writes the recorded geometry as an SVG document.
+    pub fn to_svg(&self) -> String {
+        mesh_to_svg(&self.positions, &self.normals, &self.indices, &self.style)
+    }
+
+    fn add_vertex_internal(&mut self, position: Point, normal: Vector) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.positions.len() as u32 - self.vertex_offset;
+        if id == VertexId::INVALID.0 {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        self.positions.push(position);
+        self.normals.push(normal);
+        Ok(VertexId(id))
+    }
+}
+
+impl GeometryBuilder<FillVertex> for SvgGeometryBuilder {
+    fn begin_geometry(&mut self) {
+        self.vertex_offset = self.positions.len() as u32;
+        self.index_offset = self.indices.len() as u32;
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        Count {
+            vertices: self.positions.len() as u32 - self.vertex_offset,
+            indices: self.indices.len() as u32 - self.index_offset,
+        }
+    }
+
+    fn add_vertex(&mut self, vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        self.add_vertex_internal(vertex.position, vertex.normal)
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.indices.push(a.0 + self.vertex_offset);
+        self.indices.push(b.0 + self.vertex_offset);
+        self.indices.push(c.0 + self.vertex_offset);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.positions.truncate(self.vertex_offset as usize);
+        self.normals.truncate(self.vertex_offset as usize);
+        self.indices.truncate(self.index_offset as usize);
+    }
+}
+
+impl GeometryBuilder<StrokeVertex> for SvgGeometryBuilder {
+    fn begin_geometry(&mut self) {
+        GeometryBuilder::<FillVertex>::begin_geometry(self);
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        GeometryBuilder::<FillVertex>::end_geometry(self)
+    }
+
+    fn add_vertex(&mut self, vertex: StrokeVertex) -> Result<VertexId, GeometryBuilderError> {
+        self.add_vertex_internal(vertex.position, vertex.normal)
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        GeometryBuilder::<FillVertex>::add_triangle(self, a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        GeometryBuilder::<FillVertex>::abort_geometry(self);
+    }
+}
+
+#[test]
+fn mesh_svg_output() {
+    use crate::math::{point, vector};
+
+    let positions = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)];
+    let normals = [vector(-1.0, -1.0), vector(1.0, -1.0), vector(1.0, 1.0), vector(-1.0, 1.0)];
+
+    let mut output = SvgGeometryBuilder::new(MeshStyle {
+        normal_length: 5.0,
+        vertex_labels: true,
+        ..MeshStyle::default()
+    });
+
+    // An aborted geometry doesn't appear in the output.
+    GeometryBuilder::<FillVertex>::begin_geometry(&mut output);
+    let id = GeometryBuilder::<FillVertex>::add_vertex(
+        &mut output,
+        FillVertex { position: point(100.0, 100.0), normal: vector(0.0, 0.0) },
+    ).unwrap();
+    GeometryBuilder::<FillVertex>::add_triangle(&mut output, id, id, id);
+    GeometryBuilder::<FillVertex>::abort_geometry(&mut output);
+
+    GeometryBuilder::<FillVertex>::begin_geometry(&mut output);
+    let ids: Vec<VertexId> = positions.iter().zip(normals.iter()).map(|(p, n)| {
+        GeometryBuilder::<FillVertex>::add_vertex(
+            &mut output,
+            FillVertex { position: *p, normal: *n },
+        ).unwrap()
+    }).collect();
+    GeometryBuilder::<FillVertex>::add_triangle(&mut output, ids[0], ids[1], ids[2]);
+    GeometryBuilder::<FillVertex>::add_triangle(&mut output, ids[0], ids[2], ids[3]);
+    let count = GeometryBuilder::<FillVertex>::end_geometry(&mut output);
+    assert_eq!(count, Count { vertices: 4, indices: 6 });
+
+    let svg = output.to_svg();
+    assert_eq!(svg, mesh_to_svg(&positions, &normals, &[0, 1, 2, 0, 2, 3], output.style()));
+    assert_eq!(svg.matches("<path").count(), 2);
+    assert_eq!(svg.matches("<circle").count(), 4);
+    assert_eq!(svg.matches("<line").count(), 4);
+    assert_eq!(svg.matches("<text").count(), 6);
+    assert!(svg.contains(r#"<path d="M 0 0 L 10 0 L 10 10 Z"/>"#));
+    assert!(svg.ends_with("</svg>\n"));
+}